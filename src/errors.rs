@@ -1,3 +1,4 @@
+use crate::i18n::{I18N, LangKey};
 use crate::state::{Event, Message};
 use std::env::VarError;
 use std::fmt::Debug;
@@ -14,6 +15,71 @@ pub enum Error {
     Critical(String),
     Any(String),
     InvalidInput(String),
+    /// Error reply of the server, `message` is the reply including its code.
+    Server {
+        kind: ServerErrorKind,
+        message: String,
+    },
+    /// A command writing data was refused by the client on a read-only connection.
+    ReadOnly(String),
+}
+
+/// Code at the start of an error reply, e.g. `WRONGTYPE` or `NOPERM`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerErrorKind {
+    Err,
+    WrongType,
+    NoAuth,
+    WrongPass,
+    NoPerm,
+    Moved,
+    Ask,
+    TryAgain,
+    CrossSlot,
+    ClusterDown,
+    ReadOnly,
+    MasterDown,
+    Loading,
+    Busy,
+    BusyKey,
+    NoScript,
+    Oom,
+    MisConf,
+    ExecAbort,
+    /// Any other code, e.g. of a module.
+    Other(String),
+}
+
+impl ServerErrorKind {
+    /// Parses the code of an error reply, replies without a code count as `ERR`.
+    pub fn parse(message: &str) -> Self {
+        let code = message.split_whitespace().next().unwrap_or_default();
+        if code.is_empty() || code.chars().any(|c| c.is_ascii_lowercase()) {
+            return ServerErrorKind::Err;
+        }
+        match code {
+            "ERR" => ServerErrorKind::Err,
+            "WRONGTYPE" => ServerErrorKind::WrongType,
+            "NOAUTH" => ServerErrorKind::NoAuth,
+            "WRONGPASS" => ServerErrorKind::WrongPass,
+            "NOPERM" => ServerErrorKind::NoPerm,
+            "MOVED" => ServerErrorKind::Moved,
+            "ASK" => ServerErrorKind::Ask,
+            "TRYAGAIN" => ServerErrorKind::TryAgain,
+            "CROSSSLOT" => ServerErrorKind::CrossSlot,
+            "CLUSTERDOWN" => ServerErrorKind::ClusterDown,
+            "READONLY" => ServerErrorKind::ReadOnly,
+            "MASTERDOWN" => ServerErrorKind::MasterDown,
+            "LOADING" => ServerErrorKind::Loading,
+            "BUSY" => ServerErrorKind::Busy,
+            "BUSYKEY" => ServerErrorKind::BusyKey,
+            "NOSCRIPT" => ServerErrorKind::NoScript,
+            "OOM" => ServerErrorKind::Oom,
+            "MISCONF" => ServerErrorKind::MisConf,
+            "EXECABORT" => ServerErrorKind::ExecAbort,
+            code => ServerErrorKind::Other(code.to_string()),
+        }
+    }
 }
 
 impl AsRef<Error> for Error {
@@ -70,6 +136,12 @@ impl From<std::string::FromUtf8Error> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Any(e.to_string())
+    }
+}
+
 impl From<ParseIntError> for Error {
     fn from(value: ParseIntError) -> Self {
         Error::Any(value.to_string())
@@ -128,8 +200,54 @@ impl Error {
             Error::Internal(_) => "Internal Error".into(),
             Error::SystemTime(_) => "System Time Error".into(),
             Error::InvalidInput(_) => "Invalid input".into(),
+            Error::Server { .. } => "Server error".into(),
+            Error::ReadOnly(_) => "Read-only connection".into(),
+        }
+    }
+
+    /// Error for an error reply of the server.
+    pub fn server(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Error::Server {
+            kind: ServerErrorKind::parse(&message),
+            message,
         }
     }
+
+    /// Whether the server could not be reached, unlike errors the server replied with.
+    pub fn is_connection_error(&self) -> bool {
+        matches!(self, Error::Io(_) | Error::Network(_))
+    }
+
+    /// Explains the likely cause of a server error, if there is more to say than the reply.
+    pub fn hint(&self, i18n: &I18N) -> Option<String> {
+        let kind = match self {
+            Error::Server { kind, .. } => kind,
+            Error::ReadOnly(_) => return Some(i18n.get(LangKey::ReadOnlyConnectionHint)),
+            _ => return None,
+        };
+        let key = match kind {
+            ServerErrorKind::WrongType => LangKey::WrongTypeHint,
+            ServerErrorKind::NoAuth => LangKey::NoAuthHint,
+            ServerErrorKind::WrongPass => LangKey::WrongPassHint,
+            ServerErrorKind::NoPerm => LangKey::NoPermHint,
+            ServerErrorKind::CrossSlot => LangKey::CrossSlotHint,
+            ServerErrorKind::ClusterDown | ServerErrorKind::TryAgain => LangKey::ClusterDownHint,
+            ServerErrorKind::ReadOnly => LangKey::ReadOnlyHint,
+            ServerErrorKind::MasterDown | ServerErrorKind::Loading => LangKey::LoadingHint,
+            ServerErrorKind::Busy => LangKey::BusyHint,
+            ServerErrorKind::BusyKey => LangKey::BusyKeyHint,
+            ServerErrorKind::NoScript => LangKey::NoScriptHint,
+            ServerErrorKind::Oom => LangKey::OomHint,
+            ServerErrorKind::MisConf => LangKey::MisConfHint,
+            ServerErrorKind::ExecAbort => LangKey::ExecAbortHint,
+            ServerErrorKind::Err
+            | ServerErrorKind::Moved
+            | ServerErrorKind::Ask
+            | ServerErrorKind::Other(_) => return None,
+        };
+        Some(i18n.get(key))
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -148,6 +266,13 @@ impl std::fmt::Display for Error {
             Error::InvalidInput(e) => {
                 write!(f, "{e}")
             }
+            Error::Server { message, .. } => write!(f, "{message}"),
+            Error::ReadOnly(command) => {
+                write!(
+                    f,
+                    "{command} writes data and is refused on a read-only connection"
+                )
+            }
         }
     }
 }
@@ -162,16 +287,21 @@ impl std::error::Error for Error {
             Error::Network(_) => None,
             Error::Critical(_) => None,
             Error::InvalidInput(_) => None,
+            Error::Server { .. } => None,
+            Error::ReadOnly(_) => None,
         }
     }
 }
 
 impl Error {
     pub fn show_error_dialog(self, sender: Arc<Sender<Message>>) {
+        // Server errors are kept, the dialog shows a hint for them.
+        let error = match self {
+            Error::Server { .. } | Error::ReadOnly(_) => self,
+            error => Error::from(error.to_string()),
+        };
         sender
-            .send(Message::Event(Arc::from(Event::ShowError(Error::from(
-                self.to_string(),
-            )))))
+            .send(Message::Event(Arc::from(Event::ShowError(error))))
             .unwrap_or_else(|e| {
                 Error::from(e).log_error();
             });
@@ -180,3 +310,52 @@ impl Error {
         eprintln!("Failed to send error message: {}", self);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_error() {
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        assert!(Error::Io(io).is_connection_error());
+        assert!(Error::Network("unreachable".into()).is_connection_error());
+        assert!(!Error::server("ERR unknown command").is_connection_error());
+        assert!(!Error::ReadOnly("SET".into()).is_connection_error());
+        assert!(!Error::InvalidInput("Unterminated \" quote".into()).is_connection_error());
+    }
+
+    #[test]
+    fn test_server_error_kind() {
+        let error =
+            Error::server("WRONGTYPE Operation against a key holding the wrong kind of value");
+        assert!(matches!(
+            error,
+            Error::Server {
+                kind: ServerErrorKind::WrongType,
+                ..
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "WRONGTYPE Operation against a key holding the wrong kind of value"
+        );
+        assert_eq!(
+            ServerErrorKind::parse("NOPERM User bob has no permissions to run the 'set' command"),
+            ServerErrorKind::NoPerm
+        );
+        assert_eq!(
+            ServerErrorKind::parse("ERR unknown command 'FOO'"),
+            ServerErrorKind::Err
+        );
+        assert_eq!(
+            ServerErrorKind::parse("BFERR item exists"),
+            ServerErrorKind::Other("BFERR".to_string())
+        );
+        assert_eq!(
+            ServerErrorKind::parse("invalid password"),
+            ServerErrorKind::Err
+        );
+        assert_eq!(ServerErrorKind::parse(""), ServerErrorKind::Err);
+    }
+}
@@ -186,6 +186,160 @@ pub enum LangKey {
     Add,
     LoadingKeyData,
     CommandHistory,
+    TlsHandshakeFailed,
+    ClientCertAndKeyRequired,
+    UseTls,
+    CaCertificate,
+    ClientCertificate,
+    ClientKey,
+    TlsServerName,
+    TlsInsecure,
+    UnixSocketsUnsupported,
+    UnixSocket,
+    SocketPath,
+    SocketPathRequiredField,
+    Reconnecting,
+    CompatibilityMode,
+    Protocol,
+    CompatibilityModeInfo(u8, u8, &'static str),
+    NotSupportedByServer,
+    ClusterSlotsUnavailable,
+    TooManyRedirects,
+    SentinelPrimaryUnknown,
+    NotAPrimary,
+    Sentinel,
+    Sentinels,
+    MasterName,
+    SentinelRequiredField,
+    ProxyFailed,
+    ProxyAuthFailed,
+    UseProxy,
+    ProxyHost,
+    ProxyUsername,
+    ProxyPassword,
+    SshConnectionFailed,
+    SshAuthFailed,
+    SshHostKeyUnknown,
+    SshHostKeyMismatch,
+    UseSshTunnel,
+    SshHost,
+    SshUsername,
+    SshPassword,
+    SshKeyFile,
+    KnownHostsFile,
+    SshAcceptNewHost,
+    WrongTypeHint,
+    NoAuthHint,
+    WrongPassHint,
+    NoPermHint,
+    CrossSlotHint,
+    ClusterDownHint,
+    ReadOnlyHint,
+    LoadingHint,
+    BusyHint,
+    BusyKeyHint,
+    NoScriptHint,
+    OomHint,
+    MisConfHint,
+    ExecAbortHint,
+    TransactionAborted,
+    KeyModifiedConcurrently,
+    Stop,
+    CommandStopped,
+    CommandTimeout,
+    CommandTimeoutHint,
+    PubSub,
+    Subscribe,
+    Unsubscribe,
+    Channel,
+    Pattern,
+    ShardChannel,
+    Subscriptions,
+    NoSubscriptions,
+    Publish,
+    Message,
+    Sharded,
+    PublishedToReceivers,
+    FilterMessages,
+    Clear,
+    Time,
+    ActiveChannels,
+    ActiveShardChannels,
+    Subscribers,
+    Refresh,
+    SubscriptionLost,
+    Resubscribe,
+    MessagesShown,
+    LiveUpdates,
+    LiveUpdatesHint,
+    LiveUpdatesStopped,
+    EnableKeyspaceNotifications,
+    EnableKeyspaceNotificationsText,
+    Enable,
+    KeyspaceEvents,
+    Start,
+    KeyspaceChannels,
+    KeyeventChannels,
+    Event,
+    Count,
+    Reset,
+    NotificationsDisabled,
+    NotifyFlagsHint,
+    EventsHiddenHint,
+    Monitor,
+    Pause,
+    Resume,
+    MonitorWarningTitle,
+    MonitorWarningText,
+    FilterRegex,
+    BufferSize,
+    Export,
+    ExportedEntries,
+    Client,
+    Database,
+    Command,
+    SkippedWhilePaused,
+    EntriesShown,
+    Vault,
+    EnableVault,
+    DisableVault,
+    LockVault,
+    UnlockVault,
+    MasterPassword,
+    ConfirmMasterPassword,
+    PasswordsDoNotMatch,
+    EnableVaultText,
+    DisableVaultText,
+    UnlockVaultText,
+    VaultLocked,
+    VaultUnlocked,
+    PlainTextPassword,
+    ProductionConnection,
+    TypeToConfirm,
+    Production,
+    Group,
+    Tags,
+    TagsHint,
+    Color,
+    DestructiveCommand,
+    Ungrouped,
+    ReadOnlyConnectionHint,
+    ReadOnly,
+    ReadOnlyEditingDisabled,
+    ReadOnlyConnectionText,
+    UnmigratedServers,
+    WrongMasterPassword,
+    UnlockVaultToSavePasswords,
+    VaultNotEnabled,
+    UnlockVaultFirst,
+    CachingStopped,
+    UnterminatedQuote,
+    MasterPasswordEmpty,
+    NoSecureRandom,
+    VaultFromNewerVersion,
+    VaultDamaged,
+    CannotEncryptCredentials,
+    CannotCreateVaultKey,
 }
 
 impl I18N {
@@ -378,6 +532,169 @@ impl I18N {
             LangKey::Add => self.get_lang("ADD"),
             LangKey::LoadingKeyData => self.get_lang("LOADING_KEY_DATA"),
             LangKey::CommandHistory => self.get_lang("COMMAND_HISTORY"),
+            LangKey::TlsHandshakeFailed => self.get_lang("TLS_HANDSHAKE_FAILED"),
+            LangKey::ClientCertAndKeyRequired => self.get_lang("CLIENT_CERT_AND_KEY_REQUIRED"),
+            LangKey::UseTls => self.get_lang("USE_TLS"),
+            LangKey::CaCertificate => self.get_lang("CA_CERTIFICATE"),
+            LangKey::ClientCertificate => self.get_lang("CLIENT_CERTIFICATE"),
+            LangKey::ClientKey => self.get_lang("CLIENT_KEY"),
+            LangKey::TlsServerName => self.get_lang("TLS_SERVER_NAME"),
+            LangKey::TlsInsecure => self.get_lang("TLS_INSECURE"),
+            LangKey::UnixSocketsUnsupported => self.get_lang("UNIX_SOCKETS_UNSUPPORTED"),
+            LangKey::UnixSocket => self.get_lang("UNIX_SOCKET"),
+            LangKey::SocketPath => self.get_lang("SOCKET_PATH"),
+            LangKey::SocketPathRequiredField => self.get_lang("SOCKET_PATH_REQUIRED_FIELD"),
+            LangKey::Reconnecting => self.get_lang("RECONNECTING"),
+            LangKey::CompatibilityMode => self.get_lang("COMPATIBILITY_MODE"),
+            LangKey::Protocol => self.get_lang("PROTOCOL"),
+            LangKey::CompatibilityModeInfo(major, minor, protocol) => {
+                let template = self.get_lang("COMPATIBILITY_MODE_INFO");
+                let mut params: HashMap<&str, &str> = HashMap::new();
+                let major = major.to_string();
+                let minor = minor.to_string();
+                params.insert("expected_version_major", &major);
+                params.insert("expected_version_minor", &minor);
+                params.insert("expected_protocols", protocol);
+                fill_template(&template, &params)
+            }
+            LangKey::NotSupportedByServer => self.get_lang("NOT_SUPPORTED_BY_SERVER"),
+            LangKey::ClusterSlotsUnavailable => self.get_lang("CLUSTER_SLOTS_UNAVAILABLE"),
+            LangKey::TooManyRedirects => self.get_lang("TOO_MANY_REDIRECTS"),
+            LangKey::SentinelPrimaryUnknown => self.get_lang("SENTINEL_PRIMARY_UNKNOWN"),
+            LangKey::NotAPrimary => self.get_lang("NOT_A_PRIMARY"),
+            LangKey::Sentinel => self.get_lang("SENTINEL"),
+            LangKey::Sentinels => self.get_lang("SENTINELS"),
+            LangKey::MasterName => self.get_lang("MASTER_NAME"),
+            LangKey::SentinelRequiredField => self.get_lang("SENTINEL_REQUIRED_FIELD"),
+            LangKey::ProxyFailed => self.get_lang("PROXY_FAILED"),
+            LangKey::ProxyAuthFailed => self.get_lang("PROXY_AUTH_FAILED"),
+            LangKey::UseProxy => self.get_lang("USE_PROXY"),
+            LangKey::ProxyHost => self.get_lang("PROXY_HOST"),
+            LangKey::ProxyUsername => self.get_lang("PROXY_USERNAME"),
+            LangKey::ProxyPassword => self.get_lang("PROXY_PASSWORD"),
+            LangKey::SshConnectionFailed => self.get_lang("SSH_CONNECTION_FAILED"),
+            LangKey::SshAuthFailed => self.get_lang("SSH_AUTH_FAILED"),
+            LangKey::SshHostKeyUnknown => self.get_lang("SSH_HOST_KEY_UNKNOWN"),
+            LangKey::SshHostKeyMismatch => self.get_lang("SSH_HOST_KEY_MISMATCH"),
+            LangKey::UseSshTunnel => self.get_lang("USE_SSH_TUNNEL"),
+            LangKey::SshHost => self.get_lang("SSH_HOST"),
+            LangKey::SshUsername => self.get_lang("SSH_USERNAME"),
+            LangKey::SshPassword => self.get_lang("SSH_PASSWORD"),
+            LangKey::SshKeyFile => self.get_lang("SSH_KEY_FILE"),
+            LangKey::KnownHostsFile => self.get_lang("KNOWN_HOSTS_FILE"),
+            LangKey::SshAcceptNewHost => self.get_lang("SSH_ACCEPT_NEW_HOST"),
+            LangKey::WrongTypeHint => self.get_lang("WRONG_TYPE_HINT"),
+            LangKey::NoAuthHint => self.get_lang("NO_AUTH_HINT"),
+            LangKey::WrongPassHint => self.get_lang("WRONG_PASS_HINT"),
+            LangKey::NoPermHint => self.get_lang("NO_PERM_HINT"),
+            LangKey::CrossSlotHint => self.get_lang("CROSS_SLOT_HINT"),
+            LangKey::ClusterDownHint => self.get_lang("CLUSTER_DOWN_HINT"),
+            LangKey::ReadOnlyHint => self.get_lang("READ_ONLY_HINT"),
+            LangKey::LoadingHint => self.get_lang("LOADING_HINT"),
+            LangKey::BusyHint => self.get_lang("BUSY_HINT"),
+            LangKey::BusyKeyHint => self.get_lang("BUSY_KEY_HINT"),
+            LangKey::NoScriptHint => self.get_lang("NO_SCRIPT_HINT"),
+            LangKey::OomHint => self.get_lang("OOM_HINT"),
+            LangKey::MisConfHint => self.get_lang("MIS_CONF_HINT"),
+            LangKey::ExecAbortHint => self.get_lang("EXEC_ABORT_HINT"),
+            LangKey::TransactionAborted => self.get_lang("TRANSACTION_ABORTED"),
+            LangKey::KeyModifiedConcurrently => self.get_lang("KEY_MODIFIED_CONCURRENTLY"),
+            LangKey::Stop => self.get_lang("STOP"),
+            LangKey::CommandStopped => self.get_lang("COMMAND_STOPPED"),
+            LangKey::CommandTimeout => self.get_lang("COMMAND_TIMEOUT"),
+            LangKey::CommandTimeoutHint => self.get_lang("COMMAND_TIMEOUT_HINT"),
+            LangKey::PubSub => self.get_lang("PUB_SUB"),
+            LangKey::Subscribe => self.get_lang("SUBSCRIBE"),
+            LangKey::Unsubscribe => self.get_lang("UNSUBSCRIBE"),
+            LangKey::Channel => self.get_lang("CHANNEL"),
+            LangKey::Pattern => self.get_lang("PATTERN"),
+            LangKey::ShardChannel => self.get_lang("SHARD_CHANNEL"),
+            LangKey::Subscriptions => self.get_lang("SUBSCRIPTIONS"),
+            LangKey::NoSubscriptions => self.get_lang("NO_SUBSCRIPTIONS"),
+            LangKey::Publish => self.get_lang("PUBLISH"),
+            LangKey::Message => self.get_lang("MESSAGE"),
+            LangKey::Sharded => self.get_lang("SHARDED"),
+            LangKey::PublishedToReceivers => self.get_lang("PUBLISHED_TO_RECEIVERS"),
+            LangKey::FilterMessages => self.get_lang("FILTER_MESSAGES"),
+            LangKey::Clear => self.get_lang("CLEAR"),
+            LangKey::Time => self.get_lang("TIME"),
+            LangKey::ActiveChannels => self.get_lang("ACTIVE_CHANNELS"),
+            LangKey::ActiveShardChannels => self.get_lang("ACTIVE_SHARD_CHANNELS"),
+            LangKey::Subscribers => self.get_lang("SUBSCRIBERS"),
+            LangKey::Refresh => self.get_lang("REFRESH"),
+            LangKey::SubscriptionLost => self.get_lang("SUBSCRIPTION_LOST"),
+            LangKey::Resubscribe => self.get_lang("RESUBSCRIBE"),
+            LangKey::MessagesShown => self.get_lang("MESSAGES_SHOWN"),
+            LangKey::LiveUpdates => self.get_lang("LIVE_UPDATES"),
+            LangKey::LiveUpdatesHint => self.get_lang("LIVE_UPDATES_HINT"),
+            LangKey::LiveUpdatesStopped => self.get_lang("LIVE_UPDATES_STOPPED"),
+            LangKey::EnableKeyspaceNotifications => self.get_lang("ENABLE_KEYSPACE_NOTIFICATIONS"),
+            LangKey::EnableKeyspaceNotificationsText => self.get_lang("ENABLE_KEYSPACE_NOTIFICATIONS_TEXT"),
+            LangKey::Enable => self.get_lang("ENABLE"),
+            LangKey::KeyspaceEvents => self.get_lang("KEYSPACE_EVENTS"),
+            LangKey::Start => self.get_lang("START"),
+            LangKey::KeyspaceChannels => self.get_lang("KEYSPACE_CHANNELS"),
+            LangKey::KeyeventChannels => self.get_lang("KEYEVENT_CHANNELS"),
+            LangKey::Event => self.get_lang("EVENT"),
+            LangKey::Count => self.get_lang("COUNT"),
+            LangKey::Reset => self.get_lang("RESET"),
+            LangKey::NotificationsDisabled => self.get_lang("NOTIFICATIONS_DISABLED"),
+            LangKey::NotifyFlagsHint => self.get_lang("NOTIFY_FLAGS_HINT"),
+            LangKey::EventsHiddenHint => self.get_lang("EVENTS_HIDDEN_HINT"),
+            LangKey::Monitor => self.get_lang("MONITOR"),
+            LangKey::Pause => self.get_lang("PAUSE"),
+            LangKey::Resume => self.get_lang("RESUME"),
+            LangKey::MonitorWarningTitle => self.get_lang("MONITOR_WARNING_TITLE"),
+            LangKey::MonitorWarningText => self.get_lang("MONITOR_WARNING_TEXT"),
+            LangKey::FilterRegex => self.get_lang("FILTER_REGEX"),
+            LangKey::BufferSize => self.get_lang("BUFFER_SIZE"),
+            LangKey::Export => self.get_lang("EXPORT"),
+            LangKey::ExportedEntries => self.get_lang("EXPORTED_ENTRIES"),
+            LangKey::Client => self.get_lang("CLIENT"),
+            LangKey::Database => self.get_lang("DATABASE"),
+            LangKey::Command => self.get_lang("COMMAND"),
+            LangKey::SkippedWhilePaused => self.get_lang("SKIPPED_WHILE_PAUSED"),
+            LangKey::EntriesShown => self.get_lang("ENTRIES_SHOWN"),
+            LangKey::Vault => self.get_lang("VAULT"),
+            LangKey::EnableVault => self.get_lang("ENABLE_VAULT"),
+            LangKey::DisableVault => self.get_lang("DISABLE_VAULT"),
+            LangKey::LockVault => self.get_lang("LOCK_VAULT"),
+            LangKey::UnlockVault => self.get_lang("UNLOCK_VAULT"),
+            LangKey::MasterPassword => self.get_lang("MASTER_PASSWORD"),
+            LangKey::ConfirmMasterPassword => self.get_lang("CONFIRM_MASTER_PASSWORD"),
+            LangKey::PasswordsDoNotMatch => self.get_lang("PASSWORDS_DO_NOT_MATCH"),
+            LangKey::EnableVaultText => self.get_lang("ENABLE_VAULT_TEXT"),
+            LangKey::DisableVaultText => self.get_lang("DISABLE_VAULT_TEXT"),
+            LangKey::UnlockVaultText => self.get_lang("UNLOCK_VAULT_TEXT"),
+            LangKey::VaultLocked => self.get_lang("VAULT_LOCKED"),
+            LangKey::VaultUnlocked => self.get_lang("VAULT_UNLOCKED"),
+            LangKey::PlainTextPassword => self.get_lang("PLAIN_TEXT_PASSWORD"),
+            LangKey::ProductionConnection => self.get_lang("PRODUCTION_CONNECTION"),
+            LangKey::TypeToConfirm => self.get_lang("TYPE_TO_CONFIRM"),
+            LangKey::Production => self.get_lang("PRODUCTION"),
+            LangKey::Group => self.get_lang("GROUP"),
+            LangKey::Tags => self.get_lang("TAGS"),
+            LangKey::TagsHint => self.get_lang("TAGS_HINT"),
+            LangKey::Color => self.get_lang("COLOR"),
+            LangKey::DestructiveCommand => self.get_lang("DESTRUCTIVE_COMMAND"),
+            LangKey::Ungrouped => self.get_lang("UNGROUPED"),
+            LangKey::ReadOnlyConnectionHint => self.get_lang("READ_ONLY_CONNECTION_HINT"),
+            LangKey::ReadOnly => self.get_lang("READ_ONLY"),
+            LangKey::ReadOnlyEditingDisabled => self.get_lang("READ_ONLY_EDITING_DISABLED"),
+            LangKey::ReadOnlyConnectionText => self.get_lang("READ_ONLY_CONNECTION_TEXT"),
+            LangKey::UnmigratedServers => self.get_lang("UNMIGRATED_SERVERS"),
+            LangKey::WrongMasterPassword => self.get_lang("WRONG_MASTER_PASSWORD"),
+            LangKey::UnlockVaultToSavePasswords => self.get_lang("UNLOCK_VAULT_TO_SAVE_PASSWORDS"),
+            LangKey::VaultNotEnabled => self.get_lang("VAULT_NOT_ENABLED"),
+            LangKey::UnlockVaultFirst => self.get_lang("UNLOCK_VAULT_FIRST"),
+            LangKey::CachingStopped => self.get_lang("CACHING_STOPPED"),
+            LangKey::UnterminatedQuote => self.get_lang("UNTERMINATED_QUOTE"),
+            LangKey::MasterPasswordEmpty => self.get_lang("MASTER_PASSWORD_EMPTY"),
+            LangKey::NoSecureRandom => self.get_lang("NO_SECURE_RANDOM"),
+            LangKey::VaultFromNewerVersion => self.get_lang("VAULT_FROM_NEWER_VERSION"),
+            LangKey::VaultDamaged => self.get_lang("VAULT_DAMAGED"),
+            LangKey::CannotEncryptCredentials => self.get_lang("CANNOT_ENCRYPT_CREDENTIALS"),
+            LangKey::CannotCreateVaultKey => self.get_lang("CANNOT_CREATE_VAULT_KEY"),
         }
     }
 
@@ -64,6 +64,10 @@ impl eframe::App for App {
                 ui.separator();
                 ui.add_space(8.0);
                 ui.label(&self.state.error.error_message);
+                if let Some(hint) = &self.state.error.hint {
+                    ui.add_space(4.0);
+                    ui.weak(hint);
+                }
                 ui.add_space(8.0);
                 ui.separator();
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
@@ -1,12 +1,15 @@
 use crate::errors::Error;
 use crate::i18n::{I18N, LangKey, Language};
 use crate::state::Message;
-use crate::state::message::{Event, MainWindow, RespCommand};
+use crate::state::message::{Event, Info, MainWindow, RespCommand};
 use crate::state::workbench_state::WorkbenchState;
 use crate::ui::components;
 use crate::ui::components::{ConnectionsWindow, UIComponents, UIPanels};
 use crate::ui::widgets::{ErrorModal, InfoModal, Modal, Popup, PopupType, SettingsPopup};
-use crate::utils::{AppSettings, CommandRegistry, ValkeyClient, get_commands_dir, random_string};
+use crate::utils::{
+    AppSettings, Cmd, CommandRegistry, ConnectionProfile, TransactionResult, ValkeyClient,
+    get_commands_dir, random_string,
+};
 use std::sync::mpsc::{Receiver, Sender, channel};
 use std::sync::{Arc, RwLock};
 use std::thread;
@@ -20,9 +23,13 @@ pub struct AppState {
     pub error: ErrorModal,
     pub show_about: bool,
     pub valkey_client: Option<Arc<ValkeyClient>>,
+    /// The saved profile of the connection, unset for quick connects.
+    pub connection_profile: Option<ConnectionProfile>,
     pub resizable: bool,
     pub workbench_state: WorkbenchState,
     pub command_registry: Arc<CommandRegistry>,
+    /// Set when a vault operation finished, taken by the connections window.
+    pub vault_result: Option<Result<(), String>>,
     i18n: Arc<I18N>,
     settings: Arc<AppSettings>,
     sender: Sender<Message>,
@@ -63,6 +70,7 @@ impl AppState {
             error: ErrorModal::default(),
             show_about: false,
             valkey_client: None,
+            connection_profile: None,
             i18n,
             settings,
             sender: tx,
@@ -70,6 +78,7 @@ impl AppState {
             resizable,
             workbench_state: Default::default(),
             command_registry: Arc::new(command_registry),
+            vault_result: None,
         }
     }
 
@@ -87,14 +96,45 @@ impl AppState {
                         self.info = InfoModal::default();
                     }
                     Event::ShowError(s) => {
-                        self.info.open = false;
-                        self.error = ErrorModal::from(s);
+                        // Connection failures while the connection is being restored are
+                        // covered by the indicator in the menu bar, other errors are shown.
+                        if !(s.is_connection_error()
+                            && self
+                                .valkey_client
+                                .as_ref()
+                                .is_some_and(|client| client.is_reconnecting()))
+                        {
+                            self.info.open = false;
+                            self.error = ErrorModal {
+                                hint: s.hint(&self.i18n),
+                                ..ErrorModal::from(s)
+                            };
+                        }
                     }
-                    Event::AddServer(alias, url) => {
-                        self.handle_server_operation(|settings| settings.add_server(alias, url));
+                    Event::AddServer(profile) => {
+                        let i18n = self.i18n.clone();
+                        self.handle_server_operation(|settings| {
+                            settings.add_server(profile.clone(), &i18n)
+                        });
+                    }
+                    Event::UpdateServer(alias, profile) => {
+                        let i18n = self.i18n.clone();
+                        self.handle_server_operation(|settings| {
+                            settings.update_server(alias, profile.clone(), &i18n)
+                        });
+                        if self
+                            .connection_profile
+                            .as_ref()
+                            .is_some_and(|current| &current.alias == alias)
+                        {
+                            self.connection_profile = Some(profile.clone());
+                        }
                     }
                     Event::DeleteServer(alias) => {
-                        self.handle_server_operation(|settings| settings.delete_server(alias));
+                        let i18n = self.i18n.clone();
+                        self.handle_server_operation(|settings| {
+                            settings.delete_server(alias, &i18n)
+                        });
                     }
                     Event::SetLanguage(language) => {
                         self.set_language(language);
@@ -102,6 +142,9 @@ impl AppState {
                     Event::SetConnection(vc) => {
                         self.set_vc_client(vc.clone());
                     }
+                    Event::VaultResult(result) => {
+                        self.vault_result = Some(result.clone());
+                    }
                 },
                 Message::ToggleSidebar => {
                     self.ui_panels.left_side_bar_open = !self.ui_panels.left_side_bar_open;
@@ -131,23 +174,77 @@ impl AppState {
                     if let Some(client) = &self.valkey_client {
                         let client = client.clone();
                         let sender = self.get_sender();
+                        let i18n = self.i18n();
+                        // Editors of the keys a transaction watches show their values again
+                        // afterwards, whether the changes were committed or not.
+                        let reloads: Vec<(Vec<u8>, Cmd)> = match &command {
+                            RespCommand::Transaction(transaction) => self
+                                .popups
+                                .iter()
+                                .filter_map(|popup| match &popup.popup_type {
+                                    PopupType::EditKey(edit_key)
+                                        if transaction
+                                            .watched()
+                                            .iter()
+                                            .any(|key| key.as_slice() == edit_key.key()) =>
+                                    {
+                                        Some((edit_key.key().to_vec(), edit_key.reload_command()))
+                                    }
+                                    _ => None,
+                                })
+                                .collect(),
+                            _ => Vec::new(),
+                        };
                         let handle = thread::spawn(move || {
-                            //TODO! Impl
-                            match &command {
+                            let res = match &command {
                                 RespCommand::Command(cmds) | RespCommand::CommandRefresh(cmds) => {
-                                    if cmds.len() == 1 {
-                                        //Safely unwrap
-                                        let res =
-                                            client.exec(cmds.first().unwrap()).unwrap_or_default();
+                                    if let [cmd] = cmds.as_slice() {
+                                        client.exec(cmd).map(|_| ())
                                     } else {
-                                        let res = client.exec_pipelined(cmds);
-                                    }
-                                    if matches!(command, RespCommand::CommandRefresh(_)) {
-                                        sender.send(Message::Refresh).unwrap_or_else(|e| {
-                                            eprintln!("Error sending refresh message: {e}");
+                                        client.exec_pipelined(cmds).and_then(|values| {
+                                            values.into_iter().try_for_each(|value| {
+                                                value.into_result().map(|_| ())
+                                            })
                                         })
                                     }
                                 }
+                                RespCommand::Transaction(transaction) => {
+                                    let res = match client.exec_transaction(transaction) {
+                                        Ok(TransactionResult::Committed(values)) => values
+                                            .into_iter()
+                                            .try_for_each(|value| value.into_result().map(|_| ())),
+                                        Ok(TransactionResult::Aborted) => sender
+                                            .send(Message::Event(Arc::new(Event::ShowInfo(Info {
+                                                title: i18n.get(LangKey::TransactionAborted),
+                                                message: i18n.get(LangKey::KeyModifiedConcurrently),
+                                                callback: Some(|| {}),
+                                            }))))
+                                            .map_err(Error::from),
+                                        Err(e) => Err(e),
+                                    };
+                                    for (key, cmd) in reloads {
+                                        match client.exec(&cmd) {
+                                            Ok(value) => sender
+                                                .send(Message::ReloadKey(
+                                                    key,
+                                                    value.into_bytes_vec(),
+                                                ))
+                                                .unwrap_or_else(|e| {
+                                                    eprintln!("Error sending reload message: {e}");
+                                                }),
+                                            Err(e) => e.show_error_dialog(sender.clone()),
+                                        }
+                                    }
+                                    res
+                                }
+                            };
+                            if let Err(e) = res {
+                                e.show_error_dialog(sender.clone());
+                            }
+                            if !matches!(command, RespCommand::Command(_)) {
+                                sender.send(Message::Refresh).unwrap_or_else(|e| {
+                                    eprintln!("Error sending refresh message: {e}");
+                                })
                             }
                         });
                         std::mem::forget(handle);
@@ -158,6 +255,15 @@ impl AppState {
                         ui_components.current_window.refresh(client);
                     }
                 }
+                Message::ReloadKey(key, values) => {
+                    for popup in &mut self.popups {
+                        if let PopupType::EditKey(edit_key) = &mut popup.popup_type
+                            && edit_key.key() == key.as_slice()
+                        {
+                            edit_key.reload(values.clone());
+                        }
+                    }
+                }
             }
         };
     }
@@ -182,9 +288,28 @@ impl AppState {
 
     pub fn set_vc_client(&mut self, client: Arc<ValkeyClient>) {
         self.ui_panels.current_window = Arc::new(RwLock::new(Some(MainWindow::Browser)));
+        self.connection_profile = client.alias().and_then(|alias| {
+            let servers = self.settings.get_servers().ok()?;
+            servers.into_iter().find(|server| server.alias == alias)
+        });
         self.valkey_client = Some(client);
     }
 
+    /// The alias to type before destructive actions, set while connected to production.
+    pub fn production_alias(&self) -> Option<&str> {
+        self.connection_profile
+            .as_ref()
+            .filter(|profile| profile.production)
+            .map(|profile| profile.alias.as_str())
+    }
+
+    /// Whether the client refuses commands writing data on the current connection.
+    pub fn is_read_only(&self) -> bool {
+        self.valkey_client
+            .as_ref()
+            .is_some_and(|client| client.is_read_only())
+    }
+
     pub fn get_settings(&self) -> Arc<AppSettings> {
         Arc::clone(&self.settings)
     }
@@ -198,6 +323,9 @@ impl AppState {
                 Arc::new(self.sender.clone()),
                 self.i18n.clone(),
             )),
+            MainWindow::PubSub => Box::from(components::PubSubWindow::default()),
+            MainWindow::KeyspaceEvents => Box::from(components::KeyspaceEventsWindow::default()),
+            MainWindow::Monitor => Box::from(components::MonitorWindow::default()),
             MainWindow::Documentation => Box::from(components::DocumentationWindow::default()),
         }
     }
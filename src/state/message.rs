@@ -1,7 +1,7 @@
 use crate::errors::Error;
 use crate::i18n::Language;
 use crate::ui::widgets::PopupType;
-use crate::utils::ValkeyClient;
+use crate::utils::{Cmd, ConnectionProfile, Transaction, ValkeyClient};
 use std::sync::Arc;
 
 pub enum Message {
@@ -13,11 +13,15 @@ pub enum Message {
     CloseModal(String),
     ExecRespCommand(RespCommand),
     Refresh,
+    /// Replaces the values shown by the editors of the key with the ones read again.
+    ReloadKey(Vec<u8>, Vec<Vec<u8>>),
 }
 
 pub enum RespCommand {
-    Command(Vec<String>),
-    CommandRefresh(Vec<String>),
+    Command(Vec<Cmd>),
+    CommandRefresh(Vec<Cmd>),
+    /// Changes applied atomically, the user is told if another client interfered.
+    Transaction(Transaction),
 }
 
 pub enum Event {
@@ -25,10 +29,14 @@ pub enum Event {
     ShowInfo(Info),
     CloseInfo(),
     ShowError(Error),
-    AddServer(String, String),
+    AddServer(ConnectionProfile),
+    /// Replaces the profile saved under the alias.
+    UpdateServer(String, ConnectionProfile),
     DeleteServer(String),
     SetLanguage(Language),
     SetConnection(Arc<ValkeyClient>),
+    /// Outcome of enabling, unlocking or disabling the vault, which runs on a worker thread.
+    VaultResult(Result<(), String>),
 }
 
 #[derive(Clone, Copy)]
@@ -37,6 +45,9 @@ pub enum MainWindow {
     Browser,
     Workbench,
     Insights,
+    PubSub,
+    KeyspaceEvents,
+    Monitor,
     Documentation,
 }
 
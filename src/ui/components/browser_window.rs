@@ -3,8 +3,12 @@ use crate::i18n::LangKey;
 use crate::state::Event::ShowError;
 use crate::state::{AppState, Message};
 use crate::ui::Component;
-use crate::ui::widgets::{EditKey, PopupType, shimmer_inline};
-use crate::utils::{KeyType, KeyTypeExtended, ValkeyClient, ValkeyUrl, format_size};
+use crate::ui::widgets::{AddKey, EditKey, PopupType, TypedConfirmation, shimmer_inline};
+use crate::utils::{
+    Cmd, Invalidation, KEYSPACE_EVENTS, KeyTracker, KeyType, KeyTypeExtended, KeyspaceEvent,
+    KeyspaceListener, ValkeyClient, ValkeyUrl, ValkeyValue, display_bytes, escape_bytes,
+    format_size, glob_match, is_binary, unescape_bytes,
+};
 use eframe::epaint::{Rect, Stroke};
 use egui::{
     Align, Button, Context, Id, Label, Layout, Modal, ScrollArea, Sense, StrokeKind, UiBuilder,
@@ -24,6 +28,31 @@ pub struct KeyMetadata {
     pub key_type: Option<KeyTypeExtended>,
     pub size: Option<u64>,
     pub ttl: Option<i64>, // -1 for no expiry, -2 for key doesn't exist, >= 0 for TTL in seconds
+    /// When the metadata was read, a TTL has counted down since.
+    pub fetched: Option<Instant>,
+}
+
+impl KeyMetadata {
+    /// The TTL counted down to now, as cached metadata may be older.
+    pub fn remaining_ttl(&self) -> Option<i64> {
+        let ttl = self.ttl?;
+        let elapsed = self
+            .fetched
+            .map_or(0, |fetched| fetched.elapsed().as_secs() as i64);
+        Some(if ttl >= 0 {
+            (ttl - elapsed).max(0)
+        } else {
+            ttl
+        })
+    }
+}
+
+/// Values of keys opened in the editor, kept while client tracking reports no change.
+#[derive(Default)]
+struct ValueCache {
+    values: HashMap<Vec<u8>, Vec<Vec<u8>>>,
+    /// Bumped by every invalidation, values read before are not stored.
+    generation: u64,
 }
 
 #[derive(Clone)]
@@ -32,7 +61,7 @@ enum WorkerTask {
         client: Arc<ValkeyClient>,
         cursor: Option<usize>,
         filter: String,
-        key_type: String,
+        key_type: KeyTypeExtended,
         force_run: bool,
     },
     KeyCount {
@@ -40,23 +69,32 @@ enum WorkerTask {
     },
     KeyMetadata {
         client: Arc<ValkeyClient>,
-        keys: Vec<String>,
+        keys: Vec<Vec<u8>>,
         delay_ms: u64,
     },
+    /// Checks or enables the keyspace notifications for live updates.
+    Notifications {
+        client: Arc<ValkeyClient>,
+        enable: bool,
+    },
 }
 
 #[derive(Clone)]
 enum WorkerResult {
     KeysResult {
         cursor: usize,
-        keys: Vec<String>,
+        keys: Vec<Vec<u8>>,
         force_run: bool,
     },
     KeyCountResult {
         count: usize,
     },
     KeyMetadataResult {
-        metadata: HashMap<String, KeyMetadata>,
+        metadata: HashMap<Vec<u8>, KeyMetadata>,
+    },
+    NotificationsResult {
+        enabled: Result<bool, String>,
+        enable: bool,
     },
     Error(String),
 }
@@ -118,36 +156,38 @@ impl WorkerThread {
                 key_type,
                 force_run,
             } => {
-                let command = format!(
-                    "SCAN {}{} COUNT {SCAN_COUNT} {}",
-                    cursor.unwrap_or(0),
-                    if filter.is_empty() {
-                        "".to_string()
-                    } else {
-                        format!(" MATCH {filter}")
-                    },
-                    key_type
-                );
+                let mut command = Cmd::new("SCAN")
+                    .arg(cursor.unwrap_or(0))
+                    .arg("COUNT")
+                    .arg(SCAN_COUNT);
+                if !filter.is_empty() {
+                    command = command.arg("MATCH").arg(filter);
+                }
+                if let Some(type_name) = key_type.type_name() {
+                    command = command.arg("TYPE").arg(type_name);
+                }
 
-                match client.exec(command.trim()) {
-                    Ok(mut res) => {
-                        let new_cursor = res.remove(0).parse::<usize>().unwrap_or(0);
+                match client.exec(&command) {
+                    Ok(ValkeyValue::Array(mut res)) if res.len() == 2 => {
+                        let keys = res.pop().map(|k| k.into_bytes_vec()).unwrap_or_default();
+                        let new_cursor = res
+                            .pop()
+                            .and_then(|c| c.as_int())
+                            .map(|c| c as usize)
+                            .unwrap_or(0);
                         WorkerResult::KeysResult {
                             cursor: new_cursor,
-                            keys: res,
+                            keys,
                             force_run,
                         }
                     }
+                    Ok(res) => WorkerResult::Error(format!("Failed to get keys: {res}")),
                     Err(e) => WorkerResult::Error(format!("Failed to get keys: {e:?}")),
                 }
             }
-            WorkerTask::KeyCount { client } => match client.exec("DBSIZE") {
+            WorkerTask::KeyCount { client } => match client.exec(&Cmd::new("DBSIZE")) {
                 Ok(res) => {
-                    let count = res
-                        .first()
-                        .unwrap_or(&String::from("0"))
-                        .parse::<usize>()
-                        .unwrap_or(0);
+                    let count = res.as_int().unwrap_or(0) as usize;
                     WorkerResult::KeyCountResult { count }
                 }
                 Err(e) => WorkerResult::Error(format!("Failed to get key count: {e:?}")),
@@ -169,46 +209,18 @@ impl WorkerThread {
 
                 let mut metadata = HashMap::new();
 
-                let type_commands: Vec<String> = keys
-                    .iter()
-                    .map(|key| {
-                        if key.contains(' ')
-                            || key.contains('"')
-                            || key.contains('\t')
-                            || key.contains('\n')
-                            || key.contains('\r')
-                        {
-                            let escaped_key = key.replace('"', "\\\"");
-                            format!("TYPE \"{escaped_key}\"")
-                        } else {
-                            format!("TYPE {key}")
-                        }
-                    })
-                    .collect();
-
-                let ttl_commands: Vec<String> = keys
-                    .iter()
-                    .map(|key| {
-                        if key.contains(' ')
-                            || key.contains('"')
-                            || key.contains('\t')
-                            || key.contains('\n')
-                            || key.contains('\r')
-                        {
-                            let escaped_key = key.replace('"', "\\\"");
-                            format!("TTL \"{escaped_key}\"")
-                        } else {
-                            format!("TTL {key}")
-                        }
-                    })
-                    .collect();
+                let type_commands: Vec<Cmd> =
+                    keys.iter().map(|key| Cmd::new("TYPE").arg(key)).collect();
+                let ttl_commands: Vec<Cmd> =
+                    keys.iter().map(|key| Cmd::new("TTL").arg(key)).collect();
 
                 let types_result = client.exec_pipelined(&type_commands);
                 let ttl_result = client.exec_pipelined(&ttl_commands);
 
                 match (types_result, ttl_result) {
                     (Ok(types), Ok(ttls)) => {
-                        let size_commands: Vec<String> = keys
+                        let types: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+                        let size_commands: Vec<Cmd> = keys
                             .iter()
                             .enumerate()
                             .filter_map(|(i, key)| {
@@ -217,27 +229,15 @@ impl WorkerThread {
                                     let key_type_extended = KeyTypeExtended::from(raw_key_type);
                                     let key_type: KeyType = key_type_extended.into();
 
-                                    let quoted_key = if key.contains(' ')
-                                        || key.contains('"')
-                                        || key.contains('\t')
-                                        || key.contains('\n')
-                                        || key.contains('\r')
-                                    {
-                                        let escaped_key = key.replace('"', "\\\"");
-                                        format!("\"{escaped_key}\"")
-                                    } else {
-                                        key.to_string()
-                                    };
-
                                     Some(match key_type {
                                         KeyType::Hash
                                         | KeyType::List
                                         | KeyType::Set
                                         | KeyType::SortedSet
                                         | KeyType::String => {
-                                            format!("MEMORY USAGE {quoted_key}")
+                                            Cmd::new("MEMORY").arg("USAGE").arg(key)
                                         }
-                                        KeyType::Bloom => format!("BF.INFO {quoted_key} SIZE"),
+                                        KeyType::Bloom => Cmd::new("BF.INFO").arg(key).arg("SIZE"),
                                     })
                                 } else {
                                     None
@@ -257,17 +257,16 @@ impl WorkerThread {
                                 .get(i)
                                 .cloned()
                                 .unwrap_or_else(|| "unknown".to_string());
-                            let ttl = ttls
-                                .get(i)
-                                .and_then(|t| t.parse::<i64>().ok())
-                                .unwrap_or(-2);
+                            let ttl = ttls.get(i).and_then(|t| t.as_int()).unwrap_or(-2);
 
                             let key_type_extended = KeyTypeExtended::from(&raw_key_type);
 
                             let size = if raw_key_type != "none" && raw_key_type != "unknown" {
                                 if let Some(ref sizes) = sizes_result {
-                                    let result =
-                                        sizes.get(size_idx).and_then(|s| s.parse::<u64>().ok());
+                                    let result = sizes
+                                        .get(size_idx)
+                                        .and_then(|s| s.as_int())
+                                        .map(|s| s as u64);
                                     size_idx += 1;
                                     result
                                 } else {
@@ -283,6 +282,7 @@ impl WorkerThread {
                                     key_type: Some(key_type_extended),
                                     size,
                                     ttl: Some(ttl),
+                                    fetched: Some(Instant::now()),
                                 },
                             );
                         }
@@ -294,6 +294,17 @@ impl WorkerThread {
 
                 WorkerResult::KeyMetadataResult { metadata }
             }
+            WorkerTask::Notifications { client, enable } => {
+                let enabled = if enable {
+                    KeyspaceListener::enable(&client, KEYSPACE_EVENTS).map(|_| true)
+                } else {
+                    KeyspaceListener::is_enabled(&client, KEYSPACE_EVENTS)
+                };
+                WorkerResult::NotificationsResult {
+                    enabled: enabled.map_err(|e| e.to_string()),
+                    enable,
+                }
+            }
         }
     }
 }
@@ -303,9 +314,9 @@ pub struct BrowserWindow {
     selected_key_type_idx: usize,
     key_filter: (String, String),
     cursor: Option<usize>,
-    keys: Vec<String>,
-    key_metadata: HashMap<String, KeyMetadata>,
-    pending_metadata_keys: HashSet<String>,
+    keys: Vec<Vec<u8>>,
+    key_metadata: HashMap<Vec<u8>, KeyMetadata>,
+    pending_metadata_keys: HashSet<Vec<u8>>,
     last_visible_row: Option<usize>,
     initial: bool,
     key_count: Option<usize>,
@@ -315,11 +326,26 @@ pub struct BrowserWindow {
     pending_count_request: bool,
     last_metadata_request: Option<Instant>,
     pending_metadata_range: Option<Range<usize>>,
-    key_to_delete: Option<String>,
-    key_to_rename: Option<(String, String)>,
-    ttl_to_set: Option<(String, String)>,
-    pending_key_edits: Vec<(String, KeyMetadata)>,
+    key_to_delete: Option<Vec<u8>>,
+    /// Set for deletes, renames and TTL changes on a production connection.
+    confirmation: Option<TypedConfirmation>,
+    key_to_rename: Option<(Vec<u8>, String)>,
+    ttl_to_set: Option<(Vec<u8>, String)>,
+    pending_key_edits: Vec<(Vec<u8>, KeyMetadata)>,
     loading_key_edit: bool,
+    /// Keyspace notifications updating the rows while live mode is on.
+    live: Option<KeyspaceListener>,
+    /// Set once the notifications are known to be enabled, the listener is started then.
+    start_live: bool,
+    live_error: Option<String>,
+    confirm_notifications: bool,
+    /// Reports changed keys, cached metadata and values are only reloaded for those.
+    tracker: Option<KeyTracker>,
+    /// Why tracking failed, it is tried again on refresh.
+    tracking_error: Option<String>,
+    /// Keys that changed while their metadata was being loaded.
+    stale_metadata_keys: HashSet<Vec<u8>>,
+    key_values: Arc<Mutex<ValueCache>>,
 }
 
 impl Component for BrowserWindow {
@@ -337,7 +363,35 @@ impl Component for BrowserWindow {
 
         if let Some(valkey_client) = &state.valkey_client {
             let server_info = ValkeyUrl::from(&*valkey_client.clone());
+
+            if std::mem::take(&mut self.start_live) {
+                self.start_live(valkey_client, ctx, server_info.db().unwrap_or(0));
+            }
+            if let Some(live) = &mut self.live {
+                let events = live.events();
+                if let Some(reason) = live.closed() {
+                    self.live_error = Some(reason.to_string());
+                    self.live = None;
+                }
+                self.apply_keyspace_events(valkey_client, events);
+            }
+            if self.tracker.is_none()
+                && self.tracking_error.is_none()
+                && valkey_client.server_info().supports_client_tracking()
+            {
+                self.start_tracking(valkey_client, ctx);
+            }
+            if let Some(tracker) = &mut self.tracker {
+                let invalidations = tracker.invalidations();
+                if let Some(reason) = tracker.closed() {
+                    self.tracking_error = Some(reason.to_string());
+                    self.tracker = None;
+                }
+                self.apply_invalidations(valkey_client, invalidations);
+            }
+
             let key_type_label = state.i18n().get(LangKey::KeyType);
+            let not_supported_label = state.i18n().get(LangKey::NotSupportedByServer);
 
             if !self.pending_key_edits.is_empty() {
                 let pending_edits = std::mem::take(&mut self.pending_key_edits);
@@ -382,7 +436,7 @@ impl Component for BrowserWindow {
                 let end = self.row_range.0.end.min(keys_len);
 
                 if start < end {
-                    let keys_needing_metadata: Vec<String> = self.keys[start..end]
+                    let keys_needing_metadata: Vec<Vec<u8>> = self.keys[start..end]
                         .iter()
                         .filter(|key| {
                             !self.key_metadata.contains_key(*key)
@@ -422,7 +476,7 @@ impl Component for BrowserWindow {
                 {
                     let range = pending_range.clone();
                     if range.start < keys_len && range.end <= keys_len {
-                        let keys_needing_metadata: Vec<String> = self.keys[range]
+                        let keys_needing_metadata: Vec<Vec<u8>> = self.keys[range]
                             .iter()
                             .filter(|key| {
                                 !self.key_metadata.contains_key(*key)
@@ -454,12 +508,39 @@ impl Component for BrowserWindow {
                         if ui.button("↻").clicked() {
                             self.refresh(valkey_client);
                         }
+                        let live = self.live.is_some();
+                        if ui
+                            .selectable_label(live, state.i18n().get(LangKey::LiveUpdates))
+                            .on_hover_text(state.i18n().get(LangKey::LiveUpdatesHint))
+                            .clicked()
+                        {
+                            self.live_error = None;
+                            if live {
+                                self.live = None;
+                            } else {
+                                self.request_notifications(valkey_client, false);
+                            }
+                        }
+                        if let Some(error) = &self.live_error {
+                            ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                                .on_hover_text(format!(
+                                    "{} {error}",
+                                    state.i18n().get(LangKey::LiveUpdatesStopped)
+                                ));
+                        }
+                        if let Some(error) = &self.tracking_error {
+                            ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                                .on_hover_text(format!(
+                                    "{} {error}",
+                                    state.i18n().get(LangKey::CachingStopped)
+                                ));
+                        }
                     },
                 );
                 ui.separator();
 
                 ui.with_layout(Layout::left_to_right(Align::TOP), |ui| {
-                    self.key_type_selector(ui, valkey_client, key_type_label)
+                    self.key_type_selector(ui, valkey_client, key_type_label, not_supported_label)
                         .unwrap_or_else(|e| {
                             e.show_error_dialog(state.get_sender());
                         });
@@ -469,12 +550,18 @@ impl Component for BrowserWindow {
                             .hint_text(state.i18n().get(LangKey::FilterByKeyNameOrPattern)),
                     );
                     if ui
-                        .add_sized([80.0, 0.0], Button::new(state.i18n().get(LangKey::Add)))
+                        .add_enabled_ui(!state.is_read_only(), |ui| {
+                            ui.add_sized([80.0, 0.0], Button::new(state.i18n().get(LangKey::Add)))
+                        })
+                        .inner
+                        .on_disabled_hover_text(state.i18n().get(LangKey::ReadOnlyEditingDisabled))
                         .clicked()
                     {
                         let sender = state.get_sender();
                         sender
-                            .send(Message::OpenPopup(PopupType::AddKey(Box::default())))
+                            .send(Message::OpenPopup(PopupType::AddKey(Box::new(
+                                AddKey::default().with_read_only(state.is_read_only()),
+                            ))))
                             .unwrap_or_else(|e| Error::from(e).show_error_dialog(sender.clone()));
                     };
                 });
@@ -548,20 +635,21 @@ impl Component for BrowserWindow {
                                                     format!(
                                                         "{} {}?",
                                                         state.i18n().get(LangKey::Rename),
-                                                        key_to_rename.0
+                                                        display_bytes(&key_to_rename.0)
                                                     )
                                                 } else if let Some(ttl_to_set) = &self.ttl_to_set {
                                                     format!(
                                                         "{} {}?",
                                                         state.i18n().get(LangKey::SetTtlFor),
-                                                        ttl_to_set.0
+                                                        display_bytes(&ttl_to_set.0)
                                                     )
                                                 } else if let Some(key_to_delete) =
                                                     &self.key_to_delete
                                                 {
                                                     format!(
-                                                        "{} {key_to_delete}?",
-                                                        state.i18n().get(LangKey::Delete)
+                                                        "{} {}?",
+                                                        state.i18n().get(LangKey::Delete),
+                                                        display_bytes(key_to_delete)
                                                     )
                                                 } else {
                                                     state.i18n().get(LangKey::DeleteKey)
@@ -609,6 +697,11 @@ impl Component for BrowserWindow {
                                 });
                             });
                         });
+                        if let Some(confirmation) = &mut self.confirmation {
+                            ui.add_space(8.0);
+                            confirmation.ui(ui, &state.i18n());
+                        }
+                        let confirmed = self.is_confirmed();
                         ui.add_space(8.0);
 
                         ui.horizontal(|ui| {
@@ -628,42 +721,27 @@ impl Component for BrowserWindow {
                             }
                             ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                                 if ui
-                                    .button(if self.key_to_rename.is_some() {
-                                        state.i18n().get(LangKey::Rename)
-                                    } else if self.ttl_to_set.is_some() {
-                                        state.i18n().get(LangKey::SetTtl)
-                                    } else {
-                                        state.i18n().get(LangKey::Yes)
-                                    })
+                                    .add_enabled(
+                                        confirmed,
+                                        Button::new(if self.key_to_rename.is_some() {
+                                            state.i18n().get(LangKey::Rename)
+                                        } else if self.ttl_to_set.is_some() {
+                                            state.i18n().get(LangKey::SetTtl)
+                                        } else {
+                                            state.i18n().get(LangKey::Yes)
+                                        }),
+                                    )
                                     .clicked()
                                 {
                                     let sender = state.get_sender();
                                     if let Some(client) = state.valkey_client.clone() {
-                                        let command = if let Some(key_to_rename) =
-                                            &self.key_to_rename
-                                        {
-                                            format!(
-                                                "RENAME {} {}",
-                                                key_to_rename.0, key_to_rename.1
-                                            )
-                                        } else if let Some(ttl_to_set) = &self.ttl_to_set {
-                                            let ttl_value =
-                                                ttl_to_set.1.parse::<i64>().unwrap_or(-1);
-                                            if ttl_value == -1 {
-                                                format!("PERSIST {}", ttl_to_set.0)
-                                            } else {
-                                                format!("EXPIRE {} {ttl_value}", ttl_to_set.0)
-                                            }
-                                        } else if let Some(key_to_delete) = &self.key_to_delete {
-                                            format!("DEL {key_to_delete}")
-                                        } else {
-                                            "PING".into()
-                                        };
+                                        let command =
+                                            self.key_change().unwrap_or_else(|| Cmd::new("PING"));
                                         let i18n = state.i18n();
                                         thread::spawn(move || {
                                             client.exec(&command).unwrap_or_else(|e| {
                                                 e.show_error_dialog(sender.clone());
-                                                vec![]
+                                                ValkeyValue::Null
                                             });
                                             sender.send(Message::Refresh).unwrap_or_else(|e| {
                                                 eprintln!(
@@ -705,14 +783,23 @@ impl Component for BrowserWindow {
                 self.request_keys(valkey_client, false);
             }
 
+            if self.confirm_notifications {
+                self.notifications_modal(ctx, state, valkey_client);
+            }
+
             ctx.request_repaint();
         }
         Ok(())
     }
     fn refresh(&mut self, valkey_client: &Arc<ValkeyClient>) {
         self.keys.clear();
-        self.key_metadata.clear();
+        // Tracked metadata stays valid, changed keys are reported by the server.
+        if self.tracker.is_none() {
+            self.key_metadata.clear();
+        }
         self.pending_metadata_keys.clear();
+        self.stale_metadata_keys.clear();
+        self.tracking_error = None;
         self.cursor = None;
 
         self.request_key_count(valkey_client);
@@ -723,7 +810,7 @@ impl Component for BrowserWindow {
         let end = self.row_range.0.end.min(keys_len);
 
         if start < end && !self.keys.is_empty() {
-            let visible_keys: Vec<String> = self.keys[start..end].to_vec();
+            let visible_keys: Vec<Vec<u8>> = self.keys[start..end].to_vec();
             if !visible_keys.is_empty() {
                 self.request_key_metadata(valkey_client, &visible_keys);
             }
@@ -745,23 +832,56 @@ impl BrowserWindow {
                 if force_run {
                     self.keys.clear();
                 }
+                // Live updates may have added keys the scan has not reached yet.
+                if self.live.is_some() {
+                    let known: HashSet<&Vec<u8>> = self.keys.iter().collect();
+                    keys.retain(|key| !known.contains(key));
+                }
                 self.keys.append(&mut keys);
             }
             WorkerResult::KeyCountResult { count } => {
                 self.key_count = Some(count);
                 self.pending_count_request = false;
             }
-            WorkerResult::KeyMetadataResult { metadata } => {
+            WorkerResult::KeyMetadataResult { mut metadata } => {
                 for key in metadata.keys() {
                     self.pending_metadata_keys.remove(key);
                 }
+                // The request may have read keys before they changed. Their metadata is
+                // dropped and loaded again for the visible rows.
+                let loaded = metadata.len();
+                metadata.retain(|key, _| !self.stale_metadata_keys.remove(key));
+                if metadata.len() != loaded {
+                    self.row_range.1 = Range::default();
+                }
+                // Keys added by live updates are only known to match the type filter now.
+                if self.live.is_some()
+                    && let Some(type_name) =
+                        KeyTypeExtended::vector()[self.selected_key_type_idx].type_name()
+                {
+                    self.keys.retain(|key| {
+                        metadata.get(key).is_none_or(|metadata| {
+                            metadata.key_type.and_then(|key_type| key_type.type_name())
+                                == Some(type_name)
+                        })
+                    });
+                }
                 self.key_metadata.extend(metadata);
             }
+            WorkerResult::NotificationsResult { enabled, enable } => match enabled {
+                Ok(true) => self.start_live = true,
+                Ok(false) => self.confirm_notifications = true,
+                // `CONFIG` is often restricted on hosted servers, whose notifications are
+                // configured elsewhere. Listening shows whether they are enabled.
+                Err(_) if !enable => self.start_live = true,
+                Err(error) => self.live_error = Some(error),
+            },
             WorkerResult::Error(error) => {
                 eprintln!("Worker error: {error}");
                 self.pending_key_request = false;
                 self.pending_count_request = false;
                 self.pending_metadata_keys.clear();
+                self.stale_metadata_keys.clear();
             }
         }
     }
@@ -792,9 +912,7 @@ impl BrowserWindow {
                 client: Arc::clone(valkey_client),
                 cursor: cursor_value,
                 filter: self.key_filter.0.clone(),
-                key_type: KeyTypeExtended::vector()[self.selected_key_type_idx]
-                    .to_resp_str()
-                    .to_string(),
+                key_type: KeyTypeExtended::vector()[self.selected_key_type_idx],
                 force_run,
             };
 
@@ -804,6 +922,155 @@ impl BrowserWindow {
         }
     }
 
+    fn request_notifications(&self, valkey_client: &Arc<ValkeyClient>, enable: bool) {
+        if let Some(worker) = &self.worker {
+            let _ = worker.send_task(WorkerTask::Notifications {
+                client: Arc::clone(valkey_client),
+                enable,
+            });
+        }
+    }
+
+    fn start_live(&mut self, valkey_client: &Arc<ValkeyClient>, ctx: &Context, db: u32) {
+        let ctx = ctx.clone();
+        match KeyspaceListener::new(valkey_client, &format!("__keyspace@{db}__:*"), move || {
+            ctx.request_repaint()
+        }) {
+            Ok(listener) => self.live = Some(listener),
+            Err(e) => self.live_error = Some(e.to_string()),
+        }
+    }
+
+    /// Adds new keys matching the filter, removes deleted ones and reloads the metadata of
+    /// changed keys. Metadata of keys out of sight is dropped and loaded once they are shown.
+    fn apply_keyspace_events(
+        &mut self,
+        valkey_client: &Arc<ValkeyClient>,
+        events: Vec<KeyspaceEvent>,
+    ) {
+        if events.is_empty() {
+            return;
+        }
+        let mut exists: HashMap<Vec<u8>, bool> = HashMap::new();
+        for event in events {
+            let removed = event.removes_key();
+            exists.insert(event.key, !removed);
+        }
+
+        let keys_len = self.keys.len();
+        self.keys.retain(|key| exists.get(key) != Some(&false));
+        let mut count_changed = self.keys.len() != keys_len;
+
+        let known: HashSet<Vec<u8>> = self.keys.iter().cloned().collect();
+        let visible = self.visible_keys();
+        let mut reload = Vec::new();
+        for (key, exists) in exists {
+            if !exists {
+                self.key_metadata.remove(&key);
+                self.pending_metadata_keys.remove(&key);
+            } else if !known.contains(&key) {
+                if self.key_filter.0.is_empty() || glob_match(self.key_filter.0.as_bytes(), &key) {
+                    self.keys.push(key);
+                    count_changed = true;
+                }
+            } else if visible.contains(&key) {
+                if !self.pending_metadata_keys.contains(&key) {
+                    reload.push(key);
+                }
+            } else {
+                self.key_metadata.remove(&key);
+            }
+        }
+
+        self.request_key_metadata(valkey_client, &reload);
+        if count_changed {
+            self.request_key_count(valkey_client);
+        }
+    }
+
+    fn visible_keys(&self) -> HashSet<Vec<u8>> {
+        let start = self.row_range.0.start.min(self.keys.len());
+        let end = self.row_range.0.end.min(self.keys.len()).max(start);
+        self.keys[start..end].iter().cloned().collect()
+    }
+
+    fn start_tracking(&mut self, valkey_client: &Arc<ValkeyClient>, ctx: &Context) {
+        let ctx = ctx.clone();
+        match KeyTracker::new(valkey_client, move || ctx.request_repaint()) {
+            Ok(tracker) => self.tracker = Some(tracker),
+            Err(e) => self.tracking_error = Some(e.to_string()),
+        }
+    }
+
+    /// Drops the cached data of changed keys. Metadata of visible rows is reloaded right
+    /// away, of other rows once they are shown again.
+    fn apply_invalidations(
+        &mut self,
+        valkey_client: &Arc<ValkeyClient>,
+        invalidations: Vec<Invalidation>,
+    ) {
+        if invalidations.is_empty() {
+            return;
+        }
+        let mut keys = Vec::new();
+        if let Ok(mut cache) = self.key_values.lock() {
+            cache.generation += 1;
+            for invalidation in invalidations {
+                match invalidation {
+                    Invalidation::Keys(changed) => {
+                        for key in &changed {
+                            cache.values.remove(key);
+                        }
+                        keys.extend(changed);
+                    }
+                    Invalidation::All => {
+                        cache.values.clear();
+                        keys.extend(self.key_metadata.keys().cloned());
+                        keys.extend(self.pending_metadata_keys.iter().cloned());
+                    }
+                }
+            }
+        }
+
+        let visible = self.visible_keys();
+        let mut reload = Vec::new();
+        for key in keys {
+            if self.pending_metadata_keys.contains(&key) {
+                self.stale_metadata_keys.insert(key);
+            } else if self.key_metadata.remove(&key).is_some() && visible.contains(&key) {
+                reload.push(key);
+            }
+        }
+        self.request_key_metadata(valkey_client, &reload);
+    }
+
+    fn notifications_modal(
+        &mut self,
+        ctx: &Context,
+        state: &AppState,
+        valkey_client: &Arc<ValkeyClient>,
+    ) {
+        let modal = Modal::new(Id::new("enable_notifications")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading(state.i18n().get(LangKey::EnableKeyspaceNotifications));
+            ui.add_space(8.0);
+            ui.label(state.i18n().get(LangKey::EnableKeyspaceNotificationsText));
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button(state.i18n().get(LangKey::Enable)).clicked() {
+                    self.request_notifications(valkey_client, true);
+                    self.confirm_notifications = false;
+                }
+                if ui.button(state.i18n().get(LangKey::Cancel)).clicked() {
+                    self.confirm_notifications = false;
+                }
+            });
+        });
+        if modal.should_close() {
+            self.confirm_notifications = false;
+        }
+    }
+
     fn request_key_count(&mut self, valkey_client: &Arc<ValkeyClient>) {
         if self.pending_count_request {
             return;
@@ -820,7 +1087,7 @@ impl BrowserWindow {
         }
     }
 
-    fn request_key_metadata(&mut self, valkey_client: &Arc<ValkeyClient>, keys: &[String]) {
+    fn request_key_metadata(&mut self, valkey_client: &Arc<ValkeyClient>, keys: &[Vec<u8>]) {
         if keys.is_empty() {
             return;
         }
@@ -845,26 +1112,34 @@ impl BrowserWindow {
         ui: &mut egui::Ui,
         valkey_client: &Arc<ValkeyClient>,
         key_type_label: String,
+        not_supported_label: String,
     ) -> Result<(), Error> {
+        let supported = valkey_client.server_info().supports_scan_type();
         ui.horizontal(|ui| {
             ui.label(key_type_label);
-            egui::ComboBox::new(ui.id().with("key_type"), "")
-                .width(100.0)
-                .selected_text(KeyTypeExtended::vector()[self.selected_key_type_idx].to_string())
-                .show_ui(ui, |ui| {
-                    for (i, key_type) in KeyTypeExtended::vector().iter().enumerate() {
-                        if ui
-                            .selectable_value(
-                                &mut self.selected_key_type_idx,
-                                i,
-                                key_type.to_string(),
-                            )
-                            .changed()
-                        {
-                            self.request_keys(valkey_client, true);
+            ui.add_enabled_ui(supported, |ui| {
+                egui::ComboBox::new(ui.id().with("key_type"), "")
+                    .width(100.0)
+                    .selected_text(
+                        KeyTypeExtended::vector()[self.selected_key_type_idx].to_string(),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (i, key_type) in KeyTypeExtended::vector().iter().enumerate() {
+                            if ui
+                                .selectable_value(
+                                    &mut self.selected_key_type_idx,
+                                    i,
+                                    key_type.to_string(),
+                                )
+                                .changed()
+                            {
+                                self.request_keys(valkey_client, true);
+                            }
                         }
-                    }
-                });
+                    });
+            })
+            .response
+            .on_disabled_hover_text(not_supported_label);
         });
         Ok(())
     }
@@ -894,7 +1169,7 @@ impl BrowserWindow {
         ui: &mut egui::Ui,
         row_range: Range<usize>,
         row_height: f32,
-    ) -> Result<Vec<(String, KeyMetadata)>, Error> {
+    ) -> Result<Vec<(Vec<u8>, KeyMetadata)>, Error> {
         let keys = &self.keys;
         let max_w = ui.available_width();
         let start = row_range.start as f32;
@@ -932,12 +1207,13 @@ impl BrowserWindow {
             if resp.clicked() {
                 key_edit_requests.push((key_name.clone(), metadata.clone()));
             }
+            let read_only = state.is_read_only();
             resp.context_menu(|ui| {
                 if ui
                     .add(Button::new(state.i18n().get(LangKey::Copy)))
                     .clicked()
                 {
-                    ui.ctx().copy_text(key_name.clone());
+                    ui.ctx().copy_text(display_bytes(&key_name));
                     ui.close();
                 }
                 ui.separator();
@@ -949,17 +1225,27 @@ impl BrowserWindow {
                     ui.close();
                 }
                 if ui
-                    .add(Button::new(state.i18n().get(LangKey::Rename)))
+                    .add_enabled(!read_only, Button::new(state.i18n().get(LangKey::Rename)))
+                    .on_disabled_hover_text(state.i18n().get(LangKey::ReadOnlyEditingDisabled))
                     .clicked()
                 {
-                    self.key_to_rename = Some((key_name.clone(), key_name.clone()));
+                    self.key_to_rename = Some((
+                        key_name.clone(),
+                        if is_binary(&key_name) {
+                            escape_bytes(&key_name)
+                        } else {
+                            display_bytes(&key_name)
+                        },
+                    ));
+                    self.confirmation = state.production_alias().map(TypedConfirmation::new);
                     ui.close();
                 }
                 if ui
-                    .add(Button::new(state.i18n().get(LangKey::SetTtl)))
+                    .add_enabled(!read_only, Button::new(state.i18n().get(LangKey::SetTtl)))
+                    .on_disabled_hover_text(state.i18n().get(LangKey::ReadOnlyEditingDisabled))
                     .clicked()
                 {
-                    let ttl = metadata.ttl.unwrap_or(-1);
+                    let ttl = metadata.remaining_ttl().unwrap_or(-1);
                     self.ttl_to_set = Some((
                         key_name.clone(),
                         if ttl < 0 {
@@ -968,13 +1254,16 @@ impl BrowserWindow {
                             ttl.to_string()
                         },
                     ));
+                    self.confirmation = state.production_alias().map(TypedConfirmation::new);
                     ui.close();
                 }
                 if ui
-                    .add(Button::new(state.i18n().get(LangKey::Delete)))
+                    .add_enabled(!read_only, Button::new(state.i18n().get(LangKey::Delete)))
+                    .on_disabled_hover_text(state.i18n().get(LangKey::ReadOnlyEditingDisabled))
                     .clicked()
                 {
                     self.key_to_delete = Some(key_name);
+                    self.confirmation = state.production_alias().map(TypedConfirmation::new);
                     ui.close();
                 }
             });
@@ -1013,7 +1302,7 @@ impl BrowserWindow {
                         shimmer_inline(ui, 60.0);
                     }
 
-                    if let Some(ttl) = metadata.ttl {
+                    if let Some(ttl) = metadata.remaining_ttl() {
                         let ttl_text = self.format_ttl(Some(ttl));
                         ui.add_sized([80.0, row_height], Label::new(ttl_text).selectable(false));
                     } else {
@@ -1024,7 +1313,11 @@ impl BrowserWindow {
                         [ui.available_width() - 10.0, row_height].into(),
                         Layout::left_to_right(Align::Center),
                         |ui| {
-                            ui.add(Label::new(&keys[idx]).selectable(false).truncate());
+                            ui.add(
+                                Label::new(display_bytes(&keys[idx]))
+                                    .selectable(false)
+                                    .truncate(),
+                            );
                         },
                     );
                 });
@@ -1036,7 +1329,37 @@ impl BrowserWindow {
         Ok(key_edit_requests)
     }
 
-    fn edit_key(&mut self, state: &AppState, metadata: &KeyMetadata, key_name: &str) {
+    /// On a production connection the change of the dialog only runs once the alias is typed.
+    fn is_confirmed(&self) -> bool {
+        self.confirmation
+            .as_ref()
+            .is_none_or(TypedConfirmation::is_confirmed)
+    }
+
+    /// Command run when the rename, TTL or delete dialog is accepted.
+    fn key_change(&self) -> Option<Cmd> {
+        if let Some(key_to_rename) = &self.key_to_rename {
+            let new_name = if is_binary(&key_to_rename.0) {
+                unescape_bytes(&key_to_rename.1)
+            } else {
+                key_to_rename.1.as_bytes().to_vec()
+            };
+            Some(Cmd::new("RENAME").arg(&key_to_rename.0).arg(new_name))
+        } else if let Some(ttl_to_set) = &self.ttl_to_set {
+            let ttl_value = ttl_to_set.1.parse::<i64>().unwrap_or(-1);
+            if ttl_value == -1 {
+                Some(Cmd::new("PERSIST").arg(&ttl_to_set.0))
+            } else {
+                Some(Cmd::new("EXPIRE").arg(&ttl_to_set.0).arg(ttl_value))
+            }
+        } else {
+            self.key_to_delete
+                .as_ref()
+                .map(|key_to_delete| Cmd::new("DEL").arg(key_to_delete))
+        }
+    }
+
+    fn edit_key(&mut self, state: &AppState, metadata: &KeyMetadata, key_name: &[u8]) {
         let client = state.valkey_client.clone();
         let sender = state.get_sender();
         let key_name = key_name.to_owned();
@@ -1044,30 +1367,12 @@ impl BrowserWindow {
         if let Some(client) = client {
             if let Some(key_type_extended) = metadata.key_type {
                 let i18n = state.i18n();
+                let production_alias = state.production_alias().map(str::to_string);
+                let cache = self.tracker.is_some().then(|| Arc::clone(&self.key_values));
                 thread::spawn(move || {
-                    let quoted_key = if key_name.contains(' ')
-                        || key_name.contains('"')
-                        || key_name.contains('\t')
-                        || key_name.contains('\n')
-                        || key_name.contains('\r')
-                    {
-                        let escaped_key = key_name.replace('"', "\\\"");
-                        format!("\"{escaped_key}\"")
-                    } else {
-                        key_name.clone()
-                    };
-
+                    let key = &key_name;
                     let (command, key_type) = match key_type_extended {
-                        KeyTypeExtended::KeyType(kt) => match kt {
-                            KeyType::Hash => (format!("HGETALL {quoted_key}"), kt),
-                            KeyType::List => (format!("LRANGE {quoted_key} 0 499"), kt),
-                            KeyType::Set => (format!("SMEMBERS {quoted_key}",), kt),
-                            KeyType::SortedSet => {
-                                (format!("ZRANGE {quoted_key} 0 499 WITHSCORES"), kt)
-                            }
-                            KeyType::String => (format!("GET {quoted_key}"), kt),
-                            KeyType::Bloom => (format!("BF.INFO {quoted_key}"), kt),
-                        },
+                        KeyTypeExtended::KeyType(kt) => (EditKey::read_command(key, kt), kt),
                         _ => {
                             sender
                                 .send(Message::Event(Arc::new(ShowError(Error::from(
@@ -1080,20 +1385,36 @@ impl BrowserWindow {
                         }
                     };
 
-                    match client.exec(&command) {
-                        Ok(res) => {
-                            sender
-                                .send(Message::OpenPopup(PopupType::EditKey(Box::new(
-                                    EditKey::new(key_name, key_type, res, i18n),
-                                ))))
-                                .unwrap_or_else(|e| {
-                                    Error::from(e).show_error_dialog(sender.clone())
-                                });
-                        }
-                        Err(e) => {
-                            e.show_error_dialog(sender.clone());
-                        }
-                    }
+                    // A change reported after the generation is read makes the fetched
+                    // value stale, it is not cached then.
+                    let (generation, cached) = cache
+                        .as_ref()
+                        .and_then(|cache| cache.lock().ok())
+                        .map(|cache| (cache.generation, cache.values.get(key).cloned()))
+                        .unwrap_or_default();
+                    let values = match cached {
+                        Some(values) => values,
+                        None => match client.exec(&command) {
+                            Ok(res) => {
+                                let values = res.into_bytes_vec();
+                                if let Some(cache) = &cache
+                                    && let Ok(mut cache) = cache.lock()
+                                    && cache.generation == generation
+                                {
+                                    cache.values.insert(key_name.clone(), values.clone());
+                                }
+                                values
+                            }
+                            Err(e) => return e.show_error_dialog(sender.clone()),
+                        },
+                    };
+                    sender
+                        .send(Message::OpenPopup(PopupType::EditKey(Box::new(
+                            EditKey::from_bytes(key_name.clone(), key_type, values, i18n)
+                                .with_read_only(client.is_read_only())
+                                .with_production_alias(production_alias),
+                        ))))
+                        .unwrap_or_else(|e| Error::from(e).show_error_dialog(sender.clone()));
                 });
             } else {
                 self.pending_key_edits
@@ -1107,3 +1428,44 @@ impl BrowserWindow {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_changes_need_confirmation_on_production() {
+        let key = b"key".to_vec();
+        let changes = [
+            BrowserWindow {
+                key_to_rename: Some((key.clone(), "renamed".to_string())),
+                ..Default::default()
+            },
+            BrowserWindow {
+                ttl_to_set: Some((key.clone(), String::new())),
+                ..Default::default()
+            },
+            BrowserWindow {
+                ttl_to_set: Some((key.clone(), "60".to_string())),
+                ..Default::default()
+            },
+            BrowserWindow {
+                key_to_delete: Some(key),
+                ..Default::default()
+            },
+        ];
+        for (mut browser, name) in changes
+            .into_iter()
+            .zip(["RENAME", "PERSIST", "EXPIRE", "DEL"])
+        {
+            assert_eq!(
+                browser.key_change().map(|cmd| cmd.name()),
+                Some(name.into())
+            );
+            assert!(browser.is_confirmed());
+
+            browser.confirmation = Some(TypedConfirmation::new("prod"));
+            assert!(!browser.is_confirmed(), "{name} runs without confirmation");
+        }
+    }
+}
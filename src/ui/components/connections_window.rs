@@ -1,14 +1,17 @@
 use crate::errors::Error;
 use crate::i18n::LangKey;
-use crate::state::Event::{SetConnection, SetMainWindow};
+use crate::state::Event::{SetConnection, SetMainWindow, VaultResult};
 use crate::state::Message::Event;
 use crate::state::{AppState, MainWindow, Message};
 use crate::ui::Component;
 use crate::ui::widgets::{AddConnectionPopup, PopupType};
-use crate::utils::{ValkeyClient, ValkeyUrl};
-use egui::{Align, Button, Context, Direction, Id, Label, Layout, Modal, TextWrapMode, Ui};
+use crate::utils::{ConnectionProfile, ValkeyClient, ValkeyUrl, VaultState};
+use egui::{
+    Align, Button, Color32, Context, Direction, Id, Label, Layout, Modal, RichText, TextWrapMode,
+    Ui,
+};
 use egui_extras::{Column, TableBuilder};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Arc, RwLock};
 use std::thread;
 
@@ -17,7 +20,29 @@ pub struct ConnectionsWindow {
     connection_string: String,
     filter: String,
     sorted_by: RwLock<SortedBy>,
+    /// Groups whose connections are hidden, the ungrouped ones are stored as "".
+    collapsed_groups: HashSet<String>,
     pending_delete: Option<String>,
+    vault_dialog: Option<VaultDialog>,
+    master_password: String,
+    master_password_confirm: String,
+    vault_error: Option<String>,
+    /// The key of the vault is being derived, the dialog waits for the result.
+    vault_busy: bool,
+}
+
+enum VaultDialog {
+    Enable,
+    /// Unlocks the vault, then continues with what needed the passwords.
+    Unlock(Option<PendingAction>),
+    Disable,
+}
+
+enum PendingAction {
+    Connect(String),
+    Edit(String),
+    Delete(String),
+    Add,
 }
 #[derive(Debug)]
 enum SortedBy {
@@ -56,6 +81,46 @@ impl SortedBy {
     fn toggle_last_connect(&mut self) {
         toggle_variant!(self, LastConnect);
     }
+
+    /// Sorts the profiles with their shown address by the selected column.
+    fn sort(&self, rows: &mut [(ConnectionProfile, String)]) {
+        let reversed = match *self {
+            SortedBy::Name(reversed) => {
+                rows.sort_by_key(|(server, _)| server.alias.to_lowercase());
+                reversed
+            }
+            SortedBy::HostPort(reversed) => {
+                rows.sort_by(|(_, a), (_, b)| a.cmp(b));
+                reversed
+            }
+            SortedBy::ConnectionType(reversed) => {
+                rows.sort_by(|(a, _), (b, _)| a.server_type.cmp(&b.server_type));
+                reversed
+            }
+            SortedBy::LastConnect(reversed) => {
+                rows.sort_by_key(|(server, _)| server.last_connected);
+                reversed
+            }
+        };
+        if reversed {
+            rows.reverse();
+        }
+    }
+}
+
+/// Whether the alias, the address, the group or a tag contains the lowercase filter.
+fn matches_filter(server: &ConnectionProfile, address: &str, filter: &str) -> bool {
+    filter.is_empty()
+        || server.alias.to_lowercase().contains(filter)
+        || address.to_lowercase().contains(filter)
+        || server
+            .group
+            .as_ref()
+            .is_some_and(|group| group.to_lowercase().contains(filter))
+        || server
+            .tags
+            .iter()
+            .any(|tag| tag.to_lowercase().contains(filter))
 }
 
 impl Default for SortedBy {
@@ -95,6 +160,19 @@ impl Component for ConnectionsWindow {
             });
             ui.add_space(10.0);
             ui.separator();
+            let unmigrated = state.get_settings().unmigrated_servers()?;
+            if !unmigrated.is_empty() {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "{} {}",
+                        state.i18n().get(LangKey::UnmigratedServers),
+                        unmigrated.join(", ")
+                    ),
+                );
+                ui.separator();
+            }
+            let mut vault_dialog = None;
             egui::Sides::new().show(
                 ui,
                 |ui| {
@@ -102,11 +180,15 @@ impl Component for ConnectionsWindow {
                         .button(state.i18n().get(LangKey::AddConnection))
                         .clicked()
                     {
-                        let sender = state.get_sender();
-                        sender
-                            .send(Message::OpenPopup(PopupType::AddConnection(Box::default())))
-                            .unwrap_or_else(|e| Error::from(e).show_error_dialog(sender.clone()));
+                        if state.get_settings().vault_state() == VaultState::Locked {
+                            vault_dialog = Some(VaultDialog::Unlock(Some(PendingAction::Add)));
+                        } else {
+                            Self::open_connection_popup(None, state);
+                        }
                     };
+                    if let Some(dialog) = Self::vault_menu(ui, state) {
+                        vault_dialog = Some(dialog);
+                    }
                 },
                 |ui| {
                     if !servers.is_empty() {
@@ -119,12 +201,19 @@ impl Component for ConnectionsWindow {
                 },
             );
             ui.separator();
+            if let Some(dialog) = vault_dialog {
+                self.open_vault_dialog(dialog);
+            }
 
             let sender = state.get_sender();
             self.connection_table(ui, state, &sender)?;
             Ok(())
         });
 
+        if self.vault_dialog.is_some() {
+            self.vault_modal(ctx, state);
+        }
+
         if let Some(server_alias) = &self.pending_delete.clone() {
             Modal::new(Id::new("delete_connection")).show(ctx, |ui| {
                 ui.set_width(280.0);
@@ -162,9 +251,11 @@ impl Component for ConnectionsWindow {
                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                         if ui.button(state.i18n().get(LangKey::Yes)).clicked() {
                             let settings = state.get_settings();
-                            settings.delete_server(server_alias).unwrap_or_else(|e| {
-                                e.show_error_dialog(state.get_sender());
-                            });
+                            settings
+                                .delete_server(server_alias, &state.i18n())
+                                .unwrap_or_else(|e| {
+                                    e.show_error_dialog(state.get_sender());
+                                });
                             self.pending_delete = None;
                         }
                     });
@@ -179,6 +270,197 @@ impl Component for ConnectionsWindow {
 }
 
 impl ConnectionsWindow {
+    fn open_vault_dialog(&mut self, dialog: VaultDialog) {
+        self.master_password.clear();
+        self.master_password_confirm.clear();
+        self.vault_error = None;
+        self.vault_dialog = Some(dialog);
+    }
+
+    fn open_connection_popup(profile: Option<&ConnectionProfile>, state: &AppState) {
+        let popup = match profile {
+            Some(profile) => Box::from(AddConnectionPopup::new(profile)),
+            None => Box::default(),
+        };
+        let sender = state.get_sender();
+        sender
+            .send(Message::OpenPopup(PopupType::AddConnection(popup)))
+            .unwrap_or_else(|e| Error::from(e).show_error_dialog(sender.clone()));
+    }
+
+    /// The vault actions for its state, returns the dialog to open.
+    fn vault_menu(ui: &mut Ui, state: &AppState) -> Option<VaultDialog> {
+        let i18n = state.i18n();
+        let settings = state.get_settings();
+        let mut dialog = None;
+        ui.menu_button(i18n.get(LangKey::Vault), |ui| {
+            match settings.vault_state() {
+                VaultState::Disabled => {
+                    if ui.button(i18n.get(LangKey::EnableVault)).clicked() {
+                        dialog = Some(VaultDialog::Enable);
+                    }
+                }
+                VaultState::Locked => {
+                    if ui.button(i18n.get(LangKey::UnlockVault)).clicked() {
+                        dialog = Some(VaultDialog::Unlock(None));
+                    }
+                }
+                VaultState::Unlocked => {
+                    if ui.button(i18n.get(LangKey::LockVault)).clicked() {
+                        settings
+                            .lock_vault()
+                            .unwrap_or_else(|e| e.show_error_dialog(state.get_sender()));
+                    }
+                    if ui.button(i18n.get(LangKey::DisableVault)).clicked() {
+                        dialog = Some(VaultDialog::Disable);
+                    }
+                }
+            }
+        });
+        dialog
+    }
+
+    fn vault_modal(&mut self, ctx: &Context, state: &mut AppState) {
+        let i18n = state.i18n();
+        if self.vault_busy
+            && let Some(result) = state.vault_result.take()
+        {
+            self.vault_busy = false;
+            self.finish_vault_dialog(result, state);
+        }
+        let Some(dialog) = &self.vault_dialog else {
+            return;
+        };
+        let busy = self.vault_busy;
+        let (title, text) = match dialog {
+            VaultDialog::Enable => (LangKey::EnableVault, LangKey::EnableVaultText),
+            VaultDialog::Unlock(_) => (LangKey::UnlockVault, LangKey::UnlockVaultText),
+            VaultDialog::Disable => (LangKey::DisableVault, LangKey::DisableVaultText),
+        };
+        let confirm = matches!(dialog, VaultDialog::Enable);
+        let needs_password = !matches!(dialog, VaultDialog::Disable);
+        let mut submitted = false;
+        let mut cancelled = false;
+
+        let modal = Modal::new(Id::new("vault")).show(ctx, |ui| {
+            ui.set_width(320.0);
+            ui.heading(i18n.get(title));
+            ui.add_space(8.0);
+            ui.label(i18n.get(text));
+            ui.add_space(8.0);
+            if needs_password {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.master_password)
+                        .password(true)
+                        .hint_text(i18n.get(LangKey::MasterPassword)),
+                );
+                if !confirm {
+                    response.request_focus();
+                }
+                submitted |= !confirm
+                    && response.lost_focus()
+                    && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                if confirm {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.master_password_confirm)
+                            .password(true)
+                            .hint_text(i18n.get(LangKey::ConfirmMasterPassword)),
+                    );
+                }
+            }
+            if let Some(e) = &self.vault_error {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                cancelled = ui
+                    .add_enabled(!busy, Button::new(i18n.get(LangKey::Cancel)))
+                    .clicked();
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    let enabled = !busy && (!needs_password || !self.master_password.is_empty());
+                    submitted |= ui
+                        .add_enabled(enabled, Button::new(i18n.get(title)))
+                        .clicked();
+                    if busy {
+                        ui.spinner();
+                    }
+                });
+            });
+        });
+        if busy {
+            return;
+        }
+        if cancelled || modal.should_close() {
+            self.vault_dialog = None;
+            self.master_password.clear();
+            self.master_password_confirm.clear();
+        } else if submitted {
+            self.submit_vault_dialog(state);
+        }
+    }
+
+    /// Runs the action of the dialog on a worker thread, deriving the key of the vault takes
+    /// a noticeable time. The result arrives as [`Event::VaultResult`].
+    fn submit_vault_dialog(&mut self, state: &mut AppState) {
+        let Some(dialog) = &self.vault_dialog else {
+            return;
+        };
+        if matches!(dialog, VaultDialog::Enable)
+            && self.master_password != self.master_password_confirm
+        {
+            self.vault_error = Some(state.i18n().get(LangKey::PasswordsDoNotMatch));
+            return;
+        }
+        let enable = matches!(dialog, VaultDialog::Enable);
+        let disable = matches!(dialog, VaultDialog::Disable);
+        let password = self.master_password.clone();
+        let settings = state.get_settings();
+        let sender = state.get_sender();
+        let i18n = state.i18n();
+
+        self.vault_error = None;
+        self.vault_busy = true;
+        state.vault_result = None;
+        thread::spawn(move || {
+            let result = if enable {
+                settings.enable_vault(&password, &i18n)
+            } else if disable {
+                settings.disable_vault(&i18n)
+            } else {
+                settings.unlock_vault(&password, &i18n)
+            };
+            let result = result.map_err(|e| e.to_string());
+            if let Err(e) = sender.send(Event(Arc::new(VaultResult(result)))) {
+                Error::from(e).show_error_dialog(sender);
+            }
+        });
+    }
+
+    /// Closes the dialog after the vault action succeeded and continues with the pending action.
+    fn finish_vault_dialog(&mut self, result: Result<(), String>, state: &mut AppState) {
+        if let Err(e) = result {
+            self.vault_error = Some(e);
+            return;
+        }
+        let settings = state.get_settings();
+        self.master_password.clear();
+        self.master_password_confirm.clear();
+        if let Some(VaultDialog::Unlock(Some(action))) = self.vault_dialog.take() {
+            let servers = settings.get_servers().unwrap_or_default();
+            let find = |alias: &str| servers.iter().find(|server| server.alias == alias);
+            match action {
+                PendingAction::Connect(alias) => {
+                    if let Some(server) = find(&alias) {
+                        Self::connect_to_valkey(Some(alias), server.connection_string(), state);
+                    }
+                }
+                PendingAction::Edit(alias) => Self::open_connection_popup(find(&alias), state),
+                PendingAction::Delete(alias) => self.pending_delete = Some(alias),
+                PendingAction::Add => Self::open_connection_popup(None, state),
+            }
+        }
+    }
+
     fn connection_table(
         &mut self,
         ui: &mut Ui,
@@ -189,7 +471,7 @@ impl ConnectionsWindow {
         ui.allocate_ui(ui.available_size(), |ui| {
             let servers = settings.get_servers().unwrap_or_else(|e| {
                 e.show_error_dialog(sender.clone());
-                HashMap::new()
+                Vec::new()
             });
             if servers.is_empty() {
                 ui.with_layout(Layout::centered_and_justified(Direction::TopDown), |ui| {
@@ -211,20 +493,44 @@ impl ConnectionsWindow {
     ) -> Result<(), Box<Error>> {
         let mut sorted_by = self.sorted_by.try_write()?;
         let settings = state.get_settings();
-        let servers = settings.get_servers()?;
+        let filter = self.filter.trim().to_lowercase();
+        let mut groups: BTreeMap<Option<String>, Vec<(ConnectionProfile, String)>> =
+            BTreeMap::new();
+        for server in settings.get_servers()? {
+            let address = server.url().map(|url| url.address()).unwrap_or_default();
+            if matches_filter(&server, &address, &filter) {
+                groups
+                    .entry(server.group.clone())
+                    .or_default()
+                    .push((server, address));
+            }
+        }
+        // Ungrouped connections are listed last, under a header only next to named groups.
+        let ungrouped = groups.remove(&None);
+        let show_headers = !groups.is_empty();
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.extend(ungrouped.map(|rows| (None, rows)));
+        for (_, rows) in &mut groups {
+            sorted_by.sort(rows);
+        }
+        let locked = settings.vault_state() == VaultState::Locked;
+        let mut vault_dialog = None;
         let available_height = ui.available_height();
         TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
             .sense(egui::Sense::click())
             .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::exact(16.0))
             .column(Column::auto())
             .column(Column::auto().at_least(40.0).clip(true).resizable(true))
+            .column(Column::auto().at_least(40.0).clip(true).resizable(true))
             .column(Column::auto())
             .column(Column::remainder())
             .min_scrolled_height(0.0)
             .max_scroll_height(available_height)
             .header(20.0, |mut header| {
+                header.col(|_| {});
                 header.col(|ui| {
                     ui.horizontal(|ui| {
                         if ui
@@ -238,6 +544,9 @@ impl ConnectionsWindow {
                         }
                     });
                 });
+                header.col(|ui| {
+                    ui.strong(state.i18n().get(LangKey::Tags));
+                });
                 header.col(|ui| {
                     ui.horizontal(|ui| {
                         if ui.strong("Host:Port").clicked() {
@@ -276,67 +585,147 @@ impl ConnectionsWindow {
                 });
             })
             .body(|mut body| {
-                for server in servers {
-                    let server_info = ValkeyUrl::from(&server.1);
-                    let row_height = 21.0;
-                    body.row(row_height, |mut row| {
-                        row.col(|ui| {
-                            ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
-                            ui.add(Label::new(&server.0).selectable(false));
-                        });
-                        row.col(|ui| {
-                            ui.add(
-                                Label::new(format!(
-                                    "{}:{}",
-                                    server_info.host(),
-                                    server_info.port()
-                                ))
-                                .selectable(false),
-                            );
-                        });
-                        row.col(|ui| {
-                            let connection_type = server_info.connection_type().unwrap_or("-");
-                            ui.add(Label::new(connection_type).selectable(false));
-                        });
-                        row.col(|ui| {
-                            let last_connection = server_info.last_connection().unwrap_or("-");
-                            ui.add(Label::new(last_connection).selectable(false));
+                let row_height = 21.0;
+                for (group, rows) in groups {
+                    let group = group.unwrap_or_default();
+                    let collapsed = self.collapsed_groups.contains(&group);
+                    if show_headers {
+                        body.row(row_height, |mut row| {
+                            row.col(|ui| {
+                                ui.add(
+                                    Label::new(if collapsed { "▶" } else { "▼" }).selectable(false),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                                let name = if group.is_empty() {
+                                    state.i18n().get(LangKey::Ungrouped)
+                                } else {
+                                    group.clone()
+                                };
+                                ui.add(Label::new(RichText::new(name).strong()).selectable(false));
+                                ui.weak(format!("({})", rows.len()));
+                            });
+                            for _ in 0..4 {
+                                row.col(|_| {});
+                            }
+                            if row.response().clicked() && !self.collapsed_groups.remove(&group) {
+                                self.collapsed_groups.insert(group.clone());
+                            }
                         });
+                    }
+                    if show_headers && collapsed {
+                        continue;
+                    }
+                    for (server, address) in rows {
+                        body.row(row_height, |mut row| {
+                            row.col(|ui| {
+                                let lock_state = if server.in_vault && locked {
+                                    Some(("🔒", LangKey::VaultLocked))
+                                } else if server.in_vault {
+                                    Some(("🔓", LangKey::VaultUnlocked))
+                                } else if server.has_plain_password() {
+                                    Some(("⚠", LangKey::PlainTextPassword))
+                                } else {
+                                    None
+                                };
+                                if let Some((icon, hint)) = lock_state {
+                                    ui.add(Label::new(icon).selectable(false))
+                                        .on_hover_text(state.i18n().get(hint));
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                                if let Some([r, g, b]) = server.color {
+                                    ui.add(
+                                        Label::new(
+                                            RichText::new("●").color(Color32::from_rgb(r, g, b)),
+                                        )
+                                        .selectable(false),
+                                    );
+                                }
+                                ui.add(Label::new(&server.alias).selectable(false));
+                            });
+                            row.col(|ui| {
+                                if server.production {
+                                    ui.add(
+                                        Label::new(
+                                            RichText::new(state.i18n().get(LangKey::Production))
+                                                .strong()
+                                                .color(ui.visuals().error_fg_color),
+                                        )
+                                        .selectable(false),
+                                    );
+                                }
+                                ui.add(
+                                    Label::new(server.tags.join(", "))
+                                        .selectable(false)
+                                        .truncate(),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.add(Label::new(address).selectable(false));
+                            });
+                            row.col(|ui| {
+                                let connection_type = server.server_type.as_deref().unwrap_or("-");
+                                ui.add(Label::new(connection_type).selectable(false));
+                            });
+                            row.col(|ui| {
+                                let last_connection = server
+                                    .last_connected_at()
+                                    .unwrap_or_else(|| "-".to_string());
+                                ui.add(Label::new(last_connection).selectable(false));
+                            });
 
-                        if row.response().clicked() {
-                            Self::connect_to_valkey(
-                                Some(server.0.clone()),
-                                server_info.to_string(),
-                                state,
-                            );
-                        }
+                            let needs_unlock = server.in_vault && locked;
+                            if row.response().clicked() && needs_unlock {
+                                vault_dialog = Some(VaultDialog::Unlock(Some(
+                                    PendingAction::Connect(server.alias.clone()),
+                                )));
+                            } else if row.response().clicked() {
+                                Self::connect_to_valkey(
+                                    Some(server.alias.clone()),
+                                    server.connection_string(),
+                                    state,
+                                );
+                            }
 
-                        row.response().context_menu(|ui| {
-                            if ui
-                                .add(Button::new(state.i18n().get(LangKey::Edit)))
-                                .clicked()
-                            {
-                                let sender = state.get_sender();
-                                sender
-                                    .send(Message::OpenPopup(PopupType::AddConnection(Box::from(
-                                        AddConnectionPopup::new(&server.0, &server.1),
-                                    ))))
-                                    .unwrap_or_else(|e| {
-                                        Error::from(e).show_error_dialog(sender.clone())
-                                    });
-                                ui.close();
-                            };
-                            if ui
-                                .add(Button::new(state.i18n().get(LangKey::Delete)))
-                                .clicked()
-                            {
-                                self.pending_delete = Some(server.0.clone());
-                                ui.close();
-                            };
+                            row.response().context_menu(|ui| {
+                                if ui
+                                    .add(Button::new(state.i18n().get(LangKey::Edit)))
+                                    .clicked()
+                                {
+                                    if needs_unlock {
+                                        vault_dialog = Some(VaultDialog::Unlock(Some(
+                                            PendingAction::Edit(server.alias.clone()),
+                                        )));
+                                    } else {
+                                        Self::open_connection_popup(Some(&server), state);
+                                    }
+                                    ui.close();
+                                };
+                                if ui
+                                    .add(Button::new(state.i18n().get(LangKey::Delete)))
+                                    .clicked()
+                                {
+                                    if needs_unlock {
+                                        vault_dialog = Some(VaultDialog::Unlock(Some(
+                                            PendingAction::Delete(server.alias.clone()),
+                                        )));
+                                    } else {
+                                        self.pending_delete = Some(server.alias.clone());
+                                    }
+                                    ui.close();
+                                };
+                            });
                         });
-                    });
+                    }
                 }
             });
+        drop(sorted_by);
+        if let Some(dialog) = vault_dialog {
+            self.open_vault_dialog(dialog);
+        }
         Ok(())
     }
     fn connect_to_valkey(alias: Option<String>, url: String, state: &mut AppState) {
@@ -353,22 +742,13 @@ impl ConnectionsWindow {
             ) {
                 Ok(vc) => {
                     if let Some(alias_str) = &alias {
-                        let server_type = vc.server_type();
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs();
-
-                        let timestamp = format!("{}", now);
-
-                        if let Ok(valkey_url) = ValkeyUrl::parse_valkey_url(Some(alias_str), &url) {
-                            let connection_string = format!(
-                                "{}|type:{}|last:{}",
-                                valkey_url.connection_string(),
-                                server_type,
-                                timestamp
-                            );
-                            let _ = settings.update_server(alias_str, &connection_string);
+                        let server_type = if ValkeyUrl::from(&url).master_name().is_some() {
+                            "sentinel".to_string()
+                        } else {
+                            vc.server_type()
+                        };
+                        if let Err(e) = settings.record_connection(alias_str, &server_type) {
+                            eprintln!("Cannot save the connection time: {e:?}");
                         }
                     }
 
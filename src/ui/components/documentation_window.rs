@@ -127,7 +127,7 @@ impl DocumentationWindow {
             }
         }
 
-        commands.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        commands.sort_by_key(|a| a.name.to_lowercase());
 
         self.commands = commands;
         self.loaded = true;
@@ -184,7 +184,7 @@ impl DocumentationWindow {
         }
 
         for commands in grouped.values_mut() {
-            commands.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+            commands.sort_by_key(|a| a.name.to_lowercase());
         }
 
         grouped
@@ -377,11 +377,11 @@ impl Component for DocumentationWindow {
                                         }
 
                                         for children in by_container.values_mut() {
-                                            children.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+                                            children.sort_by_key(|a| a.name.to_lowercase());
                                         }
 
                                         let mut container_keys: Vec<String> = by_container.keys().cloned().collect();
-                                        container_keys.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+                                        container_keys.sort_by_key(|a| a.to_lowercase());
 
                                         for container in container_keys {
                                             egui::CollapsingHeader::new(format!("📦 {}", container))
@@ -420,7 +420,7 @@ impl Component for DocumentationWindow {
                                                 standalone.push(cmd);
                                             }
                                         }
-                                        standalone.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+                                        standalone.sort_by_key(|a| a.name.to_lowercase());
 
                                         for cmd in standalone {
                                             self.command_entry(
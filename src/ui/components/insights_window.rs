@@ -2,7 +2,9 @@ use crate::errors::Error;
 use crate::i18n::I18N;
 use crate::state::{AppState, Message};
 use crate::ui::Component;
-use crate::utils::{ValkeyClient, format_size, type_color};
+use crate::utils::{
+    Cmd, Connection, ValkeyClient, ValkeyValue, display_bytes, format_size, type_color,
+};
 use egui::{Context, ScrollArea};
 use std::collections::HashMap;
 use std::sync::mpsc::Sender;
@@ -77,7 +79,7 @@ pub struct InsightsWindow {
 
     sort_mode: SortMode,
     sender: Arc<Sender<Message>>,
-    i18n: Arc<I18N>,
+    _i18n: Arc<I18N>,
 }
 
 impl InsightsWindow {
@@ -90,7 +92,7 @@ impl InsightsWindow {
             is_analyzing: Arc::new(RwLock::new(false)),
             sort_mode: SortMode::BySize,
             sender,
-            i18n,
+            _i18n: i18n,
         }
     }
 
@@ -142,9 +144,10 @@ impl InsightsWindow {
         thread::spawn(move || {
             let mut new_stats = HashMap::new();
 
-            if let Ok(info_result) = valkey_client.exec("INFO")
-                && let Some(info_str) = info_result.first()
+            if let Ok(info_result) = valkey_client.exec(&Cmd::new("INFO"))
+                && let Some(info_bytes) = info_result.as_bytes()
             {
+                let info_str = String::from_utf8_lossy(info_bytes);
                 for line in info_str.lines() {
                     if line.contains(':') && !line.starts_with('#') {
                         let parts: Vec<&str> = line.splitn(2, ':').collect();
@@ -155,15 +158,16 @@ impl InsightsWindow {
                 }
             }
 
-            if let Ok(dbsize_result) = valkey_client.exec("DBSIZE")
-                && let Some(dbsize_str) = dbsize_result.first()
+            if let Ok(dbsize_result) = valkey_client.exec(&Cmd::new("DBSIZE"))
+                && let Some(dbsize) = dbsize_result.as_int()
             {
-                new_stats.insert("dbsize".to_string(), dbsize_str.clone());
+                new_stats.insert("dbsize".to_string(), dbsize.to_string());
             }
 
-            if let Ok(client_list_result) = valkey_client.exec("CLIENT LIST")
-                && let Some(list_str) = client_list_result.first()
+            if let Ok(client_list_result) = valkey_client.exec(&Cmd::new("CLIENT").arg("LIST"))
+                && let Some(list_bytes) = client_list_result.as_bytes()
             {
+                let list_str = String::from_utf8_lossy(list_bytes);
                 let count = list_str.lines().filter(|l| !l.is_empty()).count();
                 new_stats.insert("connected_clients_count".to_string(), count.to_string());
             }
@@ -194,6 +198,29 @@ impl InsightsWindow {
         }
 
         thread::spawn(move || {
+            // A cluster spreads the keys over several nodes, its client scans them all.
+            let mut connection = if valkey_client.is_cluster() {
+                None
+            } else {
+                match valkey_client.dedicated_connection(None) {
+                    Ok(connection) => Some(connection),
+                    Err(e) => {
+                        e.show_error_dialog(sender.clone());
+                        write_lock_arc(&is_analyzing, |a| *a = false, &sender);
+                        return;
+                    }
+                }
+            };
+            let exec = |connection: &mut Option<Connection>, cmd: &Cmd| match connection {
+                Some(connection) => connection.exec(cmd),
+                None => valkey_client.exec(cmd),
+            };
+            let exec_pipelined =
+                |connection: &mut Option<Connection>, cmds: &[Cmd]| match connection {
+                    Some(connection) => connection.exec_pipelined(cmds),
+                    None => valkey_client.exec_pipelined(cmds),
+                };
+
             let mut type_stats: HashMap<String, TypeStats> = HashMap::new();
             let mut all_keys: Vec<KeyInfo> = Vec::new();
             let mut ttl_buckets = TtlBuckets::default();
@@ -205,22 +232,20 @@ impl InsightsWindow {
                     break;
                 }
 
-                let scan_cmd = format!("SCAN {} COUNT {}", cursor, SCAN_COUNT);
-                let scan_result = match valkey_client.exec(&scan_cmd) {
-                    Ok(result) => result,
-                    Err(_) => break,
+                let scan_cmd = Cmd::new("SCAN").arg(cursor).arg("COUNT").arg(SCAN_COUNT);
+                let mut scan_result = match exec(&mut connection, &scan_cmd) {
+                    Ok(ValkeyValue::Array(result)) if result.len() == 2 => result,
+                    _ => break,
                 };
 
-                if scan_result.len() < 2 {
-                    break;
-                }
-
-                cursor = scan_result[0].parse::<usize>().unwrap_or(0);
-                let keys: Vec<String> = scan_result[1..]
-                    .iter()
+                let keys: Vec<Vec<u8>> = scan_result
+                    .pop()
+                    .map(|k| k.into_bytes_vec())
+                    .unwrap_or_default()
+                    .into_iter()
                     .filter(|k| !k.is_empty())
-                    .map(|k| k.to_string())
                     .collect();
+                cursor = scan_result.pop().and_then(|c| c.as_int()).unwrap_or(0) as usize;
 
                 if keys.is_empty() {
                     if cursor == 0 {
@@ -234,48 +259,29 @@ impl InsightsWindow {
                 let mut memory_commands = Vec::new();
 
                 for key in &keys {
-                    let quoted_key = if key.contains(' ') || key.contains('"') {
-                        let escaped = key.replace('"', "\\\"");
-                        format!("\"{}\"", escaped)
-                    } else {
-                        key.clone()
-                    };
-
-                    type_commands.push(format!("TYPE {}", quoted_key));
-                    ttl_commands.push(format!("TTL {}", quoted_key));
-                    memory_commands.push(format!("MEMORY USAGE {}", quoted_key));
+                    type_commands.push(Cmd::new("TYPE").arg(key));
+                    ttl_commands.push(Cmd::new("TTL").arg(key));
+                    memory_commands.push(Cmd::new("MEMORY").arg("USAGE").arg(key));
                 }
 
-                let types = valkey_client
-                    .exec_pipelined(&type_commands)
-                    .unwrap_or_default();
-                let ttls = valkey_client
-                    .exec_pipelined(&ttl_commands)
-                    .unwrap_or_default();
-                let sizes = valkey_client
-                    .exec_pipelined(&memory_commands)
-                    .unwrap_or_default();
+                let types = exec_pipelined(&mut connection, &type_commands).unwrap_or_default();
+                let ttls = exec_pipelined(&mut connection, &ttl_commands).unwrap_or_default();
+                let sizes = exec_pipelined(&mut connection, &memory_commands).unwrap_or_default();
 
                 for (i, key) in keys.iter().enumerate() {
                     let key_type = types
                         .get(i)
-                        .cloned()
+                        .map(|t| t.to_string())
                         .unwrap_or_else(|| "string".to_string());
-                    let ttl = ttls
-                        .get(i)
-                        .and_then(|t| t.parse::<i64>().ok())
-                        .unwrap_or(-1);
-                    let size = sizes
-                        .get(i)
-                        .and_then(|s| s.parse::<u64>().ok())
-                        .unwrap_or(0);
+                    let ttl = ttls.get(i).and_then(|t| t.as_int()).unwrap_or(-1);
+                    let size = sizes.get(i).and_then(|s| s.as_int()).unwrap_or(0) as u64;
 
                     let stats = type_stats.entry(key_type.clone()).or_default();
                     stats.count += 1;
                     stats.total_memory += size;
 
                     all_keys.push(KeyInfo {
-                        name: key.clone(),
+                        name: display_bytes(key),
                         key_type: key_type.clone(),
                         size,
                         ttl,
@@ -344,7 +350,7 @@ impl InsightsWindow {
         }
 
         let mut sorted_types: Vec<_> = type_stats.iter().collect();
-        sorted_types.sort_by(|a, b| b.1.total_memory.cmp(&a.1.total_memory));
+        sorted_types.sort_by_key(|t| std::cmp::Reverse(t.1.total_memory));
 
         ui.vertical(|ui| {
             for (key_type, stats) in sorted_types {
@@ -435,10 +441,10 @@ impl InsightsWindow {
         let mut sorted_keys = analysis.top_keys.clone();
         match self.sort_mode {
             SortMode::BySize => {
-                sorted_keys.sort_by(|a, b| b.size.cmp(&a.size));
+                sorted_keys.sort_by_key(|k| std::cmp::Reverse(k.size));
             }
             SortMode::ByLength => {
-                sorted_keys.sort_by(|a, b| b.name.len().cmp(&a.name.len()));
+                sorted_keys.sort_by_key(|k| std::cmp::Reverse(k.name.len()));
             }
         }
 
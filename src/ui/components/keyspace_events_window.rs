@@ -0,0 +1,316 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use crate::state::AppState;
+use crate::ui::Component;
+use crate::utils::{
+    KEYEVENT_EVENTS, KEYSPACE_EVENTS, KeyspaceEvent, KeyspaceListener, ValkeyClient, ValkeyUrl,
+    display_bytes, format_timestamp, glob_match, has_notify_flags,
+};
+use egui::{Align, Button, Context, Label, Layout, ScrollArea};
+use egui_extras::{Column, TableBuilder};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+/// The oldest events are dropped beyond this, the counters keep counting.
+const MAX_EVENTS: usize = 5000;
+
+/// Channels the events are received on. Both carry the same events, keyspace channels
+/// are named after the key and keyevent channels after the event.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum EventSource {
+    #[default]
+    Keyspace,
+    Keyevent,
+}
+
+impl EventSource {
+    fn pattern(&self, db: u32) -> String {
+        match self {
+            EventSource::Keyspace => format!("__keyspace@{db}__:*"),
+            EventSource::Keyevent => format!("__keyevent@{db}__:*"),
+        }
+    }
+
+    fn required_flags(&self) -> &'static str {
+        match self {
+            EventSource::Keyspace => KEYSPACE_EVENTS,
+            EventSource::Keyevent => KEYEVENT_EVENTS,
+        }
+    }
+
+    fn label(&self, i18n: &I18N) -> String {
+        i18n.get(match self {
+            EventSource::Keyspace => LangKey::KeyspaceChannels,
+            EventSource::Keyevent => LangKey::KeyeventChannels,
+        })
+    }
+}
+
+/// Streams keyspace notifications to track down unexpected expirations or evictions.
+#[derive(Default)]
+pub struct KeyspaceEventsWindow {
+    listener: Option<KeyspaceListener>,
+    source: EventSource,
+    events: VecDeque<KeyspaceEvent>,
+    counters: BTreeMap<String, u64>,
+    hidden_events: HashSet<String>,
+    key_pattern: String,
+    error: Option<String>,
+    /// `notify-keyspace-events` of the server, `None` until loaded.
+    server_flags: Option<String>,
+    flags_input: String,
+    /// Result of the last read of the flags, taken over by the next frame.
+    fetched_flags: Arc<RwLock<Option<Result<String, String>>>>,
+    initial: bool,
+}
+
+impl KeyspaceEventsWindow {
+    fn start(&mut self, client: &Arc<ValkeyClient>, ctx: &Context) {
+        let db = ValkeyUrl::from(&**client).db().unwrap_or(0);
+        let ctx = ctx.clone();
+        match KeyspaceListener::new(client, &self.source.pattern(db), move || {
+            ctx.request_repaint()
+        }) {
+            Ok(listener) => {
+                self.listener = Some(listener);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn handle_events(&mut self) {
+        let Some(listener) = &mut self.listener else {
+            return;
+        };
+        for event in listener.events() {
+            *self.counters.entry(event.event.clone()).or_default() += 1;
+            if self.events.len() >= MAX_EVENTS {
+                self.events.pop_front();
+            }
+            self.events.push_back(event);
+        }
+        if let Some(reason) = listener.closed() {
+            self.error = Some(reason.to_string());
+            self.listener = None;
+        }
+    }
+
+    /// Reads the flags in the background. With `flags` set they are written first.
+    fn load_flags(&self, client: &Arc<ValkeyClient>, ctx: &Context, flags: Option<String>) {
+        let client = Arc::clone(client);
+        let ctx = ctx.clone();
+        let fetched_flags = Arc::clone(&self.fetched_flags);
+        thread::spawn(move || {
+            let result = flags
+                .map_or(Ok(()), |flags| KeyspaceListener::set_flags(&client, &flags))
+                .and_then(|_| KeyspaceListener::flags(&client))
+                .map_err(|e| e.to_string());
+            if let Ok(mut guard) = fetched_flags.write() {
+                *guard = Some(result);
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn config_section(&mut self, ui: &mut egui::Ui, client: &Arc<ValkeyClient>, i18n: &I18N) {
+        if let Some(result) = self
+            .fetched_flags
+            .write()
+            .ok()
+            .and_then(|mut guard| guard.take())
+        {
+            match result {
+                Ok(flags) => {
+                    self.flags_input = flags.clone();
+                    self.server_flags = Some(flags);
+                }
+                Err(e) => self.error = Some(e),
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("notify-keyspace-events:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.flags_input)
+                    .desired_width(160.0)
+                    .font(egui::TextStyle::Monospace),
+            )
+            .on_hover_text(i18n.get(LangKey::NotifyFlagsHint));
+            let changed = self.server_flags.as_ref() != Some(&self.flags_input);
+            if ui
+                .add_enabled(changed, Button::new(i18n.get(LangKey::Apply)))
+                .clicked()
+            {
+                self.load_flags(client, ui.ctx(), Some(self.flags_input.clone()));
+            }
+            if ui
+                .button("↻")
+                .on_hover_text(i18n.get(LangKey::Refresh))
+                .clicked()
+            {
+                self.load_flags(client, ui.ctx(), None);
+            }
+        });
+        if let Some(flags) = &self.server_flags
+            && !has_notify_flags(flags, self.source.required_flags())
+        {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                i18n.get(LangKey::NotificationsDisabled),
+            );
+        }
+    }
+
+    fn counters_panel(&mut self, ctx: &Context, i18n: &I18N) {
+        egui::SidePanel::right("keyspace_event_counters")
+            .default_width(200.0)
+            .show(ctx, |ui| {
+                egui::Sides::new().show(
+                    ui,
+                    |ui| ui.strong(i18n.get(LangKey::Event)),
+                    |ui| ui.strong(i18n.get(LangKey::Count)),
+                );
+                ui.weak(i18n.get(LangKey::EventsHiddenHint));
+                ui.separator();
+                ScrollArea::vertical()
+                    .id_salt("keyspace_event_counters_scroll")
+                    .show(ui, |ui| {
+                        for (event, count) in &self.counters {
+                            egui::Sides::new().show(
+                                ui,
+                                |ui| {
+                                    let mut shown = !self.hidden_events.contains(event);
+                                    if ui.checkbox(&mut shown, event).changed() {
+                                        if shown {
+                                            self.hidden_events.remove(event);
+                                        } else {
+                                            self.hidden_events.insert(event.clone());
+                                        }
+                                    }
+                                },
+                                |ui| ui.monospace(count.to_string()),
+                            );
+                        }
+                    });
+            });
+    }
+
+    fn event_table(&self, ui: &mut egui::Ui, i18n: &I18N) {
+        let events: Vec<&KeyspaceEvent> = self
+            .events
+            .iter()
+            .filter(|event| {
+                !self.hidden_events.contains(&event.event)
+                    && (self.key_pattern.is_empty()
+                        || glob_match(self.key_pattern.as_bytes(), &event.key))
+            })
+            .collect();
+
+        let available_height = ui.available_height();
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .stick_to_bottom(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::auto())
+            .column(Column::initial(120.0).at_least(60.0).clip(true))
+            .column(Column::remainder().clip(true))
+            .min_scrolled_height(0.0)
+            .max_scroll_height(available_height)
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong(i18n.get(LangKey::Time));
+                });
+                header.col(|ui| {
+                    ui.strong(i18n.get(LangKey::Event));
+                });
+                header.col(|ui| {
+                    ui.strong(i18n.get(LangKey::Key));
+                });
+            })
+            .body(|body| {
+                body.rows(20.0, events.len(), |mut row| {
+                    let event = events[row.index()];
+                    row.col(|ui| {
+                        ui.monospace(format_timestamp(event.received));
+                    });
+                    row.col(|ui| {
+                        ui.label(&event.event);
+                    });
+                    row.col(|ui| {
+                        ui.add(Label::new(display_bytes(&event.key)).truncate());
+                    });
+                });
+            });
+    }
+}
+
+impl Component for KeyspaceEventsWindow {
+    fn show(&mut self, ctx: &Context, state: &mut AppState) -> Result<(), Error> {
+        let Some(valkey_client) = state.valkey_client.clone() else {
+            return Ok(());
+        };
+        let i18n = state.i18n();
+        if !self.initial {
+            self.load_flags(&valkey_client, ctx, None);
+            self.initial = true;
+        }
+        self.handle_events();
+        self.counters_panel(ctx, &i18n);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::Sides::new().show(
+                ui,
+                |ui| ui.heading(i18n.get(LangKey::KeyspaceEvents)),
+                |ui| {
+                    if ui.button(i18n.get(LangKey::Reset)).clicked() {
+                        self.events.clear();
+                        self.counters.clear();
+                    }
+                },
+            );
+            ui.separator();
+
+            self.config_section(ui, &valkey_client, &i18n);
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let running = self.listener.is_some();
+                ui.add_enabled_ui(!running, |ui| {
+                    egui::ComboBox::new(ui.id().with("event_source"), "")
+                        .selected_text(self.source.label(&i18n))
+                        .show_ui(ui, |ui| {
+                            for source in [EventSource::Keyspace, EventSource::Keyevent] {
+                                ui.selectable_value(&mut self.source, source, source.label(&i18n));
+                            }
+                        });
+                });
+                if running {
+                    if ui.button(i18n.get(LangKey::Stop)).clicked() {
+                        self.listener = None;
+                    }
+                } else if ui.button(i18n.get(LangKey::Start)).clicked() {
+                    self.start(&valkey_client, ctx);
+                }
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.key_pattern)
+                        .desired_width(f32::INFINITY)
+                        .hint_text(i18n.get(LangKey::FilterByKeyNameOrPattern)),
+                );
+            });
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+
+            self.event_table(ui, &i18n);
+        });
+        Ok(())
+    }
+
+    fn refresh(&mut self, _: &Arc<ValkeyClient>) {
+        self.initial = false;
+    }
+}
@@ -72,6 +72,46 @@ impl Component for LeftSideBar {
                                 e.show_error_dialog(state.get_sender());
                             });
                     };
+                    if ui
+                        .add_sized(
+                            [ui.available_width(), 0.0],
+                            egui::Button::new(state.i18n().get(LangKey::PubSub))
+                                .selected(matches!(current_window, Some(MainWindow::PubSub))),
+                        )
+                        .clicked()
+                    {
+                        self.set_window_state(state, MainWindow::PubSub)
+                            .unwrap_or_else(|e| {
+                                e.show_error_dialog(state.get_sender());
+                            });
+                    };
+                    if ui
+                        .add_sized(
+                            [ui.available_width(), 0.0],
+                            egui::Button::new(state.i18n().get(LangKey::KeyspaceEvents)).selected(
+                                matches!(current_window, Some(MainWindow::KeyspaceEvents)),
+                            ),
+                        )
+                        .clicked()
+                    {
+                        self.set_window_state(state, MainWindow::KeyspaceEvents)
+                            .unwrap_or_else(|e| {
+                                e.show_error_dialog(state.get_sender());
+                            });
+                    };
+                    if ui
+                        .add_sized(
+                            [ui.available_width(), 0.0],
+                            egui::Button::new(state.i18n().get(LangKey::Monitor))
+                                .selected(matches!(current_window, Some(MainWindow::Monitor))),
+                        )
+                        .clicked()
+                    {
+                        self.set_window_state(state, MainWindow::Monitor)
+                            .unwrap_or_else(|e| {
+                                e.show_error_dialog(state.get_sender());
+                            });
+                    };
                     if ui
                         .add_sized(
                             [ui.available_width(), 0.0],
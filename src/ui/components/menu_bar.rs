@@ -1,10 +1,10 @@
 use crate::errors::Error;
 use crate::i18n::LangKey;
-use crate::state::{AppState, Message};
+use crate::state::{AppState, MainWindow, Message};
 use crate::ui::Component;
 use crate::ui::widgets::PopupType;
 use crate::utils::ValkeyClient;
-use egui::Context;
+use egui::{Color32, Context, RichText};
 use std::process::Command;
 use std::sync::Arc;
 use std::{env, thread};
@@ -63,10 +63,76 @@ impl Component for MenuBar {
                         ui.close();
                     }
                 });
+                let reconnecting = state
+                    .valkey_client
+                    .as_ref()
+                    .is_some_and(|client| client.is_reconnecting());
+                let read_only = state.is_read_only();
+                if reconnecting || read_only {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if read_only {
+                            ui.label(format!("🔒 {}", state.i18n().get(LangKey::ReadOnly)))
+                                .on_hover_text(state.i18n().get(LangKey::ReadOnlyConnectionHint));
+                        }
+                        if reconnecting {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                state.i18n().get(LangKey::Reconnecting),
+                            );
+                            ui.add(egui::Spinner::new());
+                        }
+                    });
+                }
             });
         });
+        self.production_banner(ctx, state)?;
         Ok(())
     }
 
     fn refresh(&mut self, _: &Arc<ValkeyClient>) {}
 }
+
+impl MenuBar {
+    /// Marks every window working on a production connection, in the color of the connection.
+    fn production_banner(&self, ctx: &Context, state: &AppState) -> Result<(), Error> {
+        let current_window = *state.ui_panels.current_window.read()?;
+        let Some(alias) = state.production_alias() else {
+            return Ok(());
+        };
+        if state.valkey_client.is_none()
+            || matches!(
+                current_window,
+                None | Some(MainWindow::Connection | MainWindow::Documentation)
+            )
+        {
+            return Ok(());
+        }
+        let fill = state
+            .connection_profile
+            .as_ref()
+            .and_then(|profile| profile.color)
+            .map_or(Color32::from_rgb(190, 30, 30), |[r, g, b]| {
+                Color32::from_rgb(r, g, b)
+            });
+        let text_color = if fill.intensity() > 0.6 {
+            Color32::BLACK
+        } else {
+            Color32::WHITE
+        };
+        egui::TopBottomPanel::top("production_banner")
+            .frame(egui::Frame::new().fill(fill).inner_margin(4))
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.label(
+                        RichText::new(format!(
+                            "{}: {alias}",
+                            state.i18n().get(LangKey::ProductionConnection)
+                        ))
+                        .strong()
+                        .color(text_color),
+                    );
+                });
+            });
+        Ok(())
+    }
+}
@@ -2,8 +2,11 @@ mod browser_window;
 mod connections_window;
 mod documentation_window;
 mod insights_window;
+mod keyspace_events_window;
+mod monitor_window;
 mod left_side_bar;
 mod menu_bar;
+mod pubsub_window;
 mod ui_panels;
 mod workbench_window;
 
@@ -13,8 +16,11 @@ pub use browser_window::{BrowserWindow, KeyMetadata};
 pub use connections_window::ConnectionsWindow;
 pub use documentation_window::DocumentationWindow;
 pub use insights_window::InsightsWindow;
+pub use keyspace_events_window::KeyspaceEventsWindow;
+pub use monitor_window::MonitorWindow;
 pub use left_side_bar::LeftSideBar;
 pub use menu_bar::MenuBar;
+pub use pubsub_window::PubSubWindow;
 pub use ui_panels::UIPanels;
 pub use workbench_window::WorkbenchWindow;
 
@@ -0,0 +1,311 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use crate::state::AppState;
+use crate::ui::Component;
+use crate::utils::{Monitor, MonitorEntry, PathProvider, ValkeyClient, format_timestamp};
+use egui::{Align, Context, Id, Label, Layout, Modal};
+use egui_extras::{Column, TableBuilder};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Arc;
+
+const DEFAULT_BUFFER_SIZE: usize = 10_000;
+const MAX_BUFFER_SIZE: usize = 200_000;
+
+struct MonitorRow {
+    entry: MonitorEntry,
+    /// The entry as `MONITOR` printed it, matched by the filter and exported.
+    line: String,
+}
+
+/// Streams the commands processed by the server with `MONITOR`.
+pub struct MonitorWindow {
+    monitor: Option<Monitor>,
+    confirm_start: bool,
+    paused: bool,
+    skipped: u64,
+    rows: VecDeque<MonitorRow>,
+    buffer_size: usize,
+    filter: String,
+    regex: Option<Regex>,
+    regex_error: Option<String>,
+    export_path: String,
+    export_result: Option<Result<usize, String>>,
+    error: Option<String>,
+}
+
+impl Default for MonitorWindow {
+    fn default() -> Self {
+        let export_path = PathProvider::get_config_path()
+            .map(|path| path.join("monitor.log").display().to_string())
+            .unwrap_or_default();
+        Self {
+            monitor: None,
+            confirm_start: false,
+            paused: false,
+            skipped: 0,
+            rows: VecDeque::new(),
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            filter: String::new(),
+            regex: None,
+            regex_error: None,
+            export_path,
+            export_result: None,
+            error: None,
+        }
+    }
+}
+
+impl MonitorWindow {
+    fn start(&mut self, client: &Arc<ValkeyClient>, ctx: &Context) {
+        let ctx = ctx.clone();
+        match Monitor::new(client, move || ctx.request_repaint()) {
+            Ok(monitor) => {
+                self.monitor = Some(monitor);
+                self.paused = false;
+                self.skipped = 0;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn handle_entries(&mut self) {
+        let Some(monitor) = &self.monitor else {
+            return;
+        };
+        let entries = monitor.entries();
+        let closed = monitor.closed();
+        for entry in entries {
+            if self.paused {
+                self.skipped += 1;
+                continue;
+            }
+            let line = entry.to_string();
+            self.rows.push_back(MonitorRow { entry, line });
+        }
+        self.trim_rows();
+        if let Some(reason) = closed {
+            self.error = Some(reason);
+            self.monitor = None;
+        }
+    }
+
+    fn trim_rows(&mut self) {
+        let excess = self.rows.len().saturating_sub(self.buffer_size);
+        self.rows.drain(..excess);
+    }
+
+    fn update_filter(&mut self) {
+        self.regex = None;
+        self.regex_error = None;
+        if !self.filter.is_empty() {
+            match Regex::new(&self.filter) {
+                Ok(regex) => self.regex = Some(regex),
+                Err(e) => self.regex_error = Some(e.to_string()),
+            }
+        }
+    }
+
+    fn visible_rows(&self) -> Vec<&MonitorRow> {
+        self.rows
+            .iter()
+            .filter(|row| {
+                self.regex
+                    .as_ref()
+                    .is_none_or(|regex| regex.is_match(&row.line))
+            })
+            .collect()
+    }
+
+    /// Writes the shown entries to the export file, one `MONITOR` line each.
+    fn export(&self) -> Result<usize, Error> {
+        let rows = self.visible_rows();
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&self.export_path)?);
+        for row in &rows {
+            writeln!(file, "{}", row.line)?;
+        }
+        file.flush()?;
+        Ok(rows.len())
+    }
+
+    fn start_modal(&mut self, ctx: &Context, i18n: &I18N, valkey_client: &Arc<ValkeyClient>) {
+        let modal = Modal::new(Id::new("confirm_monitor")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading(i18n.get(LangKey::MonitorWarningTitle));
+            ui.add_space(8.0);
+            ui.label(i18n.get(LangKey::MonitorWarningText));
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button(i18n.get(LangKey::Start)).clicked() {
+                    self.start(valkey_client, ctx);
+                    self.confirm_start = false;
+                }
+                if ui.button(i18n.get(LangKey::Cancel)).clicked() {
+                    self.confirm_start = false;
+                }
+            });
+        });
+        if modal.should_close() {
+            self.confirm_start = false;
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui, i18n: &I18N) {
+        ui.horizontal(|ui| {
+            if self.monitor.is_some() {
+                if ui.button(i18n.get(LangKey::Stop)).clicked() {
+                    self.monitor = None;
+                }
+                let pause = if self.paused {
+                    LangKey::Resume
+                } else {
+                    LangKey::Pause
+                };
+                if ui.button(i18n.get(pause)).clicked() {
+                    self.paused = !self.paused;
+                }
+            } else if ui.button(i18n.get(LangKey::Start)).clicked() {
+                self.confirm_start = true;
+            }
+            ui.separator();
+            ui.label(i18n.get(LangKey::BufferSize));
+            if ui
+                .add(egui::DragValue::new(&mut self.buffer_size).range(1_000..=MAX_BUFFER_SIZE))
+                .changed()
+            {
+                self.trim_rows();
+            }
+            ui.separator();
+            let mut filter_edit = egui::TextEdit::singleline(&mut self.filter)
+                .desired_width(f32::INFINITY)
+                .font(egui::TextStyle::Monospace)
+                .hint_text(i18n.get(LangKey::FilterRegex));
+            if self.regex_error.is_some() {
+                filter_edit = filter_edit.text_color(ui.visuals().error_fg_color);
+            }
+            let response = ui.add(filter_edit);
+            if response.changed() {
+                self.update_filter();
+            }
+            if let Some(e) = &self.regex_error {
+                response.on_hover_text(e);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button(i18n.get(LangKey::Export)).clicked() {
+                self.export_result = Some(self.export().map_err(|e| e.to_string()));
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.export_path)
+                    .desired_width(ui.available_width() * 0.6),
+            );
+            match &self.export_result {
+                Some(Ok(count)) => {
+                    ui.label(format!("{} {count}", i18n.get(LangKey::ExportedEntries)));
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+                None => {}
+            }
+        });
+    }
+
+    fn entry_table(&self, ui: &mut egui::Ui, i18n: &I18N, rows: &[&MonitorRow]) {
+        let available_height = ui.available_height();
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .stick_to_bottom(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::initial(160.0).at_least(60.0).clip(true))
+            .column(Column::remainder().clip(true))
+            .min_scrolled_height(0.0)
+            .max_scroll_height(available_height)
+            .header(20.0, |mut header| {
+                for key in [
+                    LangKey::Time,
+                    LangKey::Database,
+                    LangKey::Client,
+                    LangKey::Command,
+                ] {
+                    header.col(|ui| {
+                        ui.strong(i18n.get(key));
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(20.0, rows.len(), |mut row| {
+                    let entry = &rows[row.index()].entry;
+                    row.col(|ui| {
+                        ui.monospace(format_timestamp(entry.timestamp));
+                    });
+                    row.col(|ui| {
+                        ui.label(entry.db.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.add(Label::new(&entry.client).truncate());
+                    });
+                    row.col(|ui| {
+                        let command = entry.command_line();
+                        ui.add(Label::new(egui::RichText::new(&command).monospace()).truncate())
+                            .on_hover_text(command);
+                    });
+                });
+            });
+    }
+}
+
+impl Component for MonitorWindow {
+    fn show(&mut self, ctx: &Context, state: &mut AppState) -> Result<(), Error> {
+        let Some(valkey_client) = state.valkey_client.clone() else {
+            return Ok(());
+        };
+        let i18n = state.i18n();
+        self.handle_entries();
+        if self.confirm_start {
+            self.start_modal(ctx, &i18n, &valkey_client);
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let shown = self.visible_rows().len();
+            egui::Sides::new().show(
+                ui,
+                |ui| ui.heading(i18n.get(LangKey::Monitor)),
+                |ui| {
+                    if ui.button(i18n.get(LangKey::Clear)).clicked() {
+                        self.rows.clear();
+                        self.skipped = 0;
+                    }
+                    ui.label(format!(
+                        "{shown} / {} {}",
+                        self.rows.len(),
+                        i18n.get(LangKey::EntriesShown)
+                    ));
+                    if self.skipped > 0 {
+                        ui.weak(format!(
+                            "{} {}",
+                            self.skipped,
+                            i18n.get(LangKey::SkippedWhilePaused)
+                        ));
+                    }
+                },
+            );
+            ui.separator();
+            self.controls(ui, &i18n);
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+            self.entry_table(ui, &i18n, &self.visible_rows());
+        });
+        Ok(())
+    }
+
+    fn refresh(&mut self, _: &Arc<ValkeyClient>) {}
+}
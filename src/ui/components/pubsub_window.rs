@@ -0,0 +1,510 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use crate::state::{AppState, Message};
+use crate::ui::Component;
+use crate::utils::{
+    Cmd, PubSubEvent, PubSubMessage, Subscriber, SubscriptionKind, ValkeyClient, display_bytes,
+    escape_bytes, format_timestamp, unescape_bytes,
+};
+use egui::{Align, Button, Context, Key, Label, Layout, ScrollArea};
+use egui_extras::{Column, TableBuilder};
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+/// The oldest messages are dropped beyond this, so a busy channel does not fill the memory.
+const MAX_MESSAGES: usize = 5000;
+
+#[derive(Default)]
+struct ActiveChannels {
+    channels: Vec<(Vec<u8>, i64)>,
+    shard_channels: Vec<(Vec<u8>, i64)>,
+}
+
+/// Console for the publish/subscribe messaging. The subscriptions use connections of their
+/// own and end when the window is left.
+#[derive(Default)]
+pub struct PubSubWindow {
+    /// Channels and patterns, on a standalone server the shard channels as well.
+    subscriber: Option<Subscriber>,
+    /// On a cluster a shard channel is only delivered by the node owning its slot, so each
+    /// one gets its own connection.
+    shard_subscribers: HashMap<Vec<u8>, Subscriber>,
+    /// Subscriptions confirmed by the server.
+    subscriptions: Vec<(SubscriptionKind, Vec<u8>)>,
+    messages: VecDeque<PubSubMessage>,
+    /// Why a subscription connection was closed. The subscriptions are kept to resubscribe.
+    lost: Option<String>,
+    /// Last request the server rejected.
+    error: Option<String>,
+    kind: SubscriptionKind,
+    channel: String,
+    filter: String,
+    publish_channel: String,
+    publish_message: String,
+    publish_sharded: bool,
+    publish_result: Arc<RwLock<Option<String>>>,
+    active_channels: Arc<RwLock<ActiveChannels>>,
+    is_fetching: Arc<RwLock<bool>>,
+    initial: bool,
+}
+
+impl PubSubWindow {
+    fn kind_label(kind: SubscriptionKind, i18n: &I18N) -> String {
+        i18n.get(match kind {
+            SubscriptionKind::Channel => LangKey::Channel,
+            SubscriptionKind::Pattern => LangKey::Pattern,
+            SubscriptionKind::Shard => LangKey::ShardChannel,
+        })
+    }
+
+    fn spawn_subscriber(
+        client: &Arc<ValkeyClient>,
+        ctx: &Context,
+        route: Option<Cmd>,
+    ) -> Subscriber {
+        let client = Arc::clone(client);
+        let ctx = ctx.clone();
+        Subscriber::new(
+            move || client.dedicated_connection(route.as_ref()),
+            move || ctx.request_repaint(),
+        )
+    }
+
+    fn subscribe(
+        &mut self,
+        client: &Arc<ValkeyClient>,
+        ctx: &Context,
+        kind: SubscriptionKind,
+        channel: Vec<u8>,
+    ) {
+        let subscriber = if kind == SubscriptionKind::Shard && client.is_cluster() {
+            self.shard_subscribers
+                .entry(channel.clone())
+                .or_insert_with(|| {
+                    Self::spawn_subscriber(client, ctx, Some(kind.subscribe_cmd(&channel)))
+                })
+        } else {
+            self.subscriber
+                .get_or_insert_with(|| Self::spawn_subscriber(client, ctx, None))
+        };
+        subscriber.subscribe(kind, &channel);
+    }
+
+    fn unsubscribe(&mut self, kind: SubscriptionKind, channel: &[u8]) {
+        if kind == SubscriptionKind::Shard && self.shard_subscribers.remove(channel).is_some() {
+            // Closing the connection ends the subscription, no reply follows.
+            self.subscriptions
+                .retain(|subscription| *subscription != (kind, channel.to_vec()));
+        } else if let Some(subscriber) = &self.subscriber {
+            subscriber.unsubscribe(kind, channel);
+        }
+    }
+
+    /// Opens new connections for all subscriptions after a connection was closed.
+    fn resubscribe(&mut self, client: &Arc<ValkeyClient>, ctx: &Context) {
+        self.subscriber = None;
+        self.shard_subscribers.clear();
+        self.lost = None;
+        for (kind, channel) in std::mem::take(&mut self.subscriptions) {
+            self.subscribe(client, ctx, kind, channel);
+        }
+    }
+
+    fn handle_events(&mut self) {
+        let events: Vec<PubSubEvent> = self
+            .subscriber
+            .iter()
+            .chain(self.shard_subscribers.values())
+            .flat_map(Subscriber::events)
+            .collect();
+        for event in events {
+            match event {
+                PubSubEvent::Message(message) => {
+                    if self.messages.len() >= MAX_MESSAGES {
+                        self.messages.pop_front();
+                    }
+                    self.messages.push_back(message);
+                }
+                PubSubEvent::Subscribed { kind, channel, .. } => {
+                    if !self.subscriptions.contains(&(kind, channel.clone())) {
+                        self.subscriptions.push((kind, channel));
+                    }
+                }
+                PubSubEvent::Unsubscribed { kind, channel, .. } => {
+                    self.subscriptions
+                        .retain(|subscription| *subscription != (kind, channel.clone()));
+                }
+                PubSubEvent::Error(message) => self.error = Some(message),
+                PubSubEvent::Closed(reason) => self.lost = Some(reason),
+                PubSubEvent::Invalidated(_) => {}
+            }
+        }
+    }
+
+    fn is_match(message: &PubSubMessage, filter: &str) -> bool {
+        filter.is_empty()
+            || display_bytes(&message.channel)
+                .to_lowercase()
+                .contains(filter)
+            || display_bytes(&message.payload)
+                .to_lowercase()
+                .contains(filter)
+    }
+
+    /// Active channels with their number of subscribers. On a cluster the counts are those
+    /// of the default node.
+    fn fetch_active_channels(
+        &self,
+        client: &Arc<ValkeyClient>,
+        ctx: &Context,
+        sender: Arc<Sender<Message>>,
+    ) {
+        if self.is_fetching.read().map_or(true, |guard| *guard) {
+            return;
+        }
+        if let Ok(mut guard) = self.is_fetching.write() {
+            *guard = true;
+        }
+        let client = Arc::clone(client);
+        let ctx = ctx.clone();
+        let active_channels = Arc::clone(&self.active_channels);
+        let is_fetching = Arc::clone(&self.is_fetching);
+
+        thread::spawn(move || {
+            let subscribers = |list: &str, numsub: &str| -> Result<Vec<(Vec<u8>, i64)>, Error> {
+                let channels = client.exec(&Cmd::new("PUBSUB").arg(list))?.into_bytes_vec();
+                if channels.is_empty() {
+                    return Ok(Vec::new());
+                }
+                let counts = client
+                    .exec(&Cmd::new("PUBSUB").arg(numsub).args(&channels))?
+                    .into_bytes_vec();
+                let mut subscribers: Vec<(Vec<u8>, i64)> = counts
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        let count = String::from_utf8_lossy(&pair[1]).parse().unwrap_or(0);
+                        (pair[0].clone(), count)
+                    })
+                    .collect();
+                subscribers.sort();
+                Ok(subscribers)
+            };
+
+            let channels = subscribers("CHANNELS", "NUMSUB").unwrap_or_else(|e| {
+                e.show_error_dialog(sender.clone());
+                Vec::new()
+            });
+            // Shard channels exist since Valkey 7, older servers reject the command.
+            let shard_channels = subscribers("SHARDCHANNELS", "SHARDNUMSUB").unwrap_or_default();
+
+            if let Ok(mut guard) = active_channels.write() {
+                *guard = ActiveChannels {
+                    channels,
+                    shard_channels,
+                };
+            }
+            if let Ok(mut guard) = is_fetching.write() {
+                *guard = false;
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn publish(&self, client: &Arc<ValkeyClient>, ctx: &Context, state: &AppState) {
+        let cmd = Cmd::new(if self.publish_sharded {
+            "SPUBLISH"
+        } else {
+            "PUBLISH"
+        })
+        .arg(unescape_bytes(&self.publish_channel))
+        .arg(unescape_bytes(&self.publish_message));
+        let client = Arc::clone(client);
+        let ctx = ctx.clone();
+        let sender = state.get_sender();
+        let i18n = state.i18n();
+        let publish_result = Arc::clone(&self.publish_result);
+
+        thread::spawn(move || {
+            let result = match client.exec(&cmd) {
+                Ok(receivers) => {
+                    format!("{} {receivers}", i18n.get(LangKey::PublishedToReceivers))
+                }
+                Err(e @ Error::Server { .. }) => e.to_string(),
+                Err(e) => return e.show_error_dialog(sender),
+            };
+            if let Ok(mut guard) = publish_result.write() {
+                *guard = Some(result);
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn channel_list(&mut self, ui: &mut egui::Ui, kind: SubscriptionKind, shard: bool) {
+        let channels = self
+            .active_channels
+            .read()
+            .map(|active_channels| {
+                if shard {
+                    active_channels.shard_channels.clone()
+                } else {
+                    active_channels.channels.clone()
+                }
+            })
+            .unwrap_or_default();
+        for (channel, subscribers) in channels {
+            ui.horizontal(|ui| {
+                if ui.add(egui::Link::new(display_bytes(&channel))).clicked() {
+                    self.kind = kind;
+                    self.channel = escape_bytes(&channel);
+                    self.publish_channel = escape_bytes(&channel);
+                    self.publish_sharded = shard;
+                }
+                ui.weak(subscribers.to_string());
+            });
+        }
+    }
+
+    fn active_channels_panel(
+        &mut self,
+        ctx: &Context,
+        client: &Arc<ValkeyClient>,
+        state: &AppState,
+    ) {
+        let i18n = state.i18n();
+        egui::SidePanel::right("pubsub_active_channels")
+            .default_width(220.0)
+            .show(ctx, |ui| {
+                egui::Sides::new().show(
+                    ui,
+                    |ui| ui.strong(i18n.get(LangKey::ActiveChannels)),
+                    |ui| {
+                        if ui
+                            .button("↻")
+                            .on_hover_text(i18n.get(LangKey::Refresh))
+                            .clicked()
+                        {
+                            self.fetch_active_channels(client, ctx, state.get_sender());
+                        }
+                    },
+                );
+                ui.weak(i18n.get(LangKey::Subscribers));
+                ui.separator();
+                ScrollArea::vertical()
+                    .id_salt("pubsub_active_channels_scroll")
+                    .show(ui, |ui| {
+                        self.channel_list(ui, SubscriptionKind::Channel, false);
+                        ui.separator();
+                        ui.strong(i18n.get(LangKey::ActiveShardChannels));
+                        self.channel_list(ui, SubscriptionKind::Shard, true);
+                    });
+            });
+    }
+
+    fn message_table(&self, ui: &mut egui::Ui, i18n: &I18N) {
+        let filter = self.filter.to_lowercase();
+        let messages: Vec<&PubSubMessage> = self
+            .messages
+            .iter()
+            .filter(|message| Self::is_match(message, &filter))
+            .collect();
+
+        let available_height = ui.available_height();
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .stick_to_bottom(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::auto())
+            .column(Column::initial(180.0).at_least(60.0).clip(true))
+            .column(Column::remainder().clip(true))
+            .min_scrolled_height(0.0)
+            .max_scroll_height(available_height)
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong(i18n.get(LangKey::Time));
+                });
+                header.col(|ui| {
+                    ui.strong(i18n.get(LangKey::Channel));
+                });
+                header.col(|ui| {
+                    ui.strong(i18n.get(LangKey::Message));
+                });
+            })
+            .body(|body| {
+                body.rows(20.0, messages.len(), |mut row| {
+                    let message = messages[row.index()];
+                    row.col(|ui| {
+                        ui.monospace(format_timestamp(message.received));
+                    });
+                    row.col(|ui| {
+                        let channel = match &message.pattern {
+                            Some(pattern) => format!(
+                                "{} ({})",
+                                display_bytes(&message.channel),
+                                display_bytes(pattern)
+                            ),
+                            None => display_bytes(&message.channel),
+                        };
+                        ui.add(Label::new(channel).truncate());
+                    });
+                    row.col(|ui| {
+                        ui.add(Label::new(display_bytes(&message.payload)).truncate());
+                    });
+                });
+            });
+    }
+}
+
+impl Component for PubSubWindow {
+    fn show(&mut self, ctx: &Context, state: &mut AppState) -> Result<(), Error> {
+        let Some(valkey_client) = state.valkey_client.clone() else {
+            return Ok(());
+        };
+        let i18n = state.i18n();
+        if !self.initial {
+            self.fetch_active_channels(&valkey_client, ctx, state.get_sender());
+            self.initial = true;
+        }
+        self.handle_events();
+        self.active_channels_panel(ctx, &valkey_client, state);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::Sides::new().show(
+                ui,
+                |ui| ui.heading(i18n.get(LangKey::PubSub)),
+                |ui| {
+                    if ui.button(i18n.get(LangKey::Clear)).clicked() {
+                        self.messages.clear();
+                    }
+                    ui.label(format!(
+                        "{} {}",
+                        self.messages.len(),
+                        i18n.get(LangKey::MessagesShown)
+                    ));
+                },
+            );
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::new(ui.id().with("subscription_kind"), "")
+                    .selected_text(Self::kind_label(self.kind, &i18n))
+                    .show_ui(ui, |ui| {
+                        for kind in SubscriptionKind::ALL {
+                            ui.selectable_value(
+                                &mut self.kind,
+                                kind,
+                                Self::kind_label(kind, &i18n),
+                            );
+                        }
+                    });
+                let response = ui.add_sized(
+                    [ui.available_width() - 120.0, 0.0],
+                    egui::TextEdit::singleline(&mut self.channel)
+                        .hint_text(Self::kind_label(self.kind, &i18n)),
+                );
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                if (ui
+                    .add_sized([110.0, 0.0], Button::new(i18n.get(LangKey::Subscribe)))
+                    .clicked()
+                    || submitted)
+                    && !self.channel.is_empty()
+                {
+                    let channel = unescape_bytes(&self.channel);
+                    self.subscribe(&valkey_client, ctx, self.kind, channel);
+                    self.error = None;
+                }
+            });
+
+            let mut unsubscribe = None;
+            ui.horizontal_wrapped(|ui| {
+                ui.label(format!("{}:", i18n.get(LangKey::Subscriptions)));
+                if self.subscriptions.is_empty() {
+                    ui.weak(i18n.get(LangKey::NoSubscriptions));
+                }
+                for (kind, channel) in &self.subscriptions {
+                    ui.group(|ui| {
+                        ui.label(format!(
+                            "{}: {}",
+                            Self::kind_label(*kind, &i18n),
+                            display_bytes(channel)
+                        ));
+                        if ui
+                            .small_button("✖")
+                            .on_hover_text(i18n.get(LangKey::Unsubscribe))
+                            .clicked()
+                        {
+                            unsubscribe = Some((*kind, channel.clone()));
+                        }
+                    });
+                }
+            });
+            if let Some((kind, channel)) = unsubscribe {
+                self.unsubscribe(kind, &channel);
+            }
+
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            if let Some(reason) = self.lost.clone() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!("{} {reason}", i18n.get(LangKey::SubscriptionLost)),
+                    );
+                    if ui.button(i18n.get(LangKey::Resubscribe)).clicked() {
+                        self.resubscribe(&valkey_client, ctx);
+                    }
+                });
+            }
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.add_sized(
+                    [180.0, 0.0],
+                    egui::TextEdit::singleline(&mut self.publish_channel)
+                        .hint_text(i18n.get(LangKey::Channel)),
+                );
+                ui.add_sized(
+                    [(ui.available_width() - 220.0).max(100.0), 0.0],
+                    egui::TextEdit::singleline(&mut self.publish_message)
+                        .hint_text(i18n.get(LangKey::Message)),
+                );
+                ui.checkbox(&mut self.publish_sharded, i18n.get(LangKey::Sharded));
+                if ui
+                    .add_enabled(
+                        !self.publish_channel.is_empty(),
+                        Button::new(i18n.get(LangKey::Publish)),
+                    )
+                    .clicked()
+                {
+                    self.publish(&valkey_client, ctx, state);
+                }
+            });
+            if let Some(result) = self
+                .publish_result
+                .read()
+                .ok()
+                .and_then(|guard| guard.clone())
+            {
+                ui.weak(result);
+            }
+            ui.separator();
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .desired_width(f32::INFINITY)
+                    .hint_text(i18n.get(LangKey::FilterMessages)),
+            );
+            ui.separator();
+
+            self.message_table(ui, &i18n);
+        });
+        Ok(())
+    }
+
+    fn refresh(&mut self, _: &Arc<ValkeyClient>) {
+        self.initial = false;
+    }
+}
@@ -2,21 +2,108 @@ use crate::errors::Error;
 use crate::i18n::LangKey;
 use crate::state::{AppState, ResultViewMode};
 use crate::ui::Component;
-use crate::utils::ValkeyClient;
-use egui::{Button, Context, Key};
+use crate::ui::widgets::TypedConfirmation;
+use crate::utils::valkey::ToVec;
+use crate::utils::{CallOptions, CancelHandle, Cmd, DEFAULT_TIMEOUT, ValkeyClient, ValkeyValue};
+use egui::{Button, Context, Id, Key, Modal};
 use egui_extras::{Column, TableBuilder};
 use std::sync::{Arc, RwLock};
 use std::thread;
+use std::time::Duration;
+
+/// A script as typed and its lines that run, each with the command parsed from it.
+struct Script {
+    text: String,
+    lines: Vec<(String, Cmd)>,
+}
+
+impl Script {
+    /// Skips empty lines and comments. Fails with the number and the text of the first line
+    /// with an unterminated quote, the script is refused as a whole then.
+    fn parse(text: String) -> Result<Self, (usize, String)> {
+        let mut lines = Vec::new();
+        let runnable = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        for (idx, line) in runnable.enumerate() {
+            match Cmd::parse(line) {
+                Ok(cmd) => lines.push((line.to_string(), cmd)),
+                Err(_) => return Err((idx + 1, line.to_string())),
+            }
+        }
+        Ok(Self { text, lines })
+    }
+}
 
-#[derive(Default)]
 pub struct WorkbenchWindow {
     resp_result: Arc<RwLock<String>>,
     resp_data: Arc<RwLock<Vec<String>>>,
     is_executing: Arc<RwLock<bool>>,
     result_display: String,
+    /// Stops the running script, replaced for every run.
+    cancel: CancelHandle,
+    /// Timeout of each command in seconds, 0 waits until the reply or the stop button.
+    timeout_secs: u32,
+    /// Script with destructive commands waiting for the alias of the production connection.
+    pending_confirmation: Option<(Script, TypedConfirmation)>,
+    confirmed_command: Option<Script>,
+}
+
+impl Default for WorkbenchWindow {
+    fn default() -> Self {
+        Self {
+            resp_result: Default::default(),
+            resp_data: Default::default(),
+            is_executing: Default::default(),
+            result_display: String::new(),
+            cancel: CancelHandle::default(),
+            timeout_secs: DEFAULT_TIMEOUT.as_secs() as u32,
+            pending_confirmation: None,
+            confirmed_command: None,
+        }
+    }
 }
 
 impl WorkbenchWindow {
+    /// Runs the script with destructive commands once the alias is typed.
+    fn confirmation_modal(&mut self, ctx: &Context, state: &AppState) {
+        let Some((_, confirmation)) = &mut self.pending_confirmation else {
+            return;
+        };
+        let i18n = state.i18n();
+        let mut close = false;
+        let mut run = false;
+        let modal = Modal::new(Id::new("confirm_destructive_command")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading(i18n.get(LangKey::AreYouSure));
+            ui.add_space(8.0);
+            ui.label(i18n.get(LangKey::DestructiveCommand));
+            ui.add_space(8.0);
+            let confirmed = confirmation.ui(ui, &i18n);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                close = ui.button(i18n.get(LangKey::Cancel)).clicked();
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add_enabled(confirmed, Button::new(i18n.get(LangKey::Exec)))
+                        .clicked()
+                    {
+                        run = true;
+                        ctx.request_repaint();
+                        close = true;
+                    }
+                });
+            });
+        });
+        if close || modal.should_close() {
+            let pending = self.pending_confirmation.take();
+            if run {
+                self.confirmed_command = pending.map(|(script, _)| script);
+            }
+        }
+    }
+
     fn add_to_history(&self, state: &mut AppState, command: String) {
         if !command.trim().is_empty()
             && (state.workbench_state.command_history.is_empty()
@@ -98,6 +185,11 @@ impl Component for WorkbenchWindow {
                             .strong()
                             .size(14.0),
                         );
+                        if valkey_client.is_read_only() {
+                            ui.separator();
+                            ui.label(state.i18n().get(LangKey::ReadOnly))
+                                .on_hover_text(state.i18n().get(LangKey::ReadOnlyConnectionHint));
+                        }
                     });
                     ui.add_space(4.0);
                 });
@@ -275,26 +367,86 @@ impl Component for WorkbenchWindow {
                             !is_executing && !state.workbench_state.resp_command.trim().is_empty();
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.add_sized([100.0, button_height], Button::new(button_text))
+                            let clicked = ui
+                                .add_sized([100.0, button_height], Button::new(button_text))
+                                .clicked()
+                                && button_enabled;
+                            if ui
+                                .add_enabled(
+                                    is_executing,
+                                    Button::new(state.i18n().get(LangKey::Stop))
+                                        .min_size([60.0, button_height].into()),
+                                )
                                 .clicked()
-                                && button_enabled
+                            {
+                                self.cancel.cancel();
+                            }
+                            ui.add_space(8.0);
+                            ui.add_enabled(
+                                !is_executing,
+                                egui::DragValue::new(&mut self.timeout_secs)
+                                    .range(0..=3600)
+                                    .suffix(" s"),
+                            )
+                            .on_hover_text(state.i18n().get(LangKey::CommandTimeoutHint));
+                            ui.label(state.i18n().get(LangKey::CommandTimeout));
+                            clicked
                         })
                         .inner
                     })
                     .inner;
 
-                if execute_command
+                let requested = execute_command
                     || (enter_pressed
                         && !is_executing
-                        && !state.workbench_state.resp_command.trim().is_empty())
-                {
-                    let command = state.workbench_state.resp_command.trim().to_string();
-                    self.add_to_history(state, command.clone());
+                        && !state.workbench_state.resp_command.trim().is_empty());
+                let script = match self.confirmed_command.take() {
+                    Some(script) => Some(script),
+                    None if requested => {
+                        let command = state.workbench_state.resp_command.trim().to_string();
+                        match (Script::parse(command), state.production_alias()) {
+                            // A script is refused as a whole, so no line runs without the
+                            // ones before it.
+                            (Err((number, line)), _) => {
+                                if let Ok(mut guard) = self.resp_result.write() {
+                                    *guard = format!(
+                                        "{number}. {line}: {}",
+                                        state.i18n().get(LangKey::UnterminatedQuote)
+                                    );
+                                }
+                                None
+                            }
+                            (Ok(script), Some(alias))
+                                if script
+                                    .lines
+                                    .iter()
+                                    .any(|(_, cmd)| state.command_registry.is_destructive(cmd)) =>
+                            {
+                                self.pending_confirmation =
+                                    Some((script, TypedConfirmation::new(alias)));
+                                None
+                            }
+                            (Ok(script), _) => Some(script),
+                        }
+                    }
+                    None => None,
+                };
+                if let Some(script) = script {
+                    self.add_to_history(state, script.text.clone());
 
                     let res_result = self.resp_result.clone();
                     let res_data = self.resp_data.clone();
                     let is_executing_clone = self.is_executing.clone();
                     let ctx_clone = ctx.clone();
+                    self.cancel = CancelHandle::new();
+                    let cancel = self.cancel.clone();
+                    let options = CallOptions {
+                        timeout: Some(match self.timeout_secs {
+                            0 => Duration::MAX,
+                            secs => Duration::from_secs(secs.into()),
+                        }),
+                        cancel: Some(cancel.clone()),
+                    };
 
                     if let Ok(mut guard) = is_executing_clone.write() {
                         *guard = true;
@@ -305,51 +457,112 @@ impl Component for WorkbenchWindow {
                     ctx.request_repaint();
 
                     let i18n = state.i18n();
+                    let command_registry = state.command_registry.clone();
                     thread::spawn(move || {
-                        let commands: Vec<&str> = command
-                            .lines()
-                            .map(|line| line.trim())
-                            .filter(|line| !line.is_empty() && !line.starts_with('#'))
-                            .collect();
+                        let (commands, cmds): (Vec<String>, Vec<Cmd>) =
+                            script.lines.into_iter().unzip();
 
                         let mut all_results = Vec::new();
                         let mut all_responses = Vec::new();
 
-                        for (idx, cmd) in commands.iter().enumerate() {
-                            let result = match valkey_client.exec(cmd) {
-                                Ok(response) => {
-                                    all_responses.extend(response.clone());
-
-                                    if response.is_empty() {
+                        let mut run = |exec: &mut dyn FnMut(&Cmd) -> Result<ValkeyValue, Error>| {
+                            for (idx, (cmd, parsed)) in commands.iter().zip(&cmds).enumerate() {
+                                if cancel.is_cancelled() {
+                                    all_results.push(format!(
+                                        "{}. {}: {}",
+                                        idx + 1,
+                                        cmd,
+                                        i18n.get(LangKey::CommandStopped)
+                                    ));
+                                    break;
+                                }
+                                let result = match exec(parsed) {
+                                    Ok(response) => {
+                                        let response = response.to_vec();
+                                        all_responses.extend(response.clone());
+
+                                        if response.is_empty() {
+                                            format!(
+                                                "{}. {}: {}",
+                                                idx + 1,
+                                                cmd,
+                                                i18n.get(LangKey::NoResponse)
+                                            )
+                                        } else if response.len() == 1 {
+                                            format!("{}. {}: {}", idx + 1, cmd, response[0])
+                                        } else {
+                                            let items = response
+                                                .iter()
+                                                .enumerate()
+                                                .map(|(i, item)| {
+                                                    format!("   {}) \"{}\"", i + 1, item)
+                                                })
+                                                .collect::<Vec<String>>()
+                                                .join("\n");
+                                            format!("{}. {}:\n{}", idx + 1, cmd, items)
+                                        }
+                                    }
+                                    // Error replies and refused writes are results of the script, not failures.
+                                    Err(e @ (Error::Server { .. } | Error::ReadOnly(_))) => {
+                                        match e.hint(&i18n) {
+                                            Some(hint) => {
+                                                format!("{}. {}: {e}\n   {hint}", idx + 1, cmd)
+                                            }
+                                            None => format!("{}. {}: {e}", idx + 1, cmd),
+                                        }
+                                    }
+                                    Err(_) if cancel.is_cancelled() => format!(
+                                        "{}. {}: {}",
+                                        idx + 1,
+                                        cmd,
+                                        i18n.get(LangKey::CommandStopped)
+                                    ),
+                                    Err(e) => {
+                                        e.show_error_dialog(sender.clone());
                                         format!(
                                             "{}. {}: {}",
                                             idx + 1,
                                             cmd,
-                                            i18n.get(LangKey::NoResponse)
+                                            i18n.get(LangKey::AnErrorOccurred)
                                         )
-                                    } else if response.len() == 1 {
-                                        format!("{}. {}: {}", idx + 1, cmd, response[0])
-                                    } else {
-                                        let items = response
-                                            .iter()
-                                            .enumerate()
-                                            .map(|(i, item)| format!("   {}) \"{}\"", i + 1, item))
-                                            .collect::<Vec<String>>()
-                                            .join("\n");
-                                        format!("{}. {}:\n{}", idx + 1, cmd, items)
                                     }
-                                }
-                                Err(e) => {
-                                    e.show_error_dialog(sender.clone());
-                                    format!(
-                                        "{}. {}: {}",
-                                        idx + 1,
-                                        cmd,
-                                        i18n.get(LangKey::AnErrorOccurred)
-                                    )
-                                }
-                            };
-                            all_results.push(result);
+                                };
+                                all_results.push(result);
+                            }
+                        };
+
+                        // All lines share one connection so MULTI/EXEC and SELECT apply to
+                        // the following lines. Blocking commands get their own connection.
+                        // On a cluster, lines outside a transaction are routed one by one
+                        // and a transaction runs on the node of its first key.
+                        let blocking = cmds.iter().any(|cmd| command_registry.is_blocking(cmd));
+                        let transaction = cmds.iter().any(|cmd| cmd.name() == "MULTI");
+                        let route = cmds.iter().find(|cmd| cmd.name() != "MULTI");
+                        let executed = if valkey_client.is_cluster() && !transaction && !blocking {
+                            run(&mut |cmd| valkey_client.exec_with(cmd, &options));
+                            Ok(())
+                        } else if blocking {
+                            valkey_client
+                                .dedicated_connection(route)
+                                .map(|mut connection| {
+                                    run(&mut |cmd| {
+                                        connection
+                                            .exec_with(cmd, &options)
+                                            .and_then(ValkeyValue::into_result)
+                                    })
+                                })
+                        } else {
+                            valkey_client.connection(route).map(|mut connection| {
+                                run(&mut |cmd| {
+                                    connection
+                                        .exec_with(cmd, &options)
+                                        .and_then(ValkeyValue::into_result)
+                                })
+                            })
+                        };
+                        if let Err(e) = executed {
+                            e.show_error_dialog(sender.clone());
+                            all_results.push(i18n.get(LangKey::AnErrorOccurred));
                         }
 
                         if let Ok(mut guard) = res_data.write() {
@@ -681,8 +894,41 @@ impl Component for WorkbenchWindow {
                 }
             });
         }
+        if self.pending_confirmation.is_some() {
+            self.confirmation_modal(ctx, state);
+        }
         Ok(())
     }
 
     fn refresh(&mut self, _: &Arc<ValkeyClient>) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() {
+        let script = Script::parse("# setup\nset k \"a b\"\n\n  get k  ".to_string()).unwrap();
+        let lines: Vec<(&str, &[Vec<u8>])> = script
+            .lines
+            .iter()
+            .map(|(line, cmd)| (line.as_str(), cmd.arguments()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (
+                    "set k \"a b\"",
+                    [b"set".to_vec(), b"k".to_vec(), b"a b".to_vec()].as_slice()
+                ),
+                ("get k", [b"get".to_vec(), b"k".to_vec()].as_slice()),
+            ]
+        );
+
+        assert_eq!(
+            Script::parse("set a 1\nset b \"2\nset c 3".to_string()).err(),
+            Some((2, "set b \"2".to_string()))
+        );
+    }
+}
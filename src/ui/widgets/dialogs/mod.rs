@@ -1,3 +1,5 @@
 mod confirm_dialog;
+mod typed_confirmation;
 
 pub use confirm_dialog::ConfirmDialog;
+pub use typed_confirmation::TypedConfirmation;
@@ -0,0 +1,39 @@
+use crate::i18n::{I18N, LangKey};
+use egui::Ui;
+
+/// Asks to type the alias of a production connection before a destructive action.
+pub struct TypedConfirmation {
+    expected: String,
+    input: String,
+}
+
+impl TypedConfirmation {
+    pub fn new(expected: impl Into<String>) -> Self {
+        Self {
+            expected: expected.into(),
+            input: String::new(),
+        }
+    }
+
+    /// Shows the prompt with the input, returns whether the typed text matches.
+    pub fn ui(&mut self, ui: &mut Ui, i18n: &I18N) -> bool {
+        ui.colored_label(
+            ui.visuals().error_fg_color,
+            i18n.get(LangKey::ProductionConnection),
+        );
+        ui.horizontal(|ui| {
+            ui.label(i18n.get(LangKey::TypeToConfirm));
+            ui.strong(&self.expected);
+        });
+        ui.add(
+            egui::TextEdit::singleline(&mut self.input)
+                .desired_width(ui.available_width())
+                .hint_text(&self.expected),
+        );
+        self.is_confirmed()
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.input == self.expected
+    }
+}
@@ -5,6 +5,8 @@ use std::fmt::{Display, Formatter};
 pub struct ErrorModal {
     pub title: String,
     pub error_message: String,
+    /// Likely cause of the error, shown below the message.
+    pub hint: Option<String>,
     pub open: bool,
 }
 
@@ -23,6 +25,7 @@ where
         Self {
             title: error_ref.error_type(),
             error_message: error_ref.to_string(),
+            hint: None,
             open: true,
         }
     }
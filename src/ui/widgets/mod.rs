@@ -6,7 +6,7 @@ mod popup;
 mod popups;
 mod shimmer;
 
-pub use dialogs::ConfirmDialog;
+pub use dialogs::{ConfirmDialog, TypedConfirmation};
 pub use error_modal::ErrorModal;
 pub use info_modal::InfoModal;
 pub use modal::Modal;
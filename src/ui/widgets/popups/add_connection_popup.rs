@@ -1,25 +1,68 @@
 use crate::errors::Error;
 use crate::i18n::{I18N, LangKey};
-use crate::state::Event::{AddServer, DeleteServer, ShowInfo};
+use crate::state::Event::{AddServer, ShowInfo, UpdateServer};
 use crate::state::Message::Event;
 use crate::state::{Info, Message};
 use crate::ui::widgets::popups::PopupUi;
-use crate::utils::{ValkeyClient, ValkeyUrl, ValkeyUrlBuilder};
+use crate::utils::{
+    ConnectionProfile, ProxyKind, ProxyOptions, SshOptions, TlsOptions, ValkeyClient, ValkeyUrl,
+    ValkeyUrlBuilder,
+};
 use egui::Ui;
 use egui::mutex::RwLock;
 use std::sync::Arc;
 use std::sync::mpsc::Sender;
 use std::thread;
 
+/// How the server is reached.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Tcp,
+    Socket,
+    Sentinel,
+}
+
 pub struct AddConnectionPopup {
     connection_string: String,
     alias: String,
     old_alias: Option<String>,
+    /// The edited profile, its display settings are kept when saving.
+    profile: ConnectionProfile,
+    group: String,
+    /// Comma separated.
+    tags: String,
+    color: Option<[u8; 3]>,
+    production: bool,
+    read_only: bool,
     host: String,
     port: String,
     username: String,
     password: String,
     database_index: String,
+    transport: Transport,
+    socket_path: String,
+    sentinels: String,
+    master_name: String,
+    tls: bool,
+    tls_ca_file: String,
+    tls_client_cert: String,
+    tls_client_key: String,
+    tls_server_name: String,
+    tls_insecure: bool,
+    proxy: bool,
+    proxy_kind: ProxyKind,
+    proxy_host: String,
+    proxy_port: String,
+    proxy_username: String,
+    proxy_password: String,
+    ssh: bool,
+    ssh_host: String,
+    ssh_port: String,
+    ssh_username: String,
+    ssh_password: String,
+    ssh_key_file: String,
+    ssh_known_hosts: String,
+    ssh_accept_new: bool,
     show_password: (bool, bool),
     connection_string_focus: bool,
     connected: Arc<RwLock<bool>>,
@@ -30,11 +73,41 @@ impl Default for AddConnectionPopup {
             connection_string: "".to_string(),
             alias: "".to_string(),
             old_alias: None,
+            profile: ConnectionProfile::default(),
+            group: "".to_string(),
+            tags: "".to_string(),
+            color: None,
+            production: false,
+            read_only: false,
             host: "".to_string(),
             port: "".to_string(),
             username: "".to_string(),
             password: "".to_string(),
             database_index: "".to_string(),
+            transport: Transport::Tcp,
+            socket_path: "".to_string(),
+            sentinels: "".to_string(),
+            master_name: "".to_string(),
+            tls: false,
+            tls_ca_file: "".to_string(),
+            tls_client_cert: "".to_string(),
+            tls_client_key: "".to_string(),
+            tls_server_name: "".to_string(),
+            tls_insecure: false,
+            proxy: false,
+            proxy_kind: ProxyKind::Socks5,
+            proxy_host: "".to_string(),
+            proxy_port: "".to_string(),
+            proxy_username: "".to_string(),
+            proxy_password: "".to_string(),
+            ssh: false,
+            ssh_host: "".to_string(),
+            ssh_port: "".to_string(),
+            ssh_username: "".to_string(),
+            ssh_password: "".to_string(),
+            ssh_key_file: "".to_string(),
+            ssh_known_hosts: "".to_string(),
+            ssh_accept_new: false,
             show_password: (false, true),
             connection_string_focus: false,
             connected: Default::default(),
@@ -111,22 +184,96 @@ impl PopupUi for AddConnectionPopup {
         );
         ui.horizontal(|ui| {
             ui.vertical(|ui| {
-                ui.label(format!("{}*", i18n.get(LangKey::Host)));
+                ui.label(i18n.get(LangKey::Group));
                 ui.add(
-                    egui::TextEdit::singleline(&mut self.host)
-                        .desired_width(ui.available_width() * 0.7)
-                        .hint_text("127.0.0.1"),
+                    egui::TextEdit::singleline(&mut self.group)
+                        .desired_width(ui.available_width() * 0.4),
                 );
             });
             ui.vertical(|ui| {
-                ui.label(i18n.get(LangKey::Port));
+                ui.label(i18n.get(LangKey::Tags));
                 ui.add(
-                    egui::TextEdit::singleline(&mut self.port)
+                    egui::TextEdit::singleline(&mut self.tags)
                         .desired_width(ui.available_width())
-                        .hint_text("6379"),
+                        .hint_text(i18n.get(LangKey::TagsHint)),
                 );
-            })
+            });
+        });
+        ui.horizontal(|ui| {
+            let mut use_color = self.color.is_some();
+            if ui
+                .checkbox(&mut use_color, i18n.get(LangKey::Color))
+                .changed()
+            {
+                self.color = use_color.then_some([190, 30, 30]);
+            }
+            if let Some(color) = &mut self.color {
+                ui.color_edit_button_srgb(color);
+            }
+            ui.separator();
+            ui.checkbox(&mut self.production, i18n.get(LangKey::Production));
+            ui.checkbox(&mut self.read_only, i18n.get(LangKey::ReadOnly))
+                .on_hover_text(i18n.get(LangKey::ReadOnlyConnectionText));
         });
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.transport, Transport::Tcp, "TCP/IP");
+            ui.radio_value(
+                &mut self.transport,
+                Transport::Socket,
+                i18n.get(LangKey::UnixSocket),
+            );
+            ui.radio_value(
+                &mut self.transport,
+                Transport::Sentinel,
+                i18n.get(LangKey::Sentinel),
+            );
+        });
+        if self.transport == Transport::Socket {
+            ui.label(format!("{}*", i18n.get(LangKey::SocketPath)));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.socket_path)
+                    .desired_width(ui.available_width())
+                    .hint_text("/var/run/valkey/valkey.sock"),
+            );
+        } else if self.transport == Transport::Sentinel {
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(format!("{}*", i18n.get(LangKey::Sentinels)));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.sentinels)
+                            .desired_width(ui.available_width() * 0.7)
+                            .hint_text("10.0.0.1:26379, 10.0.0.2:26379"),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(format!("{}*", i18n.get(LangKey::MasterName)));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.master_name)
+                            .desired_width(ui.available_width())
+                            .hint_text("mymaster"),
+                    );
+                })
+            });
+        } else {
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(format!("{}*", i18n.get(LangKey::Host)));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.host)
+                            .desired_width(ui.available_width() * 0.7)
+                            .hint_text("127.0.0.1"),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::Port));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.port)
+                            .desired_width(ui.available_width())
+                            .hint_text("6379"),
+                    );
+                })
+            });
+        }
         ui.horizontal(|ui| {
             ui.vertical(|ui| {
                 ui.label(i18n.get(LangKey::Username));
@@ -159,6 +306,167 @@ impl PopupUi for AddConnectionPopup {
                     .hint_text("0"),
             );
         });
+        if self.transport != Transport::Socket {
+            ui.checkbox(&mut self.tls, i18n.get(LangKey::UseTls));
+        }
+        if self.tls && self.transport != Transport::Socket {
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::CaCertificate));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.tls_ca_file)
+                            .desired_width(ui.available_width() * 0.5)
+                            .hint_text("/etc/valkey/tls/ca.crt"),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::TlsServerName));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.tls_server_name)
+                            .desired_width(ui.available_width())
+                            .hint_text("valkey.example.com"),
+                    );
+                });
+            });
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::ClientCertificate));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.tls_client_cert)
+                            .desired_width(ui.available_width() * 0.5)
+                            .hint_text("/etc/valkey/tls/client.crt"),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::ClientKey));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.tls_client_key)
+                            .desired_width(ui.available_width())
+                            .hint_text("/etc/valkey/tls/client.key"),
+                    );
+                });
+            });
+            ui.checkbox(&mut self.tls_insecure, i18n.get(LangKey::TlsInsecure));
+        }
+        if self.transport != Transport::Socket {
+            ui.checkbox(&mut self.proxy, i18n.get(LangKey::UseProxy));
+        }
+        if self.proxy && self.transport != Transport::Socket {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.proxy_kind, ProxyKind::Socks5, "SOCKS5");
+                ui.radio_value(&mut self.proxy_kind, ProxyKind::Http, "HTTP CONNECT");
+            });
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(format!("{}*", i18n.get(LangKey::ProxyHost)));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.proxy_host)
+                            .desired_width(ui.available_width() * 0.7)
+                            .hint_text("proxy.example.com"),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::Port));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.proxy_port)
+                            .desired_width(ui.available_width())
+                            .hint_text(match self.proxy_kind {
+                                ProxyKind::Socks5 => "1080",
+                                ProxyKind::Http => "8080",
+                            }),
+                    );
+                })
+            });
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::ProxyUsername));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.proxy_username)
+                            .desired_width(ui.available_width() * 0.5),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::ProxyPassword));
+                    if ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.proxy_password)
+                                .password(true)
+                                .desired_width(ui.available_width()),
+                        )
+                        .has_focus()
+                    {
+                        self.handle_show_password();
+                    }
+                });
+            });
+        }
+        if self.transport != Transport::Socket {
+            ui.checkbox(&mut self.ssh, i18n.get(LangKey::UseSshTunnel));
+        }
+        if self.ssh && self.transport != Transport::Socket {
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(format!("{}*", i18n.get(LangKey::SshHost)));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.ssh_host)
+                            .desired_width(ui.available_width() * 0.7)
+                            .hint_text("bastion.example.com"),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::Port));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.ssh_port)
+                            .desired_width(ui.available_width())
+                            .hint_text("22"),
+                    );
+                })
+            });
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(format!("{}*", i18n.get(LangKey::SshUsername)));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.ssh_username)
+                            .desired_width(ui.available_width() * 0.5),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::SshPassword));
+                    if ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.ssh_password)
+                                .password(true)
+                                .desired_width(ui.available_width()),
+                        )
+                        .has_focus()
+                    {
+                        self.handle_show_password();
+                    }
+                });
+            });
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::SshKeyFile));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.ssh_key_file)
+                            .desired_width(ui.available_width() * 0.5)
+                            .hint_text("~/.ssh/id_ed25519"),
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label(i18n.get(LangKey::KnownHostsFile));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.ssh_known_hosts)
+                            .desired_width(ui.available_width())
+                            .hint_text("~/.ssh/known_hosts"),
+                    );
+                });
+            });
+            ui.checkbox(
+                &mut self.ssh_accept_new,
+                i18n.get(LangKey::SshAcceptNewHost),
+            );
+        }
         ui.separator();
         egui::Sides::new().show(
             ui,
@@ -198,7 +506,28 @@ impl AddConnectionPopup {
             return;
         }
 
-        if self.host.is_empty() {
+        if self.transport == Transport::Socket && self.socket_path.is_empty() {
+            Self::info_dialog(
+                sender.clone(),
+                i18n.clone(),
+                &i18n.get(LangKey::SocketPathRequiredField),
+            );
+            return;
+        }
+
+        if self.transport == Transport::Sentinel
+            && (ValkeyUrl::parse_sentinels(&self.sentinels).is_empty()
+                || self.master_name.trim().is_empty())
+        {
+            Self::info_dialog(
+                sender.clone(),
+                i18n.clone(),
+                &i18n.get(LangKey::SentinelRequiredField),
+            );
+            return;
+        }
+
+        if self.transport == Transport::Tcp && self.host.is_empty() {
             Self::info_dialog(
                 sender.clone(),
                 i18n.clone(),
@@ -214,7 +543,18 @@ impl AddConnectionPopup {
                 return;
             }
         };
-        let alias = self.alias.clone();
+        let mut profile = self.profile.clone();
+        profile.alias = self.alias.clone();
+        let group = self.group.trim();
+        profile.group = (!group.is_empty()).then(|| group.to_string());
+        profile.tags = Vec::new();
+        for tag in self.tags.split(',').map(str::trim) {
+            if !tag.is_empty() && !profile.tags.iter().any(|t| t == tag) {
+                profile.tags.push(tag.to_string());
+            }
+        }
+        profile.color = self.color;
+        profile.production = self.production;
         let old_alias = self.old_alias.clone();
         let connected = self.connected.clone();
         thread::spawn(move || {
@@ -227,20 +567,12 @@ impl AddConnectionPopup {
                 Ok(vc) => {
                     let mut connected = connected.write();
                     *connected = true;
-                    if !test
-                        && let Some(old_alias) = old_alias
-                        && let Err(e) = sender
-                            .send(Event(Arc::new(DeleteServer(old_alias))))
-                            .map_err(Error::from)
-                    {
-                        e.show_error_dialog(sender.clone());
-                        return;
-                    }
-
-                    if let Err(e) = sender
-                        .send(Event(Arc::new(AddServer(alias, vc.server_url()))))
-                        .map_err(Error::from)
-                    {
+                    profile.set_url(&ValkeyUrl::from(vc.server_url()));
+                    let event = match old_alias {
+                        Some(old_alias) if !test => UpdateServer(old_alias, profile),
+                        _ => AddServer(profile),
+                    };
+                    if let Err(e) = sender.send(Event(Arc::new(event))).map_err(Error::from) {
                         e.show_error_dialog(sender);
                     }
                 }
@@ -253,12 +585,20 @@ impl AddConnectionPopup {
         });
     }
 
-    pub fn new(alias: &str, connection_string: &str) -> Self {
-        if let Ok(valkey_url) = ValkeyUrl::parse_valkey_url(None, connection_string) {
-            Self {
-                connection_string: connection_string.to_string(),
+    pub fn new(profile: &ConnectionProfile) -> Self {
+        let connection_string = profile.connection_string();
+        let alias = &profile.alias;
+        if let Ok(valkey_url) = profile.url() {
+            let mut popup = Self {
+                connection_string: connection_string.clone(),
                 alias: alias.to_string(),
                 old_alias: Some(alias.to_string()),
+                profile: profile.clone(),
+                group: profile.group.clone().unwrap_or_default(),
+                tags: profile.tags.join(", "),
+                color: profile.color,
+                production: profile.production,
+                read_only: profile.read_only,
                 host: valkey_url.host().to_string(),
                 port: valkey_url.port().to_string(),
                 username: valkey_url.username().unwrap_or("").to_string(),
@@ -267,23 +607,41 @@ impl AddConnectionPopup {
                     .db()
                     .map(|dbi| dbi.to_string())
                     .unwrap_or_default(),
+                socket_path: valkey_url.socket_path().unwrap_or("").to_string(),
                 show_password: (false, false),
-                connection_string_focus: false,
-                connected: Default::default(),
-            }
+                ..Default::default()
+            };
+            popup.set_transport(&valkey_url);
+            popup.set_tls_options(valkey_url.tls());
+            popup.set_proxy_options(valkey_url.proxy());
+            popup.proxy_password = valkey_url
+                .proxy()
+                .and_then(|proxy| proxy.password.clone())
+                .unwrap_or_default();
+            popup.set_ssh_options(valkey_url.ssh());
+            popup.ssh_password = valkey_url
+                .ssh()
+                .and_then(|ssh| ssh.password.clone())
+                .unwrap_or_default();
+            popup
         } else {
             Self {
-                connection_string: connection_string.to_string(),
+                connection_string,
                 alias: alias.to_string(),
                 old_alias: Some(alias.to_string()),
+                profile: profile.clone(),
+                group: profile.group.clone().unwrap_or_default(),
+                tags: profile.tags.join(", "),
+                color: profile.color,
+                production: profile.production,
+                read_only: profile.read_only,
                 host: "".to_string(),
                 port: "".to_string(),
                 username: "".to_string(),
                 password: "".to_string(),
                 database_index: "".to_string(),
                 show_password: (false, false),
-                connection_string_focus: false,
-                connected: Default::default(),
+                ..Default::default()
             }
         }
     }
@@ -302,18 +660,39 @@ impl AddConnectionPopup {
     }
 
     fn get_url_with_cleartext_pw(&self) -> Result<String, Box<Error>> {
-        let valkey_url_builder = ValkeyUrlBuilder::from(self.connection_string.clone());
-        Ok(valkey_url_builder
-            .password(self.password.clone())
-            .build()?
-            .connection_string())
+        let mut valkey_url_builder =
+            ValkeyUrlBuilder::from(self.connection_string.clone()).password(self.password.clone());
+        if self.transport != Transport::Socket {
+            valkey_url_builder = valkey_url_builder
+                .proxy(self.proxy_options(true))
+                .ssh(self.ssh_options(true));
+        }
+        Ok(valkey_url_builder.build()?.connection_string())
     }
 
     fn parse_valkey_url(&mut self) {
-        if !self.connection_string_focus && !self.host.is_empty() {
+        let sentinels = ValkeyUrl::parse_sentinels(&self.sentinels);
+        let has_address = match self.transport {
+            Transport::Tcp => !self.host.is_empty(),
+            Transport::Socket => !self.socket_path.is_empty(),
+            Transport::Sentinel => !sentinels.is_empty() && !self.master_name.trim().is_empty(),
+        };
+        if !self.connection_string_focus && has_address {
             let mut valkey_url = ValkeyUrlBuilder::new();
             valkey_url = valkey_url.connection_name(self.alias.clone());
             valkey_url = valkey_url.host(self.host.clone());
+            valkey_url = valkey_url.read_only(self.read_only);
+            match self.transport {
+                Transport::Tcp => {}
+                Transport::Socket => {
+                    valkey_url = valkey_url.socket_path(self.socket_path.clone());
+                }
+                Transport::Sentinel => {
+                    valkey_url = valkey_url
+                        .sentinels(sentinels)
+                        .master_name(self.master_name.trim());
+                }
+            }
             if !self.port.is_empty() {
                 valkey_url = valkey_url.port(self.port.parse().unwrap_or(6379));
             }
@@ -330,6 +709,25 @@ impl AddConnectionPopup {
             if !self.database_index.is_empty() {
                 valkey_url = valkey_url.db(self.database_index.parse().unwrap_or(0))
             }
+            if self.transport != Transport::Socket {
+                let show_password = self.show_password.0 || self.show_password.1;
+                valkey_url = valkey_url
+                    .tls(self.tls_options())
+                    .proxy(self.proxy_options(show_password))
+                    .ssh(self.ssh_options(show_password));
+            }
+            // Options without a field of their own are kept from the connection string.
+            if let Ok(current) = ValkeyUrl::parse_valkey_url(None, &self.connection_string) {
+                if let Some(timeout) = current.timeout() {
+                    valkey_url = valkey_url.timeout(timeout);
+                }
+                if let Some(client_name) = current.client_name() {
+                    valkey_url = valkey_url.client_name(client_name);
+                }
+                if let Some(protocol) = current.protocol() {
+                    valkey_url = valkey_url.protocol(protocol);
+                }
+            }
             match valkey_url.build() {
                 Ok(valkey_url) => {
                     self.connection_string = valkey_url.connection_string();
@@ -339,8 +737,8 @@ impl AddConnectionPopup {
                 }
             };
         } else if let Ok(valkey_url) = ValkeyUrl::parse_valkey_url(None, &self.connection_string) {
-            self.host = valkey_url.host().to_string();
-            self.port = valkey_url.port().to_string();
+            self.set_transport(&valkey_url);
+            self.read_only = valkey_url.read_only();
             self.username = valkey_url.username().unwrap_or("").to_string();
             if self.show_password.0 || self.show_password.1 {
                 self.password = valkey_url.password().unwrap_or("").to_string();
@@ -348,7 +746,131 @@ impl AddConnectionPopup {
             self.database_index = match valkey_url.db() {
                 Some(db_idx) => db_idx.to_string(),
                 None => "".to_string(),
-            }
+            };
+            self.set_tls_options(valkey_url.tls());
+            self.set_proxy_options(valkey_url.proxy());
+            self.set_ssh_options(valkey_url.ssh());
+        }
+    }
+
+    fn set_transport(&mut self, valkey_url: &ValkeyUrl) {
+        if let Some(socket_path) = valkey_url.socket_path() {
+            self.transport = Transport::Socket;
+            self.socket_path = socket_path.to_string();
+        } else if let Some(master_name) = valkey_url.master_name() {
+            self.transport = Transport::Sentinel;
+            self.master_name = master_name.to_string();
+            self.sentinels = valkey_url
+                .sentinels()
+                .iter()
+                .map(|(host, port)| ValkeyUrl::format_address(host, *port))
+                .collect::<Vec<String>>()
+                .join(", ");
+        } else {
+            self.transport = Transport::Tcp;
+            self.host = valkey_url.host().to_string();
+            self.port = valkey_url.port().to_string();
+        }
+    }
+
+    fn tls_options(&self) -> Option<TlsOptions> {
+        let non_empty = |value: &str| (!value.trim().is_empty()).then(|| value.trim().to_string());
+        self.tls.then(|| TlsOptions {
+            ca_file: non_empty(&self.tls_ca_file),
+            client_cert: non_empty(&self.tls_client_cert),
+            client_key: non_empty(&self.tls_client_key),
+            server_name: non_empty(&self.tls_server_name),
+            insecure: self.tls_insecure,
+        })
+    }
+
+    fn set_tls_options(&mut self, tls: Option<&TlsOptions>) {
+        self.tls = tls.is_some();
+        let tls = tls.cloned().unwrap_or_default();
+        self.tls_ca_file = tls.ca_file.unwrap_or_default();
+        self.tls_client_cert = tls.client_cert.unwrap_or_default();
+        self.tls_client_key = tls.client_key.unwrap_or_default();
+        self.tls_server_name = tls.server_name.unwrap_or_default();
+        self.tls_insecure = tls.insecure;
+    }
+
+    /// The proxy of the form, its password masked unless `show_password`.
+    fn proxy_options(&self, show_password: bool) -> Option<ProxyOptions> {
+        let non_empty = |value: &str| (!value.trim().is_empty()).then(|| value.trim().to_string());
+        if !self.proxy || self.proxy_host.trim().is_empty() {
+            return None;
+        }
+        let default_port = match self.proxy_kind {
+            ProxyKind::Socks5 => 1080,
+            ProxyKind::Http => 8080,
+        };
+        let password = if show_password {
+            non_empty(&self.proxy_password)
+        } else {
+            non_empty(&self.proxy_password).map(|password| "*".repeat(password.len()))
+        };
+        Some(ProxyOptions {
+            kind: self.proxy_kind,
+            host: self.proxy_host.trim().to_string(),
+            port: self.proxy_port.parse().unwrap_or(default_port),
+            username: non_empty(&self.proxy_username),
+            password,
+        })
+    }
+
+    fn set_proxy_options(&mut self, proxy: Option<&ProxyOptions>) {
+        self.proxy = proxy.is_some();
+        let proxy = proxy.cloned().unwrap_or_default();
+        self.proxy_kind = proxy.kind;
+        self.proxy_host = proxy.host;
+        self.proxy_port = if self.proxy {
+            proxy.port.to_string()
+        } else {
+            "".to_string()
+        };
+        self.proxy_username = proxy.username.unwrap_or_default();
+        if self.show_password.0 || self.show_password.1 {
+            self.proxy_password = proxy.password.unwrap_or_default();
+        }
+    }
+
+    /// The jump host of the form, its password masked unless `show_password`.
+    fn ssh_options(&self, show_password: bool) -> Option<SshOptions> {
+        let non_empty = |value: &str| (!value.trim().is_empty()).then(|| value.trim().to_string());
+        if !self.ssh || self.ssh_host.trim().is_empty() || self.ssh_username.trim().is_empty() {
+            return None;
+        }
+        let password = if show_password {
+            non_empty(&self.ssh_password)
+        } else {
+            non_empty(&self.ssh_password).map(|password| "*".repeat(password.len()))
+        };
+        Some(SshOptions {
+            host: self.ssh_host.trim().to_string(),
+            port: self.ssh_port.parse().unwrap_or(22),
+            username: self.ssh_username.trim().to_string(),
+            password,
+            key_file: non_empty(&self.ssh_key_file),
+            known_hosts: non_empty(&self.ssh_known_hosts),
+            accept_new_host: self.ssh_accept_new,
+        })
+    }
+
+    fn set_ssh_options(&mut self, ssh: Option<&SshOptions>) {
+        self.ssh = ssh.is_some();
+        let ssh = ssh.cloned().unwrap_or_default();
+        self.ssh_host = ssh.host;
+        self.ssh_port = if self.ssh {
+            ssh.port.to_string()
+        } else {
+            "".to_string()
+        };
+        self.ssh_username = ssh.username;
+        self.ssh_key_file = ssh.key_file.unwrap_or_default();
+        self.ssh_known_hosts = ssh.known_hosts.unwrap_or_default();
+        self.ssh_accept_new = ssh.accept_new_host;
+        if self.show_password.0 || self.show_password.1 {
+            self.ssh_password = ssh.password.unwrap_or_default();
         }
     }
 
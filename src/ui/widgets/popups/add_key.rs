@@ -1,7 +1,7 @@
 use crate::i18n::{I18N, LangKey};
 use crate::state::{Message, RespCommand};
 use crate::ui::widgets::popups::PopupUi;
-use crate::utils::{KeyType, text_float_filter, text_float_filter_less_than_one};
+use crate::utils::{Cmd, KeyType, text_float_filter, text_float_filter_less_than_one};
 use egui::{ScrollArea, Ui};
 use std::string::String;
 use std::sync::Arc;
@@ -20,6 +20,8 @@ pub struct AddKey {
     push_to_head: bool,
     show_bloom_options: bool,
     bloom_filter_options: BloomFilterOptions,
+    /// The connection refuses writes, the key can not be saved.
+    read_only: bool,
 }
 
 #[derive(Default)]
@@ -192,92 +194,58 @@ impl Default for AddKey {
             push_to_head: false,
             show_bloom_options: false,
             bloom_filter_options: Default::default(),
+            read_only: false,
         }
     }
 }
 
 impl AddKey {
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
     fn save(&mut self, sender: &Arc<Sender<Message>>) {
+        if self.read_only {
+            return;
+        }
         let mut commands = Vec::new();
-        let mut data: String = String::new();
         let col0_len = self.key_form.col0.len();
         let col1_len = self.key_form.col1.len();
-        let col0_data = &mut self.key_form.col0[0..col0_len - 1];
-        let col1_data = &mut self.key_form.col1[0..col1_len - 1];
-
-        let quote_if_needed = |value: &str| -> String {
-            if value.is_empty() || value.contains(' ') {
-                format!("\"{value}\"")
-            } else {
-                value.to_string()
-            }
-        };
+        let col0_data = &self.key_form.col0[0..col0_len - 1];
+        let col1_data = &self.key_form.col1[0..col1_len - 1];
+        let key = self.key.as_str();
 
-        for col0 in col0_data.iter_mut() {
-            *col0 = quote_if_needed(col0);
-        }
-        for col1 in col1_data.iter_mut() {
-            *col1 = quote_if_needed(col1);
-        }
-
-        let command_type = match self.key_type {
-            KeyType::Hash | KeyType::SortedSet => {
+        let command = match self.key_type {
+            KeyType::Hash => {
+                let mut cmd = Cmd::new("HSET").arg(key);
                 for (col0, col1) in col0_data.iter().zip(col1_data.iter()) {
-                    if matches!(self.key_type, KeyType::SortedSet) {
-                        data.push_str(col1);
-                        data.push(' ');
-                        data.push_str(col0);
-                    } else {
-                        data.push_str(col0);
-                        data.push(' ');
-                        data.push_str(col1);
-                    }
-                    data.push(' ');
-                }
-                match self.key_type {
-                    KeyType::Hash => "HSET",
-                    _ => "ZADD",
+                    cmd = cmd.arg(col0).arg(col1);
                 }
+                cmd
             }
-            KeyType::List | KeyType::Set => {
-                for col0 in col0_data.iter() {
-                    data.push_str(col0);
-                    data.push(' ');
-                }
-                match self.key_type {
-                    KeyType::List => {
-                        if self.push_to_head {
-                            "LPUSH"
-                        } else {
-                            "RPUSH"
-                        }
-                    }
-                    _ => "SADD",
-                }
-            }
-            KeyType::String => {
-                if let Some(s) = self.key_form.col0.first() {
-                    data = quote_if_needed(s);
-                } else {
-                    data = "\"\"".to_owned();
+            KeyType::SortedSet => {
+                let mut cmd = Cmd::new("ZADD").arg(key);
+                for (col0, col1) in col0_data.iter().zip(col1_data.iter()) {
+                    cmd = cmd.arg(col1).arg(col0);
                 }
-                "SET"
+                cmd
             }
+            KeyType::List => Cmd::new(if self.push_to_head { "LPUSH" } else { "RPUSH" })
+                .arg(key)
+                .args(col0_data),
+            KeyType::Set => Cmd::new("SADD").arg(key).args(col0_data),
+            KeyType::String => Cmd::new("SET")
+                .arg(key)
+                .arg(self.key_form.col0.first().map_or("", |s| s.as_str())),
             KeyType::Bloom => {
-                for col1 in col1_data.iter() {
-                    data.push_str(col1);
-                    data.push(' ');
-                }
                 if col1_data.len() <= 1 {
-                    "BF.ADD"
+                    Cmd::new("BF.ADD").arg(key).args(col1_data)
                 } else {
-                    "BF.INSERT"
+                    Cmd::new("BF.INSERT").arg(key).arg("ITEMS").args(col1_data)
                 }
             }
         };
 
-        let key = quote_if_needed(&self.key);
-
         if self.show_bloom_options {
             let error_rate = self
                 .bloom_filter_options
@@ -313,32 +281,25 @@ impl AddKey {
                     );
                     3
                 });
-            let expansion_cmd_slice = if self.bloom_filter_options.non_scaling {
-                "NONSCALING".to_string()
+            let mut reserve = Cmd::new("BF.RESERVE")
+                .arg(key)
+                .arg(error_rate)
+                .arg(capacity);
+            if self.bloom_filter_options.non_scaling {
+                reserve = reserve.arg("NONSCALING");
             } else if !self.bloom_filter_options.expansion.is_empty() {
-                format!("EXPANSION {expansion}")
-            } else {
-                "".to_string()
-            };
-            commands.push(format!(
-                "BF.RESERVE {key} {error_rate} {capacity} {expansion_cmd_slice}"
-            ));
+                reserve = reserve.arg("EXPANSION").arg(expansion);
+            }
+            commands.push(reserve);
         }
 
         if !matches!(self.key_type, KeyType::Bloom) || !col1_data.is_empty() {
-            commands.push(format!(
-                "{command_type} {key}{}{data}",
-                if !(matches!(self.key_type, KeyType::Bloom) && col1_data.len() > 1) {
-                    " "
-                } else {
-                    " ITEMS "
-                }
-            ));
+            commands.push(command);
         }
 
         let ttl = self.ttl.parse::<i64>().unwrap_or(-1);
         if ttl > 0 {
-            commands.push(format!("EXPIRE {key} {ttl}"));
+            commands.push(Cmd::new("EXPIRE").arg(key).arg(ttl));
         }
         sender
             .send(Message::ExecRespCommand(RespCommand::CommandRefresh(
@@ -358,6 +319,13 @@ impl PopupUi for AddKey {
         i18n: &Arc<I18N>,
         open: &mut bool,
     ) {
+        if self.read_only {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                i18n.get(LangKey::ReadOnlyEditingDisabled),
+            );
+            ui.add_space(5.0);
+        }
         ui.vertical(|ui| {
             ui.label("Key name*");
             ui.add(
@@ -409,78 +377,83 @@ impl PopupUi for AddKey {
             });
         });
         if matches!(self.key_type, KeyType::Bloom) {
-            ui.checkbox(
-                &mut self.show_bloom_options,
-                "Set additional bloom filter options",
-            );
-            if self.show_bloom_options {
-                ui.horizontal(|ui| {
-                    ui.vertical(|ui| {
-                        ui.label("Error rate*");
-                        if ui
-                            .add(
-                                egui::TextEdit::singleline(
-                                    &mut self.bloom_filter_options.error_rate,
+            ui.add_enabled_ui(!self.read_only, |ui| {
+                ui.checkbox(
+                    &mut self.show_bloom_options,
+                    "Set additional bloom filter options",
+                );
+                if self.show_bloom_options {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label("Error rate*");
+                            if ui
+                                .add(
+                                    egui::TextEdit::singleline(
+                                        &mut self.bloom_filter_options.error_rate,
+                                    )
+                                    .desired_width(ui.available_width() * 0.5)
+                                    .hint_text("0.01"),
                                 )
-                                .desired_width(ui.available_width() * 0.5)
-                                .hint_text("0.01"),
-                            )
-                            .changed()
-                        {
-                            self.bloom_filter_options.error_rate = text_float_filter_less_than_one(
-                                &self.bloom_filter_options.error_rate.to_string(),
-                            );
-                        }
-                    });
+                                .changed()
+                            {
+                                self.bloom_filter_options.error_rate =
+                                    text_float_filter_less_than_one(
+                                        &self.bloom_filter_options.error_rate.to_string(),
+                                    );
+                            }
+                        });
 
-                    ui.vertical(|ui| {
-                        ui.label("Capacity*");
-                        if ui
-                            .add(
-                                egui::TextEdit::singleline(&mut self.bloom_filter_options.capacity)
+                        ui.vertical(|ui| {
+                            ui.label("Capacity*");
+                            if ui
+                                .add(
+                                    egui::TextEdit::singleline(
+                                        &mut self.bloom_filter_options.capacity,
+                                    )
                                     .desired_width(ui.available_width())
                                     .hint_text("1000"),
-                            )
-                            .changed()
-                        {
-                            self.bloom_filter_options.capacity = self
-                                .bloom_filter_options
-                                .capacity
-                                .chars()
-                                .filter(|c| c.is_ascii_digit())
-                                .collect();
-                        }
-                    });
-                });
-                ui.horizontal(|ui| {
-                    ui.vertical(|ui| {
-                        ui.label("Expansion");
-                        if ui
-                            .add_enabled(
-                                !self.bloom_filter_options.non_scaling,
-                                egui::TextEdit::singleline(
-                                    &mut self.bloom_filter_options.expansion,
                                 )
-                                .desired_width(ui.available_width() * 0.5)
-                                .hint_text("3"),
-                            )
-                            .changed()
-                        {
-                            self.bloom_filter_options.expansion = self
-                                .bloom_filter_options
-                                .expansion
-                                .chars()
-                                .filter(|c| c.is_ascii_digit())
-                                .collect();
-                        }
+                                .changed()
+                            {
+                                self.bloom_filter_options.capacity = self
+                                    .bloom_filter_options
+                                    .capacity
+                                    .chars()
+                                    .filter(|c| c.is_ascii_digit())
+                                    .collect();
+                            }
+                        });
                     });
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label("Expansion");
+                            if ui
+                                .add_enabled(
+                                    !self.bloom_filter_options.non_scaling,
+                                    egui::TextEdit::singleline(
+                                        &mut self.bloom_filter_options.expansion,
+                                    )
+                                    .desired_width(ui.available_width() * 0.5)
+                                    .hint_text("3"),
+                                )
+                                .changed()
+                            {
+                                self.bloom_filter_options.expansion = self
+                                    .bloom_filter_options
+                                    .expansion
+                                    .chars()
+                                    .filter(|c| c.is_ascii_digit())
+                                    .collect();
+                            }
+                        });
 
-                    ui.vertical(|ui| {
-                        ui.add_space(16.0);
-                        ui.checkbox(&mut self.bloom_filter_options.non_scaling, "Non-scaling");
+                        ui.vertical(|ui| {
+                            ui.add_space(16.0);
+                            ui.checkbox(&mut self.bloom_filter_options.non_scaling, "Non-scaling");
+                        });
                     });
-                });
-            }
+                }
+            });
         }
 
         ui.add_space(5.0);
@@ -491,7 +464,9 @@ impl PopupUi for AddKey {
             .max_height(SCROLL_VIEW_HEIGHT)
             .auto_shrink([false, true])
             .show(ui, |ui| {
-                self.key_form.ui(&self.key_type, ui);
+                ui.add_enabled_ui(!self.read_only, |ui| {
+                    self.key_form.ui(&self.key_type, ui);
+                });
             });
         ui.add_space(5.0);
         ui.separator();
@@ -503,7 +478,8 @@ impl PopupUi for AddKey {
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 if ui
                     .add_enabled(
-                        !self.key.is_empty()
+                        !self.read_only
+                            && !self.key.is_empty()
                             && (!self.show_bloom_options
                                 || (!self.bloom_filter_options.error_rate.is_empty()
                                     && !self.bloom_filter_options.capacity.is_empty())),
@@ -1,7 +1,11 @@
 use crate::i18n::{I18N, LangKey};
 use crate::state::{Message, RespCommand};
+use crate::ui::widgets::TypedConfirmation;
 use crate::ui::widgets::popups::PopupUi;
-use crate::utils::{KeyType, format_size, text_float_filter};
+use crate::utils::{
+    Cmd, KeyType, Transaction, display_bytes, escape_bytes, format_size, is_binary,
+    text_float_filter, unescape_bytes,
+};
 use egui::{Key, Ui};
 use egui_extras::{Column, TableBuilder};
 use std::collections::HashMap;
@@ -11,7 +15,8 @@ use std::sync::mpsc::Sender;
 const COL_HEIGHT: f32 = 28.0;
 
 pub struct EditKey {
-    key: String,
+    key: Vec<u8>,
+    binary: bool,
     key_type: KeyType,
     data: Vec<(String, String)>,
     focused_cell: Option<(usize, usize)>,
@@ -20,13 +25,47 @@ pub struct EditKey {
     new_field_name: String,
     new_field_value: String,
     show_add_form: bool,
+    /// The connection refuses writes, the values are only shown.
+    read_only: bool,
+    /// Set on a production connection, rows are only deleted once the alias is typed.
+    production_alias: Option<String>,
+    pending_delete: Option<(usize, Cmd, TypedConfirmation)>,
     i18n: Arc<I18N>,
 }
 
 impl EditKey {
     pub fn new(key: String, key_type: KeyType, data: Vec<String>, i18n: Arc<I18N>) -> Self {
+        Self::from_bytes(
+            key.into_bytes(),
+            key_type,
+            data.into_iter().map(String::into_bytes).collect(),
+            i18n,
+        )
+    }
+
+    /// Creates the editor from raw reply data. If the key or any element is not valid UTF-8,
+    /// all cells are shown escaped and unescaped again before they are written back.
+    pub fn from_bytes(
+        key: Vec<u8>,
+        key_type: KeyType,
+        data: Vec<Vec<u8>>,
+        i18n: Arc<I18N>,
+    ) -> Self {
+        let binary = is_binary(&key) || data.iter().any(|value| is_binary(value));
+        let data: Vec<String> = data
+            .iter()
+            .map(|value| {
+                if binary {
+                    escape_bytes(value)
+                } else {
+                    display_bytes(value)
+                }
+            })
+            .collect();
+
         Self {
             key,
+            binary,
             key_type,
             data: match key_type {
                 KeyType::Hash | KeyType::SortedSet | KeyType::Bloom => data
@@ -63,76 +102,168 @@ impl EditKey {
             new_field_name: String::new(),
             new_field_value: String::new(),
             show_add_form: false,
+            read_only: false,
+            production_alias: None,
+            pending_delete: None,
             i18n,
         }
     }
 
-    pub fn key_name(&self) -> &str {
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    pub fn with_production_alias(self, production_alias: Option<String>) -> Self {
+        Self {
+            production_alias,
+            ..self
+        }
+    }
+
+    pub fn key_name(&self) -> String {
+        display_bytes(&self.key)
+    }
+
+    pub fn key(&self) -> &[u8] {
         &self.key
     }
 
+    /// Command the values shown in the editor are read with.
+    pub fn read_command(key: &[u8], key_type: KeyType) -> Cmd {
+        match key_type {
+            KeyType::Hash => Cmd::new("HGETALL").arg(key),
+            KeyType::List => Cmd::new("LRANGE").arg(key).arg(0).arg(499),
+            KeyType::Set => Cmd::new("SMEMBERS").arg(key),
+            KeyType::SortedSet => Cmd::new("ZRANGE")
+                .arg(key)
+                .arg(0)
+                .arg(499)
+                .arg("WITHSCORES"),
+            KeyType::String => Cmd::new("GET").arg(key),
+            KeyType::Bloom => Cmd::new("BF.INFO").arg(key),
+        }
+    }
+
+    pub fn reload_command(&self) -> Cmd {
+        Self::read_command(&self.key, self.key_type)
+    }
+
+    /// Shows the values read again after a change, edits that were not saved are dropped.
+    pub fn reload(&mut self, data: Vec<Vec<u8>>) {
+        let reloaded = Self::from_bytes(
+            std::mem::take(&mut self.key),
+            self.key_type,
+            data,
+            self.i18n.clone(),
+        );
+        *self = Self {
+            new_field_name: std::mem::take(&mut self.new_field_name),
+            new_field_value: std::mem::take(&mut self.new_field_value),
+            show_add_form: self.show_add_form,
+            read_only: self.read_only,
+            production_alias: self.production_alias.take(),
+            ..reloaded
+        };
+    }
+
+    fn encode(binary: bool, text: &str) -> Vec<u8> {
+        if binary {
+            unescape_bytes(text)
+        } else {
+            text.as_bytes().to_vec()
+        }
+    }
+
+    /// Returns the command deleting the row when the button is clicked.
     fn delete_button(
         ui: &mut Ui,
-        key: &str,
+        (key, binary): (&[u8], bool),
         key_type: &KeyType,
         row: (&String, &String),
-        sender: &Arc<Sender<Message>>,
-    ) -> bool {
+    ) -> Option<Cmd> {
         if ui.small_button("🗑").clicked() {
-            let command = match key_type {
-                KeyType::Hash => {
-                    vec![format!(
-                        "HDEL \"{}\" \"{}\"",
-                        key.replace("\"", "\\\""),
-                        row.0.replace("\"", "\\\"")
-                    )]
-                }
-                KeyType::List => {
-                    vec![format!(
-                        "LREM \"{}\" 1 \"{}\"",
-                        key.replace("\"", "\\\""),
-                        row.1.replace("\"", "\\\"")
-                    )]
-                }
-                KeyType::Set => {
-                    vec![format!(
-                        "SREM \"{}\" \"{}\"",
-                        key.replace("\"", "\\\""),
-                        row.0.replace("\"", "\\\"")
-                    )]
-                }
-                KeyType::SortedSet => {
-                    vec![format!(
-                        "ZREM \"{}\" \"{}\"",
-                        key.replace("\"", "\\\""),
-                        row.0.replace("\"", "\\\"")
-                    )]
-                }
-                KeyType::String => {
-                    vec![format!("DEL \"{}\"", key.replace("\"", "\\\""))]
-                }
+            Some(match key_type {
+                KeyType::Hash => Cmd::new("HDEL").arg(key).arg(Self::encode(binary, row.0)),
+                KeyType::List => Cmd::new("LREM")
+                    .arg(key)
+                    .arg(1)
+                    .arg(Self::encode(binary, row.1)),
+                KeyType::Set => Cmd::new("SREM").arg(key).arg(Self::encode(binary, row.0)),
+                KeyType::SortedSet => Cmd::new("ZREM").arg(key).arg(Self::encode(binary, row.0)),
+                KeyType::String => Cmd::new("DEL").arg(key),
                 KeyType::Bloom => {
                     // Bloom filters don't support editing of individual items
                     panic!("Not implemented");
                 }
-            };
+            })
+        } else {
+            None
+        }
+    }
 
-            sender
-                .send(Message::ExecRespCommand(RespCommand::Command(command)))
-                .unwrap_or_else(|e| {
-                    eprintln!("Error sending message: {e}");
+    /// Deletes the row, on a production connection once the alias is typed.
+    fn request_delete(&mut self, row_index: usize, command: Cmd, sender: &Arc<Sender<Message>>) {
+        match &self.production_alias {
+            Some(alias) => {
+                self.pending_delete = Some((row_index, command, TypedConfirmation::new(alias)))
+            }
+            None => self.delete_row(row_index, command, sender),
+        }
+    }
+
+    fn delete_row(&mut self, row_index: usize, command: Cmd, sender: &Arc<Sender<Message>>) {
+        sender
+            .send(Message::ExecRespCommand(RespCommand::Command(vec![
+                command,
+            ])))
+            .unwrap_or_else(|e| {
+                eprintln!("Error sending message: {e}");
+            });
+        self.rows_to_delete.push(row_index);
+    }
+
+    fn delete_confirmation_modal(&mut self, ctx: &egui::Context, sender: &Arc<Sender<Message>>) {
+        let Some((_, command, confirmation)) = &mut self.pending_delete else {
+            return;
+        };
+        let mut close = false;
+        let mut delete = false;
+        let modal = egui::Modal::new(egui::Id::new("confirm_row_delete")).show(ctx, |ui| {
+            ui.set_width(320.0);
+            ui.heading(format!(
+                "{} {}?",
+                self.i18n.get(LangKey::Delete),
+                command.name()
+            ));
+            ui.add_space(8.0);
+            let confirmed = confirmation.ui(ui, &self.i18n);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                close = ui.button(self.i18n.get(LangKey::Cancel)).clicked();
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add_enabled(confirmed, egui::Button::new(self.i18n.get(LangKey::Delete)))
+                        .clicked()
+                    {
+                        delete = true;
+                        close = true;
+                    }
                 });
-            true
-        } else {
-            false
+            });
+        });
+        if close || modal.should_close() {
+            let pending = self.pending_delete.take();
+            if delete && let Some((row_index, command, _)) = pending {
+                self.delete_row(row_index, command, sender);
+            }
         }
     }
 
     fn highlighted_column(
-        (key, key_type): (&str, &KeyType),
+        (key, key_type, binary): (&[u8], &KeyType, bool),
         ui: &mut Ui,
         (row, index): ((&String, &mut String), usize),
-        (read_only, is_focused): (bool, bool),
+        (read_only, editable, is_focused): (bool, bool, bool),
         cell_id: (usize, usize),
         original_values: &mut HashMap<(usize, usize), String>,
         (sender, i18n): (&Arc<Sender<Message>>, &I18N),
@@ -178,7 +309,10 @@ impl EditKey {
             ui.style_mut().visuals.widgets.hovered.bg_fill = egui::Color32::TRANSPARENT;
             ui.style_mut().visuals.widgets.active.bg_fill = egui::Color32::TRANSPARENT;
 
-            let text_edit = egui::TextEdit::singleline(row.1)
+            // Values of a read-only connection can still be selected and copied.
+            let mut shown = row.1.as_str();
+            let text: &mut dyn egui::TextBuffer = if editable { row.1 } else { &mut shown };
+            let text_edit = egui::TextEdit::singleline(text)
                 .desired_width(f32::INFINITY)
                 .frame(false)
                 .margin(egui::Margin::ZERO);
@@ -196,73 +330,99 @@ impl EditKey {
                 && let Some(original_text) = original_values.get(&cell_id)
             {
                 if *row.1 != *original_text {
-                    let commands = match key_type {
-                        KeyType::Hash => {
-                            vec![format!(
-                                "HSET \"{}\" \"{}\" \"{}\"",
-                                key.replace("\"", "\\\""),
-                                row.0.replace("\"", "\\\""),
-                                row.1.replace("\"", "\\\"")
-                            )]
-                        }
-                        KeyType::List => {
-                            vec![format!(
-                                "LSET \"{}\" {} \"{}\"",
-                                key.replace("\"", "\\\""),
-                                index,
-                                row.1.replace("\"", "\\\"")
-                            )]
-                        }
-                        KeyType::Set => {
-                            vec![
-                                format!(
-                                    "SREM \"{}\" \"{}\"",
-                                    key.replace("\"", "\\\""),
-                                    original_text.replace("\"", "\\\"")
+                    // Each change is checked against the value shown when the cell was
+                    // entered, so changes of other clients are not overwritten.
+                    let transaction = Transaction::new().watch(key);
+                    let transaction = match key_type {
+                        KeyType::Hash => Some(
+                            transaction
+                                .expect(
+                                    Cmd::new("HGET").arg(key).arg(Self::encode(binary, row.0)),
+                                    Self::encode(binary, original_text),
+                                )
+                                .command(
+                                    Cmd::new("HSET")
+                                        .arg(key)
+                                        .arg(Self::encode(binary, row.0))
+                                        .arg(Self::encode(binary, row.1)),
                                 ),
-                                format!(
-                                    "SADD \"{}\" \"{}\"",
-                                    key.replace("\"", "\\\""),
-                                    row.1.replace("\"", "\\\"")
+                        ),
+                        KeyType::List => Some(
+                            transaction
+                                .expect(
+                                    Cmd::new("LINDEX").arg(key).arg(index),
+                                    Self::encode(binary, original_text),
+                                )
+                                .command(
+                                    Cmd::new("LSET")
+                                        .arg(key)
+                                        .arg(index)
+                                        .arg(Self::encode(binary, row.1)),
                                 ),
-                            ]
-                        }
+                        ),
+                        KeyType::Set => Some(
+                            transaction
+                                .expect(
+                                    Cmd::new("SISMEMBER")
+                                        .arg(key)
+                                        .arg(Self::encode(binary, original_text)),
+                                    "1",
+                                )
+                                .command(
+                                    Cmd::new("SREM")
+                                        .arg(key)
+                                        .arg(Self::encode(binary, original_text)),
+                                )
+                                .command(
+                                    Cmd::new("SADD").arg(key).arg(Self::encode(binary, row.1)),
+                                ),
+                        ),
                         KeyType::SortedSet => {
                             if row.1.parse::<f64>().is_ok() {
-                                vec![
-                                    format!(
-                                        "ZREM \"{}\" \"{}\"",
-                                        key.replace("\"", "\\\""),
-                                        row.0.replace("\"", "\\\"")
-                                    ),
-                                    format!(
-                                        "ZADD \"{}\" {} \"{}\"",
-                                        key.replace("\"", "\\\""),
-                                        row.1,
-                                        row.0.replace("\"", "\\\"")
-                                    ),
-                                ]
+                                Some(
+                                    transaction
+                                        .expect(
+                                            Cmd::new("ZSCORE")
+                                                .arg(key)
+                                                .arg(Self::encode(binary, row.0)),
+                                            original_text.as_str(),
+                                        )
+                                        .command(
+                                            Cmd::new("ZREM")
+                                                .arg(key)
+                                                .arg(Self::encode(binary, row.0)),
+                                        )
+                                        .command(
+                                            Cmd::new("ZADD")
+                                                .arg(key)
+                                                .arg(row.1.as_str())
+                                                .arg(Self::encode(binary, row.0)),
+                                        ),
+                                )
                             } else {
                                 eprintln!("Invalid score for sorted set: {}", row.1);
-                                vec![]
+                                None
                             }
                         }
-                        KeyType::String => {
-                            vec![format!(
-                                "SET \"{}\" \"{}\"",
-                                key.replace("\"", "\\\""),
-                                row.1.replace("\"", "\\\"")
-                            )]
-                        }
+                        KeyType::String => Some(
+                            transaction
+                                .expect(
+                                    Cmd::new("GET").arg(key),
+                                    Self::encode(binary, original_text),
+                                )
+                                .command(Cmd::new("SET").arg(key).arg(Self::encode(binary, row.1))),
+                        ),
                         KeyType::Bloom => {
                             // Bloom filters don't support editing of individual items
                             panic!("Not implemented");
                         }
                     };
 
-                    if !commands.is_empty() {
+                    if let Some(transaction) = transaction {
                         sender
-                            .send(Message::ExecRespCommand(RespCommand::Command(commands)))
+                            .send(Message::ExecRespCommand(RespCommand::Transaction(
+                                transaction,
+                            )))
                             .unwrap_or_else(|e| {
                                 eprintln!("{}: {e}", i18n.get(LangKey::ErrorSendMsg));
                             });
@@ -283,8 +443,11 @@ impl EditKey {
             egui::ScrollArea::vertical()
                 .id_salt("edit_key_input_field_scroll")
                 .show(ui, |ui| {
-                    let text_edit = egui::TextEdit::multiline(value);
-                    let response = ui.add_sized(ui.available_size(), text_edit);
+                    let mut shown = value.as_str();
+                    let text: &mut dyn egui::TextBuffer =
+                        if self.read_only { &mut shown } else { value };
+                    let response =
+                        ui.add_sized(ui.available_size(), egui::TextEdit::multiline(text));
 
                     if response.gained_focus() {
                         self.original_values.insert(cell_id, value.clone());
@@ -294,14 +457,22 @@ impl EditKey {
                         && let Some(original_text) = self.original_values.get(&cell_id)
                     {
                         if *value != *original_text {
-                            let commands = vec![format!(
-                                "SET \"{}\" \"{}\"",
-                                self.key.replace("\"", "\\\""),
-                                value.replace("\"", "\\\"")
-                            )];
+                            let transaction = Transaction::new()
+                                .watch(self.key.clone())
+                                .expect(
+                                    Cmd::new("GET").arg(&self.key),
+                                    Self::encode(self.binary, original_text),
+                                )
+                                .command(
+                                    Cmd::new("SET")
+                                        .arg(&self.key)
+                                        .arg(Self::encode(self.binary, value)),
+                                );
 
                             sender
-                                .send(Message::ExecRespCommand(RespCommand::Command(commands)))
+                                .send(Message::ExecRespCommand(RespCommand::Transaction(
+                                    transaction,
+                                )))
                                 .unwrap_or_else(|e| {
                                     eprintln!("Error sending message: {e}");
                                 });
@@ -394,6 +565,9 @@ impl EditKey {
     }
 
     fn add_new_field(&mut self, sender: &Arc<Sender<Message>>) {
+        if self.read_only {
+            return;
+        }
         if self.new_field_name.is_empty()
             && matches!(
                 self.key_type,
@@ -411,51 +585,37 @@ impl EditKey {
             return;
         }
 
+        let key = &self.key;
+        let name = Self::encode(self.binary, &self.new_field_name);
+        let value = Self::encode(self.binary, &self.new_field_value);
         let commands = match self.key_type {
             KeyType::Hash => {
-                vec![format!(
-                    "HSET \"{}\" \"{}\" \"{}\"",
-                    self.key.replace("\"", "\\\""),
-                    self.new_field_name.replace("\"", "\\\""),
-                    self.new_field_value.replace("\"", "\\\"")
-                )]
+                vec![Cmd::new("HSET").arg(key).arg(name).arg(value)]
             }
             KeyType::Set => {
-                vec![format!(
-                    "SADD \"{}\" \"{}\"",
-                    self.key.replace("\"", "\\\""),
-                    self.new_field_name.replace("\"", "\\\"")
-                )]
+                vec![Cmd::new("SADD").arg(key).arg(name)]
             }
             KeyType::SortedSet => {
                 if let Ok(_score) = self.new_field_value.parse::<f64>() {
-                    vec![format!(
-                        "ZADD \"{}\" {} \"{}\"",
-                        self.key.replace("\"", "\\\""),
-                        self.new_field_value,
-                        self.new_field_name.replace("\"", "\\\"")
-                    )]
+                    vec![
+                        Cmd::new("ZADD")
+                            .arg(key)
+                            .arg(&self.new_field_value)
+                            .arg(name),
+                    ]
                 } else {
                     eprintln!("Invalid score for sorted set: {}", self.new_field_value);
                     return;
                 }
             }
             KeyType::List => {
-                vec![format!(
-                    "RPUSH \"{}\" \"{}\"",
-                    self.key.replace("\"", "\\\""),
-                    self.new_field_value.replace("\"", "\\\"")
-                )]
+                vec![Cmd::new("RPUSH").arg(key).arg(value)]
             }
             KeyType::String => {
                 return;
             }
             KeyType::Bloom => {
-                vec![format!(
-                    "BF.ADD \"{}\" \"{}\"",
-                    self.key.replace("\"", "\\\""),
-                    self.new_field_value.replace("\"", "\\\"")
-                )]
+                vec![Cmd::new("BF.ADD").arg(key).arg(value)]
             }
         };
 
@@ -496,6 +656,7 @@ impl EditKey {
     fn data_table(&mut self, ui: &mut Ui, sender: &Arc<Sender<Message>>) {
         self.delete_marked_rows();
         self.rows_to_delete.clear();
+        let mut requested_delete = None;
 
         let available_height = ui.available_height();
         let form_height = if self.show_add_form { 100.0 } else { 50.0 };
@@ -562,10 +723,10 @@ impl EditKey {
                                         let is_focused = self.focused_cell == Some((row_index, 0));
 
                                         Self::highlighted_column(
-                                            (&self.key, &self.key_type),
+                                            (&self.key, &self.key_type, self.binary),
                                             ui,
                                             ((&row.0, &mut row.1), row_index),
-                                            (true, is_focused),
+                                            (true, false, is_focused),
                                             (row_index, 0),
                                             &mut self.original_values,
                                             (sender, &self.i18n),
@@ -577,10 +738,10 @@ impl EditKey {
                                         let is_focused = self.focused_cell == Some((row_index, 1));
 
                                         let response = Self::highlighted_column(
-                                            (&self.key, &self.key_type),
+                                            (&self.key, &self.key_type, self.binary),
                                             ui,
                                             ((&row.0, &mut row.1), row_index),
-                                            (false, is_focused),
+                                            (false, !self.read_only, is_focused),
                                             (row_index, 1),
                                             &mut self.original_values,
                                             (sender, &self.i18n),
@@ -594,21 +755,27 @@ impl EditKey {
                                     });
                                 }
                                 row_ui.col(|ui| {
-                                    if Self::delete_button(
-                                        ui,
-                                        &self.key,
-                                        &self.key_type,
-                                        (&row.0, &row.1),
-                                        sender,
-                                    ) {
-                                        self.rows_to_delete.push(row_index);
-                                    }
+                                    ui.add_enabled_ui(!self.read_only, |ui| {
+                                        if let Some(command) = Self::delete_button(
+                                            ui,
+                                            (&self.key, self.binary),
+                                            &self.key_type,
+                                            (&row.0, &row.1),
+                                        ) {
+                                            requested_delete = Some((row_index, command));
+                                        }
+                                    });
                                 });
                             });
                         }
                     });
             });
 
+        if let Some((row_index, command)) = requested_delete {
+            self.request_delete(row_index, command, sender);
+        }
+        self.delete_confirmation_modal(ui.ctx(), sender);
+
         ui.add_space(10.0);
         ui.separator();
         ui.add_space(5.0);
@@ -616,7 +783,10 @@ impl EditKey {
         if !self.show_add_form {
             ui.horizontal(|ui| {
                 if ui
-                    .button(format!("➕ {}", self.i18n.get(LangKey::AddNew)))
+                    .add_enabled(
+                        !self.read_only,
+                        egui::Button::new(format!("➕ {}", self.i18n.get(LangKey::AddNew))),
+                    )
                     .clicked()
                 {
                     self.show_add_form = !self.show_add_form;
@@ -629,7 +799,7 @@ impl EditKey {
         }
     }
 
-    fn bloom_filter(&self, ui: &mut Ui, sender: &Arc<Sender<Message>>) {
+    fn bloom_filter(&self, ui: &mut Ui) {
         let bf_info: HashMap<String, String> = self.data.clone().into_iter().collect();
 
         ui.vertical(|ui| {
@@ -870,10 +1040,18 @@ impl PopupUi for EditKey {
                     *open = false;
                 }
 
+                if self.read_only {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        self.i18n.get(LangKey::ReadOnlyEditingDisabled),
+                    );
+                    ui.add_space(5.0);
+                }
+
                 if matches!(self.key_type, KeyType::String) {
                     self.input_field(ui, sender);
                 } else if matches!(self.key_type, KeyType::Bloom) {
-                    self.bloom_filter(ui, sender);
+                    self.bloom_filter(ui);
                 } else {
                     self.data_table(ui, sender);
                 }
@@ -1030,6 +1208,46 @@ mod tests {
         assert_eq!(edit_key.data[1], ("member2".to_string(), "2.0".to_string()));
     }
 
+    #[test]
+    fn test_row_delete_needs_confirmation_on_production() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let sender = Arc::new(tx);
+        let data = vec!["field".to_string(), "value".to_string()];
+        for key_type in [
+            KeyType::Hash,
+            KeyType::List,
+            KeyType::Set,
+            KeyType::SortedSet,
+        ] {
+            let mut edit_key = EditKey::new(
+                "key".to_string(),
+                key_type,
+                data.clone(),
+                Arc::new(Default::default()),
+            )
+            .with_production_alias(Some("prod".to_string()));
+
+            edit_key.request_delete(0, Cmd::new("HDEL").arg("key").arg("field"), &sender);
+            assert!(rx.try_recv().is_err());
+            assert!(edit_key.rows_to_delete.is_empty());
+            assert!(edit_key.pending_delete.is_some());
+        }
+
+        let mut edit_key = EditKey::new(
+            "key".to_string(),
+            KeyType::Hash,
+            data,
+            Arc::new(Default::default()),
+        );
+        edit_key.request_delete(0, Cmd::new("HDEL").arg("key").arg("field"), &sender);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(Message::ExecRespCommand(RespCommand::Command(_)))
+        ));
+        assert_eq!(edit_key.rows_to_delete, vec![0]);
+        assert!(edit_key.pending_delete.is_none());
+    }
+
     #[test]
     fn test_row_deletion_tracking() {
         let mut edit_key = EditKey::new(
@@ -1052,6 +1270,35 @@ mod tests {
         assert_eq!(edit_key.data.len(), 1);
     }
 
+    #[test]
+    fn test_reload_replaces_edits() {
+        let mut edit_key = EditKey::new(
+            "myhash".to_string(),
+            KeyType::Hash,
+            vec!["field".to_string(), "old".to_string()],
+            Arc::new(Default::default()),
+        )
+        .with_read_only(true);
+        edit_key.data[0].1 = "typed".to_string();
+        edit_key.original_values.insert((0, 1), "old".to_string());
+        edit_key.new_field_name = "pending".to_string();
+
+        edit_key.reload(vec![b"field".to_vec(), b"changed".to_vec()]);
+
+        assert_eq!(edit_key.key(), b"myhash");
+        assert_eq!(
+            edit_key.data,
+            vec![("field".to_string(), "changed".to_string())]
+        );
+        assert!(edit_key.original_values.is_empty());
+        assert_eq!(edit_key.new_field_name, "pending");
+        assert!(edit_key.read_only);
+        assert_eq!(
+            edit_key.reload_command().arguments(),
+            [b"HGETALL".to_vec(), b"myhash".to_vec()]
+        );
+    }
+
     #[test]
     fn test_focus_management() {
         let mut edit_key = EditKey::new(
@@ -1270,4 +1517,25 @@ mod tests {
 
         edit_key.new_field_value = "1.5".to_string();
     }
+
+    #[test]
+    fn test_binary_data_round_trip() {
+        let edit_key = EditKey::from_bytes(
+            vec![0xff, b'k'],
+            KeyType::Hash,
+            vec![b"field".to_vec(), vec![0x00, 0xc3, b'\\', 0x28]],
+            Arc::new(Default::default()),
+        );
+
+        assert!(edit_key.binary);
+        assert_eq!(edit_key.key_name(), "\\xffk");
+        assert_eq!(
+            edit_key.data[0],
+            ("field".to_string(), "\0\\xc3\\\\(".to_string())
+        );
+        assert_eq!(
+            EditKey::encode(edit_key.binary, &edit_key.data[0].1),
+            vec![0x00, 0xc3, b'\\', 0x28]
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use crate::utils::Cmd;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
@@ -18,8 +19,48 @@ pub struct Command {
     pub full_name: String,
     pub summary: String,
     pub arguments_desc: String,
+    pub flags: Vec<String>,
+    /// ACL categories, e.g. `KEYSPACE` or `DANGEROUS`.
+    pub categories: Vec<String>,
 }
 
+impl Command {
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    pub fn has_category(&self, category: &str) -> bool {
+        self.categories.iter().any(|c| c == category)
+    }
+}
+
+/// Administrative commands that lose data or state but are not flagged as writes.
+const DESTRUCTIVE_COMMANDS: &[&str] = &[
+    "ACL DELUSER",
+    "CLIENT KILL",
+    "CLUSTER FAILOVER",
+    "CLUSTER RESET",
+    "CONFIG SET",
+    "DEBUG",
+    "FAILOVER",
+    "REPLICAOF",
+    "SCRIPT FLUSH",
+    "SHUTDOWN",
+    "SLAVEOF",
+];
+
+/// Scripts may write, only their `_RO` variants are flagged as read-only.
+const SCRIPT_COMMANDS: &[&str] = &["EVAL", "EVALSHA", "FCALL"];
+
+/// Writing commands of the bloom filter module, which has no docs in the commands directory.
+const BLOOM_WRITE_COMMANDS: &[&str] = &[
+    "BF.ADD",
+    "BF.MADD",
+    "BF.INSERT",
+    "BF.RESERVE",
+    "BF.LOADCHUNK",
+];
+
 #[derive(Debug, Deserialize)]
 struct CommandFile {
     #[serde(flatten)]
@@ -32,6 +73,10 @@ struct CommandDefinition {
     container: Option<String>,
     #[serde(default)]
     arguments: Vec<Argument>,
+    #[serde(default)]
+    command_flags: Vec<String>,
+    #[serde(default)]
+    acl_categories: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -80,6 +125,10 @@ impl CommandRegistry {
         Ok(registry)
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
     fn parse_command_json(json: &str) -> Option<Command> {
         let command_file: CommandFile = serde_json::from_str(json).ok()?;
 
@@ -103,6 +152,8 @@ impl CommandRegistry {
             full_name,
             summary,
             arguments_desc,
+            flags: definition.command_flags,
+            categories: definition.acl_categories,
         })
     }
 
@@ -144,6 +195,47 @@ impl CommandRegistry {
         suggestions
     }
 
+    /// Looks up the definition of a command. Container commands like `CLIENT LIST`
+    /// are matched together with their subcommand.
+    pub fn find(&self, cmd: &Cmd) -> Option<&Command> {
+        let name = cmd.name();
+        if let Some(subcommand) = cmd.arguments().get(1) {
+            let full_name = format!(
+                "{} {}",
+                name,
+                String::from_utf8_lossy(subcommand).to_uppercase()
+            );
+            if let Some(command) = self.commands.iter().find(|c| c.full_name == full_name) {
+                return Some(command);
+            }
+        }
+        self.commands.iter().find(|c| c.full_name == name)
+    }
+
+    /// Whether the command may block the connection until data arrives, e.g. `BLPOP`.
+    pub fn is_blocking(&self, cmd: &Cmd) -> bool {
+        self.find(cmd).is_some_and(|c| c.has_flag("BLOCKING"))
+    }
+
+    /// Whether the command writes data: flagged `WRITE` in its docs, a script that may
+    /// write or a bloom filter write. Refused on read-only connections.
+    pub fn is_write(&self, cmd: &Cmd) -> bool {
+        BLOOM_WRITE_COMMANDS.contains(&cmd.name().as_str())
+            || self.find(cmd).is_some_and(|c| {
+                c.has_flag("WRITE") || SCRIPT_COMMANDS.contains(&c.full_name.as_str())
+            })
+    }
+
+    /// Whether the command deletes or replaces data, e.g. `DEL`, `FLUSHDB` or `CONFIG SET`.
+    /// Writes to the keyspace and dangerous writes count, unknown commands do not.
+    pub fn is_destructive(&self, cmd: &Cmd) -> bool {
+        self.find(cmd).is_some_and(|c| {
+            DESTRUCTIVE_COMMANDS.contains(&c.full_name.as_str())
+                || (c.has_flag("WRITE")
+                    && (c.has_category("KEYSPACE") || c.has_category("DANGEROUS")))
+        })
+    }
+
     pub fn get_all_commands(&self) -> &[Command] {
         &self.commands
     }
@@ -154,3 +246,64 @@ impl Default for CommandRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_destructive() {
+        let registry = CommandRegistry::load_from_directory("commands").unwrap();
+        for line in [
+            "DEL a",
+            "unlink a b",
+            "FLUSHDB",
+            "FLUSHALL ASYNC",
+            "config set a b",
+        ] {
+            assert!(
+                registry.is_destructive(&Cmd::parse(line).unwrap()),
+                "{line}"
+            );
+        }
+        for line in [
+            "GET a",
+            "SET a b",
+            "KEYS *",
+            "CONFIG GET *",
+            "INFO",
+            "NOSUCHCOMMAND",
+        ] {
+            assert!(
+                !registry.is_destructive(&Cmd::parse(line).unwrap()),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_write() {
+        let registry = CommandRegistry::load_from_directory("commands").unwrap();
+        for line in [
+            "SET a b",
+            "hset h f v",
+            "DEL a",
+            "EVAL 'return 1' 0",
+            "FUNCTION FLUSH",
+            "BF.ADD f a",
+            "bf.madd f a b",
+        ] {
+            assert!(registry.is_write(&Cmd::parse(line).unwrap()), "{line}");
+        }
+        for line in [
+            "GET a",
+            "SCAN 0",
+            "EVAL_RO 'return 1' 0",
+            "CLIENT LIST",
+            "MULTI",
+            "BF.EXISTS f a",
+        ] {
+            assert!(!registry.is_write(&Cmd::parse(line).unwrap()), "{line}");
+        }
+    }
+}
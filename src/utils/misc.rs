@@ -1,12 +1,20 @@
 use crate::APP_NAME;
 use crate::errors::Error;
 use egui::Color32;
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 pub struct PathProvider;
 
 impl PathProvider {
     pub fn get_config_path() -> Result<PathBuf, Error> {
+        // Tests must never touch the saved connections and secrets of the developer.
+        if cfg!(test) {
+            let path = std::env::temp_dir().join(format!("{APP_NAME}-test-{}", std::process::id()));
+            std::fs::create_dir_all(&path)?;
+            return Ok(path);
+        }
         if cfg!(target_os = "windows") {
             let path = PathBuf::from(std::env::var("APP_DATA")?);
             if !path.exists() {
@@ -27,6 +35,19 @@ impl PathProvider {
     }
 }
 
+/// Writes the file through a temporary file next to it, so a crash or a full disk never
+/// leaves it half written.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
 pub fn random_string(len: usize) -> Result<String, Error> {
     let seed = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)?
@@ -103,3 +124,212 @@ pub fn text_float_filter(input: &str) -> String {
     }
     result
 }
+
+pub fn is_binary(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_err()
+}
+
+/// Renders bytes for display: valid UTF-8 is shown as is, anything else is escaped.
+pub fn display_bytes(data: &[u8]) -> String {
+    match std::str::from_utf8(data) {
+        Ok(s) => s.to_string(),
+        Err(_) => escape_bytes(data),
+    }
+}
+
+/// Escapes `\\` and every byte that is not valid UTF-8 as `\xHH`, so that
+/// `unescape_bytes` restores the exact input.
+pub fn escape_bytes(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len() * 2);
+    for chunk in data.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if c == '\\' {
+                result.push_str("\\\\");
+            } else {
+                result.push(c);
+            }
+        }
+        for byte in chunk.invalid() {
+            result.push_str(&format!("\\x{byte:02x}"));
+        }
+    }
+    result
+}
+
+pub fn unescape_bytes(input: &str) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 1 < bytes.len() {
+            if bytes[i + 1] == b'\\' {
+                result.push(b'\\');
+                i += 2;
+                continue;
+            }
+            if bytes[i + 1] == b'x'
+                && i + 3 < bytes.len()
+                && let Some(hex) = input.get(i + 2..i + 4)
+                && let Ok(byte) = u8::from_str_radix(hex, 16)
+            {
+                result.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+    result
+}
+
+/// Time of day in UTC as `HH:MM:SS.mmm`, for message logs.
+pub fn format_timestamp(time: std::time::SystemTime) -> String {
+    let since_epoch = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = since_epoch.as_secs() % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Date and time in UTC as `YYYY-MM-DD HH:MM:SS`.
+pub fn format_date_time(time: std::time::SystemTime) -> String {
+    let timestamp = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let secs_per_day = 86400;
+    let secs_per_hour = 3600;
+    let secs_per_minute = 60;
+
+    let days_since_epoch = timestamp / secs_per_day;
+    let remaining_secs = timestamp % secs_per_day;
+
+    let hours = remaining_secs / secs_per_hour;
+    let minutes = (remaining_secs % secs_per_hour) / secs_per_minute;
+    let seconds = remaining_secs % secs_per_minute;
+
+    let mut year = 1970;
+    let mut remaining_days = days_since_epoch as i64;
+
+    loop {
+        let days_in_year = if (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0) {
+            366
+        } else {
+            365
+        };
+        if remaining_days < days_in_year {
+            break;
+        }
+        remaining_days -= days_in_year;
+        year += 1;
+    }
+
+    let is_leap = (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0);
+    let days_in_months = if is_leap {
+        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
+    } else {
+        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
+    };
+
+    let mut month = 1;
+    for &days_in_month in &days_in_months {
+        if remaining_days < days_in_month {
+            break;
+        }
+        remaining_days -= days_in_month;
+        month += 1;
+    }
+    let day = remaining_days + 1;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hours, minutes, seconds
+    )
+}
+
+/// Matches like `SCAN ... MATCH`: `*`, `?`, `[a-z]`, `[^a]` and `\` escapes.
+pub fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_match(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && glob_match(rest, &text[1..]),
+        Some((b'[', rest)) => {
+            let Some((&byte, text_rest)) = text.split_first() else {
+                return false;
+            };
+            let (negated, mut class) = match rest.split_first() {
+                Some((b'^', class)) => (true, class),
+                _ => (false, rest),
+            };
+            let mut matched = false;
+            loop {
+                match class {
+                    [] => break,
+                    [b']', tail @ ..] => {
+                        class = tail;
+                        break;
+                    }
+                    [b'\\', escaped, tail @ ..] => {
+                        matched |= *escaped == byte;
+                        class = tail;
+                    }
+                    [start, b'-', end, tail @ ..] if *end != b']' => {
+                        let (low, high) = if start <= end {
+                            (*start, *end)
+                        } else {
+                            (*end, *start)
+                        };
+                        matched |= (low..=high).contains(&byte);
+                        class = tail;
+                    }
+                    [single, tail @ ..] => {
+                        matched |= *single == byte;
+                        class = tail;
+                    }
+                }
+            }
+            matched != negated && glob_match(class, text_rest)
+        }
+        Some((b'\\', [escaped, rest @ ..])) => {
+            text.first() == Some(escaped) && glob_match(rest, &text[1..])
+        }
+        Some((literal, rest)) => text.first() == Some(literal) && glob_match(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(b"", b""));
+        assert!(glob_match(b"*", b"anything"));
+        assert!(glob_match(b"user:*", b"user:42"));
+        assert!(!glob_match(b"user:*", b"session:42"));
+        assert!(glob_match(b"h?llo", b"hallo"));
+        assert!(!glob_match(b"h?llo", b"hllo"));
+        assert!(glob_match(b"h[ae]llo", b"hello"));
+        assert!(!glob_match(b"h[^e]llo", b"hello"));
+        assert!(glob_match(b"key[0-9]", b"key7"));
+        assert!(!glob_match(b"key[0-9]", b"keyx"));
+        assert!(glob_match(b"a\\*b", b"a*b"));
+        assert!(!glob_match(b"a\\*b", b"axb"));
+    }
+
+    #[test]
+    fn test_format_date_time() {
+        let at = |secs| std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+        assert_eq!(format_date_time(at(0)), "1970-01-01 00:00:00");
+        assert_eq!(format_date_time(at(951_782_400)), "2000-02-29 00:00:00");
+        assert_eq!(format_date_time(at(1_339_518_083)), "2012-06-12 16:21:23");
+    }
+}
@@ -1,13 +1,24 @@
 mod commands;
 mod misc;
+mod profiles;
 mod settings;
 pub(crate) mod valkey;
+mod vault;
 
 pub use commands::{CommandRegistry, get_commands_dir};
 pub use misc::{
-    PathProvider, format_size, random_string, text_float_filter, text_float_filter_less_than_one,
-    type_color,
+    PathProvider, display_bytes, escape_bytes, format_date_time, format_size, format_timestamp,
+    glob_match, is_binary, random_string, text_float_filter, text_float_filter_less_than_one,
+    type_color, unescape_bytes, write_atomic,
 };
+pub use profiles::ConnectionProfile;
 pub use settings::AppSettings;
 pub use valkey::valkey_client::ValkeyClient;
-pub use valkey::{KeyType, KeyTypeExtended, ValkeyUrl, ValkeyUrlBuilder, ValkeyValue};
+pub use valkey::{
+    CallOptions, CancelHandle, Cmd, Connection, DEFAULT_TIMEOUT, Invalidation, KEYEVENT_EVENTS,
+    KEYSPACE_EVENTS, KeyTracker, KeyType, KeyTypeExtended, KeyspaceEvent, KeyspaceListener,
+    Monitor, MonitorEntry, Protocol, ProxyKind, ProxyOptions, PubSubEvent, PubSubMessage,
+    ServerInfo, SshOptions, Subscriber, SubscriptionKind, TlsOptions, ToArg, Transaction,
+    TransactionResult, ValkeyUrl, ValkeyUrlBuilder, ValkeyValue, has_notify_flags,
+};
+pub use vault::{Credentials, Vault, VaultState};
@@ -1,31 +1,49 @@
 use crate::errors::Error;
-use crate::i18n::Language;
-use crate::utils::PathProvider;
+use crate::i18n::{I18N, LangKey, Language};
+use crate::utils::profiles::{LEGACY_FILE_NAME, PROFILES_FILE_NAME, load_profiles, save_profiles};
+use crate::utils::vault::VAULT_FILE_NAME;
+use crate::utils::{ConnectionProfile, Credentials, PathProvider, Vault, VaultState, write_atomic};
 use std::collections::HashMap;
 use std::fs;
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
-use std::sync::RwLock;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 
-const SETTINGS_FILE_NAMES: [&str; 2] = ["settings.vks", "server.vks"];
+const SETTINGS_FILE_NAME: &str = "settings.vks";
+/// Setting with the name of the vault file, set while the vault is enabled.
+const VAULT_SETTING: &str = "vault";
 
 #[derive(Default)]
 pub struct AppSettings {
     settings: RwLock<HashMap<String, String>>,
-    servers: RwLock<HashMap<String, String>>,
+    servers: RwLock<Vec<ConnectionProfile>>,
+    /// Unset while the profile file could not be read, so it is not overwritten.
+    servers_loaded: AtomicBool,
+    /// Aliases of `server.vks` entries that could not be migrated, the file is kept for them.
+    unmigrated_servers: RwLock<Vec<String>>,
+    /// The passwords of the profiles are kept in the vault file.
+    vault_enabled: AtomicBool,
+    /// The vault while unlocked, for the rest of the session unless locked again.
+    vault: RwLock<Option<Vault>>,
 }
 
 impl AppSettings {
     pub fn new() -> Self {
         AppSettings {
             settings: HashMap::new().into(),
-            servers: HashMap::new().into(),
+            servers: Vec::new().into(),
+            servers_loaded: AtomicBool::new(false),
+            unmigrated_servers: RwLock::new(Vec::new()),
+            vault_enabled: AtomicBool::new(false),
+            vault: RwLock::new(None),
         }
     }
 
     pub fn new_from_file() -> Self {
         let settings = Self::default();
-        let _ = settings.load_from_file();
+        if let Err(e) = settings.load_from_file() {
+            eprintln!("Cannot load settings: {e:?}");
+        }
         settings
     }
 
@@ -34,59 +52,53 @@ impl AppSettings {
         self.servers.write()?.clear();
 
         let config_path = PathProvider::get_config_path()?;
-
-        for file_name in SETTINGS_FILE_NAMES.iter() {
-            let mut path = config_path.clone();
-            path.push(*file_name);
-            if !fs::exists(&path)? {
-                continue;
-            }
-            let file = File::open(path)?;
-            let reader = BufReader::new(file);
-
-            for line_result in reader.lines() {
-                let line = line_result?;
+        let path = config_path.join(SETTINGS_FILE_NAME);
+        if fs::exists(&path)? {
+            for line in fs::read_to_string(path)?.lines() {
                 if line.trim().is_empty() || line.starts_with('#') {
                     continue;
                 }
 
-                if let Some(eq_pos) = line.find('=') {
-                    let key = line[..eq_pos].trim().to_string();
-                    let value = line[eq_pos + 1..].trim().to_string();
-                    if *file_name == "settings.vks" {
-                        self.settings.write()?.insert(key, value);
-                    } else if *file_name == "server.vks" {
-                        self.servers.write()?.insert(key, value);
-                    }
+                if let Some((key, value)) = line.split_once('=') {
+                    self.settings
+                        .write()?
+                        .insert(key.trim().to_string(), value.trim().to_string());
                 }
             }
         }
 
+        self.servers_loaded.store(false, Ordering::Relaxed);
+        let (servers, unmigrated) = load_profiles(&config_path)?;
+        *self.servers.write()? = servers;
+        *self.unmigrated_servers.write()? = unmigrated;
+        self.servers_loaded.store(true, Ordering::Relaxed);
+        let vault_exists = match self.vault_path()? {
+            Some(path) => fs::exists(path)?,
+            None => false,
+        };
+        self.vault_enabled.store(vault_exists, Ordering::Relaxed);
         Ok(())
     }
 
+    /// The vault file named in the settings.
+    fn vault_path(&self) -> Result<Option<PathBuf>, Error> {
+        let file_name = self.get_settings_value(VAULT_SETTING, "");
+        if file_name.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(PathProvider::get_config_path()?.join(file_name)))
+    }
+
     pub fn save_to_file(&self) -> Result<(), Error> {
         let config_path = PathProvider::get_config_path()?;
 
-        for file_name in SETTINGS_FILE_NAMES.iter() {
-            let mut path = config_path.clone();
-            path.push(*file_name);
-
-            let mut file = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(path)?;
-
-            if *file_name == "settings.vks" {
-                for (key, value) in self.settings.read()?.iter() {
-                    writeln!(file, "{key}={value}")?;
-                }
-            } else if *file_name == "server.vks" {
-                for (key, value) in self.servers.read()?.iter() {
-                    writeln!(file, "{key}={value}")?;
-                }
-            }
+        let mut contents = String::new();
+        for (key, value) in self.settings.read()?.iter() {
+            contents.push_str(&format!("{key}={value}\n"));
+        }
+        write_atomic(&config_path.join(SETTINGS_FILE_NAME), contents.as_bytes())?;
+        if self.servers_loaded.load(Ordering::Relaxed) {
+            save_profiles(&config_path, &self.servers.read()?)?;
         }
         Ok(())
     }
@@ -109,10 +121,15 @@ impl AppSettings {
     }
 
     pub fn delete_settings_files(&self) -> Result<(), Box<Error>> {
-        for file_name in SETTINGS_FILE_NAMES.iter() {
+        for file_name in [
+            SETTINGS_FILE_NAME,
+            PROFILES_FILE_NAME,
+            LEGACY_FILE_NAME,
+            VAULT_FILE_NAME,
+        ] {
             let config_path = PathProvider::get_config_path()?;
             let mut path = config_path.clone();
-            path.push(*file_name);
+            path.push(file_name);
             if fs::exists(&path)? {
                 fs::remove_file(path)?;
             }
@@ -136,31 +153,196 @@ impl AppSettings {
         self.set_settings_value("theme", theme)
     }
 
-    pub fn add_server(&self, alias: &str, url: &str) -> Result<(), Box<Error>> {
-        if !self.servers.read()?.contains_key(alias) {
-            self.servers
-                .write()?
-                .insert(alias.to_owned(), url.to_owned());
+    /// Adds the profile unless there is one with its alias already.
+    pub fn add_server(&self, profile: ConnectionProfile, i18n: &I18N) -> Result<(), Box<Error>> {
+        if self
+            .servers
+            .read()?
+            .iter()
+            .any(|server| server.alias == profile.alias)
+        {
+            return Ok(());
+        }
+        let profile = self.seal(profile, i18n)?;
+        self.servers.write()?.push(profile);
+        Ok(())
+    }
+
+    /// Aliases of servers of an older version that could not be migrated.
+    pub fn unmigrated_servers(&self) -> Result<Vec<String>, Box<Error>> {
+        Ok(self.unmigrated_servers.read()?.clone())
+    }
+
+    /// The profiles in the order they were added, with the passwords of the vault while it
+    /// is unlocked.
+    pub fn get_servers(&self) -> Result<Vec<ConnectionProfile>, Box<Error>> {
+        let mut servers = self.servers.read()?.clone();
+        if let Some(vault) = self.vault.read()?.as_ref() {
+            for server in servers.iter_mut().filter(|server| server.in_vault) {
+                if let Some(credentials) = vault.get(&server.alias) {
+                    credentials.apply(server);
+                }
+            }
+        }
+        Ok(servers)
+    }
+
+    /// Replaces the profile saved as `alias`, the profile may carry a new alias.
+    pub fn update_server(
+        &self,
+        alias: &str,
+        profile: ConnectionProfile,
+        i18n: &I18N,
+    ) -> Result<(), Box<Error>> {
+        if !self
+            .servers
+            .read()?
+            .iter()
+            .any(|server| server.alias == alias)
+        {
+            return Ok(());
+        }
+        if let Some(vault) = self.vault.write()?.as_mut()
+            && alias != profile.alias
+        {
+            vault.remove(alias)?;
+        }
+        let profile = self.seal(profile, i18n)?;
+        let mut servers = self.servers.write()?;
+        if let Some(server) = servers.iter_mut().find(|server| server.alias == alias) {
+            *server = profile;
+        }
+        Ok(())
+    }
+
+    /// Moves the passwords of the profile into the vault while it is enabled.
+    fn seal(
+        &self,
+        mut profile: ConnectionProfile,
+        i18n: &I18N,
+    ) -> Result<ConnectionProfile, Box<Error>> {
+        profile.in_vault = false;
+        if !self.vault_enabled.load(Ordering::Relaxed) {
+            return Ok(profile);
         }
+        let credentials = Credentials::take(&mut profile);
+        profile.in_vault = !credentials.is_empty();
+        match self.vault.write()?.as_mut() {
+            Some(vault) => vault.set(&profile.alias, credentials)?,
+            None if credentials.is_empty() => {}
+            None => {
+                return Err(Box::new(Error::InvalidInput(
+                    i18n.get(LangKey::UnlockVaultToSavePasswords),
+                )));
+            }
+        }
+        Ok(profile)
+    }
+
+    pub fn vault_state(&self) -> VaultState {
+        if !self.vault_enabled.load(Ordering::Relaxed) {
+            VaultState::Disabled
+        } else if self.vault.read().is_ok_and(|vault| vault.is_some()) {
+            VaultState::Unlocked
+        } else {
+            VaultState::Locked
+        }
+    }
+
+    /// Creates the vault and moves the passwords of all profiles into it. The backup of the
+    /// server list of older versions is deleted, as it holds the passwords in plain text.
+    pub fn enable_vault(&self, master_password: &str, i18n: &Arc<I18N>) -> Result<(), Box<Error>> {
+        let config_path = PathProvider::get_config_path()?;
+        let mut vault = Vault::create(
+            &config_path.join(VAULT_FILE_NAME),
+            master_password,
+            i18n.clone(),
+        )?;
+        for server in self.servers.write()?.iter_mut() {
+            let credentials = Credentials::take(server);
+            server.in_vault = !credentials.is_empty();
+            vault.set(&server.alias, credentials)?;
+        }
+        *self.vault.write()? = Some(vault);
+        self.vault_enabled.store(true, Ordering::Relaxed);
+        self.set_settings_value(VAULT_SETTING, VAULT_FILE_NAME)?;
+        self.save_to_file()?;
+
+        let backup = config_path.join(format!("{LEGACY_FILE_NAME}.bak"));
+        if fs::exists(&backup)? {
+            fs::remove_file(backup)?;
+        }
+        Ok(())
+    }
+
+    /// Decrypts the vault for the rest of the session.
+    pub fn unlock_vault(&self, master_password: &str, i18n: &Arc<I18N>) -> Result<(), Box<Error>> {
+        let path = self
+            .vault_path()?
+            .ok_or_else(|| Error::InvalidInput(i18n.get(LangKey::VaultNotEnabled)))?;
+        let vault = Vault::unlock(&path, master_password, i18n.clone())?;
+        *self.vault.write()? = Some(vault);
+        Ok(())
+    }
+
+    /// Forgets the key, the master password is asked again for the next saved password.
+    pub fn lock_vault(&self) -> Result<(), Box<Error>> {
+        *self.vault.write()? = None;
         Ok(())
     }
 
-    pub fn get_servers(&self) -> Result<HashMap<String, String>, Box<Error>> {
-        Ok(self.servers.read()?.clone())
+    /// Puts the passwords back into the profiles and deletes the vault, it has to be unlocked.
+    pub fn disable_vault(&self, i18n: &I18N) -> Result<(), Box<Error>> {
+        let vault = self
+            .vault
+            .write()?
+            .take()
+            .ok_or_else(|| Error::InvalidInput(i18n.get(LangKey::UnlockVaultFirst)))?;
+        let credentials = vault.delete()?;
+        for server in self.servers.write()?.iter_mut() {
+            if let Some(credentials) = credentials.get(&server.alias) {
+                credentials.apply(server);
+            }
+            server.in_vault = false;
+        }
+        self.vault_enabled.store(false, Ordering::Relaxed);
+        self.settings.write()?.remove(VAULT_SETTING);
+        self.save_to_file()?;
+        Ok(())
     }
 
-    pub fn update_server(&self, alias: &str, url: &str) -> Result<(), Box<Error>> {
-        if self.servers.read()?.contains_key(alias) {
-            self.servers
-                .write()?
-                .insert(alias.to_owned(), url.to_owned());
-            self.save_to_file()?;
+    /// Stores the type and the time of a successful connect.
+    pub fn record_connection(&self, alias: &str, server_type: &str) -> Result<(), Box<Error>> {
+        if let Some(server) = self
+            .servers
+            .write()?
+            .iter_mut()
+            .find(|server| server.alias == alias)
+        {
+            server.record_connection(server_type);
         }
+        self.save_to_file()?;
         Ok(())
     }
 
-    pub fn delete_server(&self, alias: &str) -> Result<(), Box<Error>> {
-        self.servers.write()?.remove(alias);
+    /// Removes the profile and its passwords, a locked vault has to be unlocked first as the
+    /// passwords can only be removed from the decrypted vault.
+    pub fn delete_server(&self, alias: &str, i18n: &I18N) -> Result<(), Box<Error>> {
+        let in_vault = self
+            .servers
+            .read()?
+            .iter()
+            .any(|server| server.alias == alias && server.in_vault);
+        match self.vault.write()?.as_mut() {
+            Some(vault) => vault.remove(alias)?,
+            None if in_vault => {
+                return Err(Box::new(Error::InvalidInput(
+                    i18n.get(LangKey::UnlockVaultFirst),
+                )));
+            }
+            None => {}
+        }
+        self.servers.write()?.retain(|server| server.alias != alias);
         self.save_to_file()?;
         Ok(())
     }
@@ -168,6 +350,8 @@ impl AppSettings {
 
 #[test]
 fn load_settings_from_file_test() {
+    let config_path = PathProvider::get_config_path().expect("No config path.");
+    assert!(config_path.starts_with(std::env::temp_dir()));
     let settings = AppSettings::new();
     settings
         .delete_settings_files()
@@ -187,12 +371,28 @@ fn load_settings_from_file_test() {
 
     let servers = settings.get_servers();
     assert_eq!(servers.expect("Cannot get servers").len(), 0);
+    let url = crate::utils::ValkeyUrl::parse_valkey_url(None, "valkey://127.0.0.1:1234").unwrap();
     settings
-        .add_server("srv0", "127.0.0.1:1234")
+        .add_server(ConnectionProfile::new("srv0", &url), &I18N::default())
         .expect("Failed to add server.");
     settings.save_to_file().expect("Failed to save settings.");
     settings.load_from_file().expect("Failed to load settings.");
     let servers = settings.get_servers().expect("Cannot get servers");
     assert_eq!(servers.len(), 1);
-    assert_eq!(servers.get("srv0"), Some(&"127.0.0.1:1234".to_string()));
+    assert_eq!(servers[0].alias, "srv0");
+    assert_eq!(servers[0].connection_string(), "valkey://127.0.0.1:1234");
+}
+
+#[test]
+fn delete_server_with_locked_vault_test() {
+    let settings = AppSettings::new();
+    let url = crate::utils::ValkeyUrl::parse_valkey_url(None, "valkey://127.0.0.1:1234").unwrap();
+    let mut profile = ConnectionProfile::new("srv0", &url);
+    profile.in_vault = true;
+    settings.servers.write().unwrap().push(profile);
+    settings.vault_enabled.store(true, Ordering::Relaxed);
+
+    // The password would stay behind in the encrypted file.
+    assert!(settings.delete_server("srv0", &I18N::default()).is_err());
+    assert_eq!(settings.get_servers().unwrap().len(), 1);
 }
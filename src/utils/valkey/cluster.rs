@@ -0,0 +1,520 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use crate::utils::ValkeyUrl;
+use crate::utils::valkey::slots::{Redirect, SlotMap, key_slot};
+use crate::utils::valkey::{
+    CallOptions, Cmd, Connection, ConnectionPool, PooledConnection, ValkeyValue,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Redirects followed for a single command before giving up.
+const MAX_REDIRECTS: usize = 5;
+/// Minimum time between two reloads of the slot map triggered by `MOVED`.
+const SLOT_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where the first key of a command is found, taken from the `COMMAND` reply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeyPosition {
+    Keyless,
+    Index(usize),
+    /// Keys depend on other arguments (`EVAL`, `XREAD`), resolved by `COMMAND GETKEYS`.
+    Movable,
+}
+
+#[derive(Default)]
+struct CommandKeys {
+    positions: HashMap<String, KeyPosition>,
+}
+
+impl CommandKeys {
+    fn from_command_reply(reply: &ValkeyValue) -> Self {
+        let mut keys = Self::default();
+        if let ValkeyValue::Array(entries) = reply {
+            for entry in entries {
+                keys.insert(entry);
+            }
+        }
+        keys
+    }
+
+    /// Entries look like `[name, arity, flags, first key, last key, step, ...]`,
+    /// subcommands like `object|encoding` follow as tenth element.
+    fn insert(&mut self, entry: &ValkeyValue) {
+        let ValkeyValue::Array(fields) = entry else {
+            return;
+        };
+        let (Some(name), Some(first_key)) = (fields.first(), fields.get(3)) else {
+            return;
+        };
+        let movable = fields
+            .get(2)
+            .map(|flags| flags.clone().into_bytes_vec())
+            .is_some_and(|flags| flags.iter().any(|flag| flag == b"movablekeys"));
+        let position = match first_key.as_int() {
+            Some(index) if index > 0 => KeyPosition::Index(index as usize),
+            _ if movable => KeyPosition::Movable,
+            _ => KeyPosition::Keyless,
+        };
+        self.positions
+            .insert(name.to_string().to_lowercase(), position);
+
+        if let Some(ValkeyValue::Array(subcommands)) = fields.get(9) {
+            for subcommand in subcommands {
+                self.insert(subcommand);
+            }
+        }
+    }
+
+    fn position(&self, cmd: &Cmd) -> KeyPosition {
+        let name = cmd.name().to_lowercase();
+        cmd.arguments()
+            .get(1)
+            .and_then(|sub| {
+                let sub = String::from_utf8_lossy(sub).to_lowercase();
+                self.positions.get(&format!("{name}|{sub}"))
+            })
+            .or_else(|| self.positions.get(&name))
+            .copied()
+            .unwrap_or(KeyPosition::Keyless)
+    }
+}
+
+/// Routes commands to the primary owning the hash slot of their key and follows
+/// `MOVED`/`ASK` redirects. Every node gets its own [`ConnectionPool`].
+pub struct ClusterClient {
+    url: ValkeyUrl,
+    i18n: Arc<I18N>,
+    nodes: Mutex<HashMap<String, Arc<ConnectionPool>>>,
+    slots: RwLock<SlotMap>,
+    keys: CommandKeys,
+    last_refresh: Mutex<Instant>,
+}
+
+impl ClusterClient {
+    /// Reads the topology and the key positions of all commands through the first connection.
+    pub fn new(url: ValkeyUrl, i18n: Arc<I18N>, mut connection: Connection) -> Result<Self, Error> {
+        let keys = CommandKeys::from_command_reply(&connection.exec(&Cmd::new("COMMAND"))?);
+        let slots = Self::load_slots(&mut connection, &url, &i18n)?;
+        let seed = Arc::new(ConnectionPool::new(
+            url.clone(),
+            i18n.clone(),
+            Some(connection),
+        ));
+        Ok(Self {
+            nodes: Mutex::new(HashMap::from([(url.address(), seed)])),
+            url,
+            i18n,
+            slots: RwLock::new(slots),
+            keys,
+            last_refresh: Mutex::new(Instant::now()),
+        })
+    }
+
+    fn load_slots(
+        connection: &mut Connection,
+        url: &ValkeyUrl,
+        i18n: &I18N,
+    ) -> Result<SlotMap, Error> {
+        let shards = connection.exec(&Cmd::new("CLUSTER").arg("SHARDS"))?;
+        let slots = if shards.is_error() {
+            let slots = connection.exec(&Cmd::new("CLUSTER").arg("SLOTS"))?;
+            SlotMap::from_slots(&slots, url.host())
+        } else {
+            SlotMap::from_shards(&shards, url.host(), url.tls().is_some())
+        };
+        slots
+            .filter(|slots| !slots.is_empty())
+            .ok_or_else(|| Error::Network(i18n.get(LangKey::ClusterSlotsUnavailable)))
+    }
+
+    /// Reloads the slot map, at most once per [`SLOT_REFRESH_INTERVAL`].
+    fn refresh_slots(&self) -> Result<(), Error> {
+        {
+            let mut last_refresh = self.last_refresh.lock()?;
+            if last_refresh.elapsed() < SLOT_REFRESH_INTERVAL {
+                return Ok(());
+            }
+            *last_refresh = Instant::now();
+        }
+        let mut connection = self.node(&self.default_node()?)?.get()?;
+        let slots = Self::load_slots(&mut connection, &self.url, &self.i18n)?;
+        *self.slots.write()? = slots;
+        Ok(())
+    }
+
+    fn node(&self, address: &str) -> Result<Arc<ConnectionPool>, Error> {
+        let mut nodes = self.nodes.lock()?;
+        if let Some(pool) = nodes.get(address) {
+            return Ok(pool.clone());
+        }
+        let (host, port) = address
+            .rsplit_once(':')
+            .and_then(|(host, port)| Some((host, port.parse().ok()?)))
+            .ok_or_else(|| Error::Network(format!("{address}: invalid node address")))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let pool = Arc::new(ConnectionPool::new(
+            self.url.with_address(host, port),
+            self.i18n.clone(),
+            None,
+        ));
+        nodes.insert(address.to_string(), pool.clone());
+        Ok(pool)
+    }
+
+    /// Node for commands without a key: the primary of slot 0.
+    fn default_node(&self) -> Result<String, Error> {
+        Ok(self
+            .slots
+            .read()?
+            .primary(0)
+            .map(String::from)
+            .unwrap_or_else(|| self.url.address()))
+    }
+
+    /// Address of the primary owning the first key of the command.
+    fn route(&self, cmd: &Cmd) -> Result<String, Error> {
+        let key = match self.keys.position(cmd) {
+            KeyPosition::Index(index) => cmd.arguments().get(index).cloned(),
+            KeyPosition::Movable => {
+                let getkeys = Cmd::new("COMMAND").arg("GETKEYS").args(cmd.arguments());
+                let mut connection = self.node(&self.default_node()?)?.get()?;
+                let keys = connection.exec(&getkeys)?;
+                (!keys.is_error())
+                    .then(|| keys.into_bytes_vec().into_iter().next())
+                    .flatten()
+            }
+            KeyPosition::Keyless => None,
+        };
+        if let Some(key) = key
+            && let Some(primary) = self.slots.read()?.primary(key_slot(&key))
+        {
+            return Ok(primary.to_string());
+        }
+        self.default_node()
+    }
+
+    /// The options apply to each node the command is sent to.
+    pub fn exec_with(&self, cmd: &Cmd, options: &CallOptions) -> Result<ValkeyValue, Error> {
+        match cmd.name().as_str() {
+            "SCAN" => self.scan(cmd, options),
+            "DBSIZE" => self.dbsize(options),
+            _ => self.exec_on(self.route(cmd)?, false, cmd, options),
+        }
+    }
+
+    /// Sends the commands grouped by node as one pipeline per node. Replies keep the
+    /// order of `cmds`; redirected commands are repeated one by one at the redirect target.
+    pub fn exec_pipelined(&self, cmds: &[Cmd]) -> Result<Vec<ValkeyValue>, Error> {
+        let mut batches: Vec<(String, Vec<usize>)> = Vec::new();
+        for (index, cmd) in cmds.iter().enumerate() {
+            let address = self.route(cmd)?;
+            match batches.iter_mut().find(|(a, _)| *a == address) {
+                Some((_, indices)) => indices.push(index),
+                None => batches.push((address, vec![index])),
+            }
+        }
+
+        let mut values = vec![ValkeyValue::Null; cmds.len()];
+        for (address, indices) in batches {
+            let batch: Vec<Cmd> = indices.iter().map(|&i| cmds[i].clone()).collect();
+            let replies = self.node(&address)?.get()?.exec_pipelined(&batch)?;
+            for (index, value) in indices.into_iter().zip(replies) {
+                values[index] = match Redirect::parse(&value) {
+                    Some(redirect) => {
+                        let (to, asking) = self.follow(redirect)?;
+                        self.exec_on(to, asking, &cmds[index], &CallOptions::default())?
+                    }
+                    None => value,
+                };
+            }
+        }
+        Ok(values)
+    }
+
+    /// Runs the command on the node, `asking` sends `ASKING` first as an `ASK` redirect
+    /// requires.
+    fn exec_on(
+        &self,
+        mut address: String,
+        mut asking: bool,
+        cmd: &Cmd,
+        options: &CallOptions,
+    ) -> Result<ValkeyValue, Error> {
+        for _ in 0..MAX_REDIRECTS {
+            let mut connection = self.node(&address)?.get()?;
+            if asking {
+                connection.exec(&Cmd::new("ASKING"))?;
+            }
+            let value = connection.exec_with(cmd, options)?;
+            drop(connection);
+            match Redirect::parse(&value) {
+                Some(redirect) => (address, asking) = self.follow(redirect)?,
+                None => return Ok(value),
+            }
+        }
+        Err(Error::Network(self.i18n.get(LangKey::TooManyRedirects)))
+    }
+
+    /// The node to repeat a redirected command on and whether it needs `ASKING`. A `MOVED`
+    /// slot belongs to the new node from now on.
+    fn follow(&self, redirect: Redirect) -> Result<(String, bool), Error> {
+        match redirect {
+            Redirect::Moved { slot, address } => {
+                self.slots.write()?.set_primary(slot, &address);
+                self.refresh_slots()?;
+                Ok((address, false))
+            }
+            Redirect::Ask { address, .. } => Ok((address, true)),
+        }
+    }
+
+    /// `SCAN` over all primaries one after the other. The lowest byte of the cursor
+    /// holds the index of the node, the rest is the cursor of that node.
+    fn scan(&self, cmd: &Cmd, options: &CallOptions) -> Result<ValkeyValue, Error> {
+        let primaries = self.slots.read()?.primaries();
+        let cursor: u64 = cmd
+            .arguments()
+            .get(1)
+            .and_then(|cursor| String::from_utf8_lossy(cursor).parse().ok())
+            .unwrap_or(0);
+        let node = (cursor & 0xFF) as usize;
+        let Some(address) = primaries.get(node) else {
+            return Ok(ValkeyValue::Array(vec![
+                ValkeyValue::BulkString(b"0".to_vec()),
+                ValkeyValue::Array(Vec::new()),
+            ]));
+        };
+
+        let node_scan = Cmd::new("SCAN")
+            .arg(cursor >> 8)
+            .args(cmd.arguments().iter().skip(2));
+        let value = self.exec_on(address.clone(), false, &node_scan, options)?;
+        let ValkeyValue::Array(mut reply) = value else {
+            return Ok(value);
+        };
+        if let Some(next) = reply.first_mut() {
+            let node_cursor: u64 = next
+                .as_bytes()
+                .and_then(|c| String::from_utf8_lossy(c).parse().ok())
+                .unwrap_or(0);
+            let cursor = match node_cursor {
+                0 if node + 1 < primaries.len() => node as u64 + 1,
+                0 => 0,
+                node_cursor => node_cursor << 8 | node as u64,
+            };
+            *next = ValkeyValue::BulkString(cursor.to_string().into_bytes());
+        }
+        Ok(ValkeyValue::Array(reply))
+    }
+
+    /// Sum of the key counts of all primaries.
+    fn dbsize(&self, options: &CallOptions) -> Result<ValkeyValue, Error> {
+        let mut total = 0;
+        for address in self.slots.read()?.primaries() {
+            total += self
+                .exec_on(address, false, &Cmd::new("DBSIZE"), options)?
+                .as_int()
+                .unwrap_or(0);
+        }
+        Ok(ValkeyValue::Integer(total))
+    }
+
+    /// Pooled connection to the node owning the key of `route`, or the default node.
+    pub fn connection(&self, route: Option<&Cmd>) -> Result<PooledConnection, Error> {
+        self.node(&self.node_for(route)?)?.get()
+    }
+
+    pub fn dedicated_connection(&self, route: Option<&Cmd>) -> Result<Connection, Error> {
+        self.node(&self.node_for(route)?)?.dedicated()
+    }
+
+    pub fn dedicated_connection_to(&self, address: &str) -> Result<Connection, Error> {
+        self.node(address)?.dedicated()
+    }
+
+    pub fn set_tracking(&self, address: &str, redirect: Option<i64>) -> Result<(), Error> {
+        self.node(address)?.set_tracking(redirect)
+    }
+
+    pub fn primaries(&self) -> Result<Vec<String>, Error> {
+        Ok(self.slots.read()?.primaries())
+    }
+
+    fn node_for(&self, route: Option<&Cmd>) -> Result<String, Error> {
+        match route {
+            Some(cmd) => self.route(cmd),
+            None => self.default_node(),
+        }
+    }
+
+    pub fn is_reconnecting(&self) -> bool {
+        self.nodes
+            .lock()
+            .is_ok_and(|nodes| nodes.values().any(|pool| pool.is_reconnecting()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ValkeyValue::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    type Handler = dyn Fn(&[String], &mut bool) -> String + Send + Sync;
+
+    /// A cluster node on localhost answering RESP2 commands with `reply`, which gets the
+    /// arguments and whether `ASKING` was sent on the connection. Returns the commands
+    /// received.
+    fn fake_node(listener: TcpListener, reply: Arc<Handler>) -> Arc<Mutex<Vec<String>>> {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let received = log.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (reply, log) = (reply.clone(), log.clone());
+                std::thread::spawn(move || {
+                    let mut writer = stream.try_clone().unwrap();
+                    let mut reader = BufReader::new(stream);
+                    let mut asking = false;
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                        let count: usize = line.trim()[1..].parse().unwrap();
+                        let mut args = Vec::new();
+                        for _ in 0..count * 2 {
+                            line.clear();
+                            reader.read_line(&mut line).unwrap();
+                            if !line.starts_with('$') {
+                                args.push(line.trim_end().to_string());
+                            }
+                        }
+                        log.lock().unwrap().push(args.join(" "));
+                        let answer = match args[0].to_uppercase().as_str() {
+                            "PING" => "+PONG\r\n".to_string(),
+                            "CLIENT" => "+OK\r\n".to_string(),
+                            "COMMAND" => "*0\r\n".to_string(),
+                            _ => reply(&args, &mut asking),
+                        };
+                        writer.write_all(answer.as_bytes()).unwrap();
+                        line.clear();
+                    }
+                });
+            }
+        });
+        received
+    }
+
+    #[test]
+    fn test_pipeline_follows_redirects() {
+        let (listener_a, listener_b) = (
+            TcpListener::bind("127.0.0.1:0").unwrap(),
+            TcpListener::bind("127.0.0.1:0").unwrap(),
+        );
+        let port_a = listener_a.local_addr().unwrap().port();
+        let port_b = listener_b.local_addr().unwrap().port();
+        // A owns all slots, but `ask` is being migrated to B and `moved` already is.
+        let log_a = fake_node(
+            listener_a,
+            Arc::new(move |args, _| match args[0].as_str() {
+                "CLUSTER" if args[1] == "SLOTS" => {
+                    format!("*1\r\n*3\r\n:0\r\n:16383\r\n*2\r\n$9\r\n127.0.0.1\r\n:{port_a}\r\n")
+                }
+                "GET" if args[1] == "ask" => format!("-ASK 0 127.0.0.1:{port_b}\r\n"),
+                "GET" => format!("-MOVED 0 127.0.0.1:{port_b}\r\n"),
+                _ => "-ERR unknown command\r\n".to_string(),
+            }),
+        );
+        let log_b = fake_node(
+            listener_b,
+            Arc::new(move |args, asking| match args[0].as_str() {
+                "ASKING" => {
+                    *asking = true;
+                    "+OK\r\n".to_string()
+                }
+                "GET" if args[1] == "ask" && !*asking => {
+                    format!("-MOVED 0 127.0.0.1:{port_a}\r\n")
+                }
+                "GET" => format!("${}\r\n{}\r\n", args[1].len(), args[1]),
+                _ => "-ERR unknown command\r\n".to_string(),
+            }),
+        );
+
+        let url =
+            ValkeyUrl::parse_valkey_url(None, &format!("valkey://127.0.0.1:{port_a}?protocol=2"))
+                .unwrap();
+        let i18n = Arc::new(I18N::default());
+        let (connection, _) = Connection::open(&url, None, &i18n).unwrap();
+        let cluster = ClusterClient::new(url, i18n, connection).unwrap();
+        let values = cluster
+            .exec_pipelined(&[Cmd::new("GET").arg("ask"), Cmd::new("GET").arg("moved")])
+            .unwrap();
+        assert_eq!(
+            values.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["ask", "moved"]
+        );
+        let gets = |log: &Mutex<Vec<String>>| {
+            let log = log.lock().unwrap();
+            log.iter()
+                .filter(|cmd| cmd.starts_with("GET") || *cmd == "ASKING")
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        // Each command reaches the node that refused it only once.
+        assert_eq!(gets(&log_a), ["GET ask", "GET moved"]);
+        assert_eq!(gets(&log_b), ["ASKING", "GET ask", "GET moved"]);
+    }
+
+    fn entry(
+        name: &str,
+        first_key: i64,
+        flags: &[&str],
+        subcommands: Vec<ValkeyValue>,
+    ) -> ValkeyValue {
+        Array(vec![
+            BulkString(name.as_bytes().to_vec()),
+            Integer(-2),
+            Sets(flags.iter().map(|f| SimpleString(f.to_string())).collect()),
+            Integer(first_key),
+            Integer(first_key),
+            Integer(1),
+            Array(vec![]),
+            Array(vec![]),
+            Array(vec![]),
+            Array(subcommands),
+        ])
+    }
+
+    #[test]
+    fn test_command_keys() {
+        let reply = Array(vec![
+            entry("get", 1, &["readonly"], vec![]),
+            entry("eval", 0, &["noscript", "movablekeys"], vec![]),
+            entry("ping", 0, &["fast"], vec![]),
+            entry(
+                "object",
+                0,
+                &[],
+                vec![entry("object|encoding", 2, &["readonly"], vec![])],
+            ),
+        ]);
+        let keys = CommandKeys::from_command_reply(&reply);
+        assert_eq!(
+            keys.position(&Cmd::new("GET").arg("k")),
+            KeyPosition::Index(1)
+        );
+        assert_eq!(
+            keys.position(&Cmd::new("eval").arg("s").arg(1).arg("k")),
+            KeyPosition::Movable
+        );
+        assert_eq!(keys.position(&Cmd::new("PING")), KeyPosition::Keyless);
+        assert_eq!(
+            keys.position(&Cmd::new("OBJECT").arg("ENCODING").arg("k")),
+            KeyPosition::Index(2)
+        );
+        assert_eq!(
+            keys.position(&Cmd::new("UNKNOWN").arg("k")),
+            KeyPosition::Keyless
+        );
+    }
+}
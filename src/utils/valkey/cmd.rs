@@ -0,0 +1,232 @@
+use crate::errors::Error;
+use std::fmt::Display;
+
+/// A single command as a vector of raw arguments, encoded directly to RESP,
+/// e.g. `Cmd::new("HSET").arg(key).arg(field).arg(value)`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cmd {
+    args: Vec<Vec<u8>>,
+}
+
+pub trait ToArg {
+    fn to_arg(&self) -> Vec<u8>;
+}
+
+impl ToArg for [u8] {
+    fn to_arg(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl<const N: usize> ToArg for [u8; N] {
+    fn to_arg(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl ToArg for Vec<u8> {
+    fn to_arg(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
+impl ToArg for str {
+    fn to_arg(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl ToArg for String {
+    fn to_arg(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+macro_rules! impl_to_arg_display {
+    ($($t:ty),*) => {
+        $(impl ToArg for $t {
+            fn to_arg(&self) -> Vec<u8> {
+                self.to_string().into_bytes()
+            }
+        })*
+    };
+}
+
+impl_to_arg_display!(i32, i64, u32, u64, usize, f64);
+
+impl<T: ToArg + ?Sized> ToArg for &T {
+    fn to_arg(&self) -> Vec<u8> {
+        (**self).to_arg()
+    }
+}
+
+impl Cmd {
+    pub fn new(name: &str) -> Self {
+        Self {
+            args: vec![name.as_bytes().to_vec()],
+        }
+    }
+
+    pub fn arg<A: ToArg>(mut self, arg: A) -> Self {
+        self.args.push(arg.to_arg());
+        self
+    }
+
+    pub fn args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: ToArg,
+    {
+        self.args.extend(args.into_iter().map(|arg| arg.to_arg()));
+        self
+    }
+
+    /// Splits a command line as typed by the user (e.g. in the workbench) into arguments.
+    /// Single and double quotes group words, `\"`, `\'`, `\\`, `\n`, `\t` and `\xHH` are
+    /// unescaped inside quotes. Fails for a quote that is not closed.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut args = Vec::new();
+        let mut current: Vec<u8> = Vec::new();
+        let mut quote: Option<char> = None;
+        let mut has_token = false;
+        let mut chars = input.trim().chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' if quote.is_some() => match chars.next() {
+                    Some('n') => current.push(b'\n'),
+                    Some('r') => current.push(b'\r'),
+                    Some('t') => current.push(b'\t'),
+                    Some('x') => {
+                        let hex: String = chars.clone().take(2).collect();
+                        if let Ok(byte) = u8::from_str_radix(&hex, 16)
+                            && hex.len() == 2
+                        {
+                            current.push(byte);
+                            chars.nth(1);
+                        } else {
+                            current.extend(b"\\x");
+                        }
+                    }
+                    Some(next) if next == '"' || next == '\'' || next == '\\' => {
+                        current.extend(next.to_string().as_bytes());
+                    }
+                    Some(next) => {
+                        current.push(b'\\');
+                        current.extend(next.to_string().as_bytes());
+                    }
+                    None => current.push(b'\\'),
+                },
+                '"' | '\'' if quote.is_none() => {
+                    quote = Some(ch);
+                    has_token = true;
+                }
+                c if Some(c) == quote => {
+                    quote = None;
+                }
+                c if c.is_whitespace() && quote.is_none() => {
+                    if has_token {
+                        args.push(std::mem::take(&mut current));
+                        has_token = false;
+                    }
+                }
+                c => {
+                    current.extend(c.to_string().as_bytes());
+                    has_token = true;
+                }
+            }
+        }
+
+        if let Some(quote) = quote {
+            return Err(Error::InvalidInput(format!("Unterminated {quote} quote")));
+        }
+        if has_token {
+            args.push(current);
+        }
+
+        Ok(Self { args })
+    }
+
+    pub fn name(&self) -> String {
+        self.args
+            .first()
+            .map(|name| String::from_utf8_lossy(name).to_uppercase())
+            .unwrap_or_default()
+    }
+
+    pub fn arguments(&self) -> &[Vec<u8>] {
+        &self.args
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut resp = format!("*{}\r\n", self.args.len()).into_bytes();
+        for arg in &self.args {
+            resp.extend(format!("${}\r\n", arg.len()).as_bytes());
+            resp.extend(arg);
+            resp.extend(b"\r\n");
+        }
+        resp
+    }
+}
+
+impl Display for Cmd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| crate::utils::display_bytes(arg))
+            .collect();
+        write!(f, "{}", args.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let cmd = Cmd::new("HSET")
+            .arg("my key")
+            .arg(b"f\"\\\n".as_slice())
+            .arg(42);
+        assert_eq!(
+            cmd.encode(),
+            b"*4\r\n$4\r\nHSET\r\n$6\r\nmy key\r\n$4\r\nf\"\\\n\r\n$2\r\n42\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_binary_args() {
+        let cmd = Cmd::new("SET").arg(vec![0x00, 0xff]).arg([0xc3, 0x28]);
+        assert_eq!(
+            cmd.arguments(),
+            &[b"SET".to_vec(), vec![0x00, 0xff], vec![0xc3, 0x28]]
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let cmd = Cmd::parse(r#"set "a b" 'it\'s' "\x00\n" ''  plain"#).unwrap();
+        assert_eq!(
+            cmd.arguments(),
+            &[
+                b"set".to_vec(),
+                b"a b".to_vec(),
+                b"it's".to_vec(),
+                vec![0x00, b'\n'],
+                b"".to_vec(),
+                b"plain".to_vec(),
+            ]
+        );
+        assert_eq!(cmd.name(), "SET");
+
+        assert!(Cmd::parse(r#"set k "v"#).is_err());
+        assert!(Cmd::parse(r#"set k 'it\'s"#).is_err());
+        assert!(Cmd::parse(r#"set k "it's""#).is_ok());
+    }
+}
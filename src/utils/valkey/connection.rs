@@ -0,0 +1,461 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use crate::utils::valkey::stream::ValkeyStream;
+use crate::utils::valkey::{Cmd, Protocol, RespDecoder, ValkeyValue, proxy, sentinel, tls};
+use crate::utils::{CommandRegistry, ValkeyUrl};
+use std::io;
+use std::io::ErrorKind;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Name shown for our connections in `CLIENT LIST`.
+const CLIENT_NAME: &str = "vk_commander";
+/// How long a call waits for its reply unless [`CallOptions`] say otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Reads wake up this often to check the deadline and the cancel handle of the call.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Limits of a single call.
+#[derive(Clone, Debug, Default)]
+pub struct CallOptions {
+    /// How long to wait for the reply, the timeout of the url or [`DEFAULT_TIMEOUT`] if
+    /// unset and without limit for `Duration::MAX`, e.g. for blocking commands.
+    pub timeout: Option<Duration>,
+    pub cancel: Option<CancelHandle>,
+}
+
+/// Stops the calls it is passed to from another thread, e.g. a stop button. A cancelled
+/// call closes its connection, as the reply would still arrive later on it.
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A socket together with the decoder state of the replies read from it.
+pub struct Connection {
+    stream: ValkeyStream,
+    decoder: RespDecoder,
+    db: Option<u32>,
+    /// Reply timeout of calls without their own.
+    timeout: Duration,
+    broken: bool,
+    /// A call was cancelled, only this connection is affected.
+    cancelled: bool,
+    /// Opened through sentinels, where a `READONLY` reply means the primary was demoted.
+    via_sentinel: bool,
+    /// Refuses write commands, set for read-only urls.
+    write_filter: Option<Arc<CommandRegistry>>,
+    /// Client id the invalidations of keys read on this connection are redirected to.
+    tracking: Option<i64>,
+}
+
+impl Connection {
+    /// Opens a socket to the server and runs the handshake (AUTH, SELECT, PING, HELLO 3 with
+    /// the client name). `db` overrides the database of the url. Returns the reply to `HELLO`,
+    /// or `None` when the server does not support RESP3 or the url asks for RESP2 and the
+    /// connection stays on RESP2.
+    ///
+    /// For sentinel urls the primary is looked up first, so every reconnect follows a failover.
+    pub fn open(
+        valkey_url: &ValkeyUrl,
+        db: Option<u32>,
+        i18n: &I18N,
+    ) -> Result<(Self, Option<ValkeyValue>), Error> {
+        if valkey_url.master_name().is_some() {
+            let primary = sentinel::resolve_primary(valkey_url, i18n)?;
+            let (mut connection, hello) = Self::open(&primary, db, i18n)?;
+            if !sentinel::is_primary(&mut connection)? {
+                return Err(Error::Network(i18n.get(LangKey::NotAPrimary)));
+            }
+            connection.via_sentinel = true;
+            return Ok((connection, hello));
+        }
+
+        let stream = Self::open_stream(valkey_url, i18n)?;
+        let mut connection = Self {
+            stream,
+            decoder: RespDecoder::new(),
+            db: None,
+            timeout: valkey_url.timeout().unwrap_or(DEFAULT_TIMEOUT),
+            broken: false,
+            cancelled: false,
+            via_sentinel: false,
+            write_filter: valkey_url.write_filter().cloned(),
+            tracking: None,
+        };
+
+        if valkey_url.password().is_some() || valkey_url.username().is_some() {
+            let user = valkey_url.username().unwrap_or("");
+            let pass = valkey_url.password().unwrap_or("");
+
+            let auth_cmd = if !user.is_empty() {
+                Cmd::new("AUTH").arg(user).arg(pass)
+            } else {
+                Cmd::new("AUTH").arg(pass)
+            };
+            if connection.exec(&auth_cmd)?.to_string() != "OK" {
+                return Err(Error::Network(i18n.get(LangKey::AuthFailed)))?;
+            }
+        }
+
+        if let Some(db_index) = db.or(valkey_url.db()) {
+            let select_cmd = Cmd::new("SELECT").arg(db_index);
+            if connection.exec(&select_cmd)?.to_string() != "OK" {
+                return Err(Error::Network(i18n.get(LangKey::SelectDbFail)))?;
+            }
+        }
+
+        // A missing password shows up here as `NOAUTH`, which has its own hint.
+        if connection
+            .exec(&Cmd::new("PING"))?
+            .into_result()?
+            .to_string()
+            != "PONG"
+        {
+            return Err(Error::Network(i18n.get(LangKey::ServerConnectionFailed)))?;
+        }
+
+        let client_name = valkey_url.client_name().unwrap_or(CLIENT_NAME);
+        let hello = match valkey_url.protocol() {
+            Some(Protocol::Resp2) => None,
+            _ => {
+                Some(connection.exec(&Cmd::new("HELLO").arg("3").arg("SETNAME").arg(client_name))?)
+            }
+        };
+        let Some(hello) = hello.filter(|hello| !hello.is_error()) else {
+            // Servers without RESP3 keep talking RESP2, the name is best effort there.
+            connection.exec(&Cmd::new("CLIENT").arg("SETNAME").arg(client_name))?;
+            return Ok((connection, None));
+        };
+        Ok((connection, Some(hello)))
+    }
+
+    pub fn exec(&mut self, cmd: &Cmd) -> Result<ValkeyValue, Error> {
+        self.exec_with(cmd, &CallOptions::default())
+    }
+
+    /// Runs a command with its own timeout or cancel handle. A call that times out or is
+    /// cancelled leaves the connection broken, it is closed instead of being reused.
+    pub fn exec_with(&mut self, cmd: &Cmd, options: &CallOptions) -> Result<ValkeyValue, Error> {
+        self.check_writable(cmd)?;
+        let result = self
+            .send(&cmd.encode())
+            .and_then(|_| self.read_reply(options));
+        match &result {
+            Ok(value) => self.track_reply(cmd, value),
+            Err(_) => self.broken = true,
+        }
+        result
+    }
+
+    /// Sends a command without waiting for its reply, for subscribed connections where
+    /// replies arrive as push messages read with [`Self::read_push`].
+    pub fn send_command(&mut self, cmd: &Cmd) -> Result<(), Error> {
+        self.check_writable(cmd)?;
+        let result = self.send(&cmd.encode());
+        if result.is_err() {
+            self.broken = true;
+        }
+        result
+    }
+
+    /// Waits up to `timeout` for the next value the server sends on its own, e.g. a
+    /// published message. Returns `None` if nothing arrived in time.
+    pub fn read_push(&mut self, timeout: Duration) -> Result<Option<ValkeyValue>, Error> {
+        let options = CallOptions {
+            timeout: Some(timeout),
+            cancel: None,
+        };
+        match self.read_value(&options) {
+            Ok(value) => Ok(Some(value)),
+            Err(Error::Io(e)) if e.kind() == ErrorKind::TimedOut => Ok(None),
+            Err(e) => {
+                self.broken = true;
+                Err(e)
+            }
+        }
+    }
+
+    pub fn exec_pipelined(&mut self, cmds: &[Cmd]) -> Result<Vec<ValkeyValue>, Error> {
+        for cmd in cmds {
+            self.check_writable(cmd)?;
+        }
+        let mut resp = Vec::new();
+        for cmd in cmds {
+            resp.extend(cmd.encode());
+        }
+        if let Err(e) = self.send(&resp) {
+            self.broken = true;
+            return Err(e);
+        }
+
+        let mut values = Vec::with_capacity(cmds.len());
+        for cmd in cmds {
+            match self.read_reply(&CallOptions::default()) {
+                Ok(value) => {
+                    self.track_reply(cmd, &value);
+                    values.push(value);
+                }
+                Err(e) => {
+                    self.broken = true;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Fails with [`Error::ReadOnly`] for commands writing data on a read-only connection.
+    fn check_writable(&self, cmd: &Cmd) -> Result<(), Error> {
+        match &self.write_filter {
+            Some(registry) if registry.is_write(cmd) => Err(Error::ReadOnly(cmd.name())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Turns client tracking on with the invalidations of the keys read on this connection
+    /// sent to the connection with the client id `redirect`, or off for `None`.
+    pub fn set_tracking(&mut self, redirect: Option<i64>) -> Result<(), Error> {
+        let cmd = match redirect {
+            Some(id) => Cmd::new("CLIENT")
+                .arg("TRACKING")
+                .arg("ON")
+                .arg("REDIRECT")
+                .arg(id),
+            None => Cmd::new("CLIENT").arg("TRACKING").arg("OFF"),
+        };
+        self.exec(&cmd)?.into_result()?;
+        self.tracking = redirect;
+        Ok(())
+    }
+
+    /// Client id the invalidations are redirected to, see [`Self::set_tracking`].
+    pub fn tracking(&self) -> Option<i64> {
+        self.tracking
+    }
+
+    /// Database currently selected on this connection, `None` for the server default.
+    pub fn db(&self) -> Option<u32> {
+        self.db
+    }
+
+    /// A connection is broken once a request failed on the transport level. Its replies
+    /// can no longer be matched to requests, so it must not be reused. Connections to a
+    /// primary demoted by a failover are broken as well.
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Whether the connection broke because a call was cancelled, not because of the server.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Checks without blocking whether an idle connection is still usable. Detects sockets
+    /// closed by the server, e.g. after a restart. Unexpected data on an idle connection
+    /// also makes it unusable, as it would be mistaken for the reply to the next request.
+    pub fn is_closed(&mut self) -> bool {
+        if self.broken {
+            return true;
+        }
+        if self.stream.set_nonblocking(true).is_err() {
+            return true;
+        }
+        let closed = match self.stream.read(&mut [0; 1]) {
+            Ok(_) => true,
+            Err(e) => e.kind() != ErrorKind::WouldBlock,
+        };
+        self.stream.set_nonblocking(false).is_err() || closed
+    }
+
+    fn track_reply(&mut self, cmd: &Cmd, value: &ValkeyValue) {
+        if self.via_sentinel && value.is_error() && value.to_string().starts_with("READONLY") {
+            // Dropping the connection makes the pool ask the sentinels for the new primary.
+            self.broken = true;
+        }
+        if cmd.name() == "SELECT" && value.to_string() == "OK" {
+            self.db = cmd
+                .arguments()
+                .get(1)
+                .and_then(|db| String::from_utf8_lossy(db).parse().ok());
+        }
+    }
+
+    fn open_stream(valkey_url: &ValkeyUrl, i18n: &I18N) -> Result<ValkeyStream, Error> {
+        if let Some(socket_path) = valkey_url.socket_path() {
+            return Self::open_unix_stream(socket_path, valkey_url.timeout(), i18n);
+        }
+
+        // Behind an SSH tunnel the proxy already carries the SSH session.
+        let stream = match (valkey_url.tunnel(), valkey_url.proxy()) {
+            (None, Some(proxy)) => {
+                proxy::connect(proxy, valkey_url.host(), valkey_url.port(), i18n)?
+            }
+            (tunnel, _) => {
+                let socket_addr: SocketAddr = match tunnel {
+                    Some(tunnel) => tunnel.forward(valkey_url.host(), valkey_url.port())?,
+                    None => valkey_url
+                        .address()
+                        .to_socket_addrs()?
+                        .next()
+                        .ok_or(i18n.get(LangKey::NoValidAddress))?,
+                };
+
+                let timeout = valkey_url.timeout();
+                let stream = TcpStream::connect_timeout(
+                    &socket_addr,
+                    timeout.unwrap_or(Duration::from_secs(5)),
+                )?;
+                stream.set_read_timeout(Some(timeout.unwrap_or(DEFAULT_TIMEOUT)))?;
+                stream.set_write_timeout(Some(timeout.unwrap_or(DEFAULT_TIMEOUT)))?;
+                stream
+            }
+        };
+        stream.set_nodelay(true)?;
+        let stream = match valkey_url.tls() {
+            Some(options) => ValkeyStream::Tls(Box::new(tls::connect(
+                stream,
+                valkey_url.host(),
+                options,
+                i18n,
+            )?)),
+            None => ValkeyStream::Tcp(stream),
+        };
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        Ok(stream)
+    }
+
+    #[cfg(unix)]
+    fn open_unix_stream(
+        socket_path: &str,
+        timeout: Option<Duration>,
+        _i18n: &I18N,
+    ) -> Result<ValkeyStream, Error> {
+        let stream = std::os::unix::net::UnixStream::connect(socket_path)?;
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        stream.set_write_timeout(Some(timeout.unwrap_or(DEFAULT_TIMEOUT)))?;
+        Ok(ValkeyStream::Unix(stream))
+    }
+
+    #[cfg(not(unix))]
+    fn open_unix_stream(
+        _socket_path: &str,
+        _timeout: Option<Duration>,
+        i18n: &I18N,
+    ) -> Result<ValkeyStream, Error> {
+        Err(Error::Network(i18n.get(LangKey::UnixSocketsUnsupported)))
+    }
+
+    fn send(&mut self, command: &[u8]) -> Result<(), Error> {
+        self.stream.write_all(command)?;
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    /// Reads the reply to a request, skipping the tracking notices RESP3 servers push in
+    /// between.
+    fn read_reply(&mut self, options: &CallOptions) -> Result<ValkeyValue, Error> {
+        loop {
+            let value = self.read_value(options)?;
+            let notice = match &value {
+                ValkeyValue::Pushes(parts) => parts
+                    .first()
+                    .and_then(ValkeyValue::as_bytes)
+                    .is_some_and(|kind| kind == b"invalidate" || kind == b"tracking-redir-broken"),
+                _ => false,
+            };
+            if !notice {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Reads the next reply. The socket read timeout is the poll interval, so the deadline
+    /// and the cancel handle are checked while the server is still working.
+    fn read_value(&mut self, options: &CallOptions) -> Result<ValkeyValue, Error> {
+        let mut buffer = [0; 8192];
+        let deadline = Instant::now().checked_add(options.timeout.unwrap_or(self.timeout));
+
+        loop {
+            if options
+                .cancel
+                .as_ref()
+                .is_some_and(|cancel| cancel.is_cancelled())
+            {
+                self.cancelled = true;
+                return Err(Error::from(io::Error::new(
+                    ErrorKind::Interrupted,
+                    "Command cancelled",
+                )));
+            }
+
+            if let Some(value) = self
+                .decoder
+                .next_value()
+                .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?
+            {
+                return Ok(value);
+            }
+
+            match self.stream.read(&mut buffer) {
+                Ok(0) => {
+                    let message = if self.decoder.is_idle() {
+                        "Connection closed by server without response"
+                    } else {
+                        "Connection closed by server with an incomplete response"
+                    };
+                    return Err(Error::from(io::Error::new(
+                        ErrorKind::UnexpectedEof,
+                        message,
+                    )));
+                }
+                Ok(n) => {
+                    self.decoder.feed(&buffer[..n]);
+                }
+                Err(e) => match e.kind() {
+                    ErrorKind::WouldBlock | ErrorKind::TimedOut => {
+                        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                            if self.decoder.is_idle() {
+                                return Err(Error::from(io::Error::new(
+                                    ErrorKind::TimedOut,
+                                    "Server did not respond within timeout period",
+                                )));
+                            } else {
+                                return Err(Error::from(io::Error::new(
+                                    ErrorKind::TimedOut,
+                                    "Server response incomplete - timeout while waiting for more data",
+                                )));
+                            }
+                        }
+                        continue;
+                    }
+                    ErrorKind::Interrupted => continue,
+                    ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => {
+                        return Err(Error::from(io::Error::new(
+                            e.kind(),
+                            "Connection lost to server",
+                        )));
+                    }
+                    _ => {
+                        return Err(Error::from(e));
+                    }
+                },
+            }
+        }
+    }
+}
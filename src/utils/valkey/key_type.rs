@@ -28,11 +28,12 @@ impl Display for KeyTypeExtended {
 }
 
 impl KeyTypeExtended {
-    pub fn to_resp_str(&self) -> &'static str {
+    /// Name used by `TYPE` and `SCAN ... TYPE`, `None` when no type filter applies.
+    pub fn type_name(&self) -> Option<&'static str> {
         match self {
-            KeyTypeExtended::All => "",
-            KeyTypeExtended::None => "",
-            KeyTypeExtended::KeyType(kt) => kt.to_resp_str(),
+            KeyTypeExtended::All => None,
+            KeyTypeExtended::None => None,
+            KeyTypeExtended::KeyType(kt) => Some(kt.type_name()),
         }
     }
 }
@@ -68,14 +69,14 @@ pub enum KeyType {
 }
 
 impl KeyType {
-    pub fn to_resp_str(&self) -> &'static str {
+    pub fn type_name(&self) -> &'static str {
         match self {
-            KeyType::Hash => "TYPE hash",
-            KeyType::List => "TYPE list",
-            KeyType::Set => "TYPE set",
-            KeyType::SortedSet => "TYPE zset",
-            KeyType::String => "TYPE string",
-            KeyType::Bloom => "TYPE bloomfltr",
+            KeyType::Hash => "hash",
+            KeyType::List => "list",
+            KeyType::Set => "set",
+            KeyType::SortedSet => "zset",
+            KeyType::String => "string",
+            KeyType::Bloom => "bloomfltr",
         }
     }
 }
@@ -0,0 +1,238 @@
+use crate::errors::Error;
+use crate::utils::valkey::valkey_client::ValkeyClient;
+use crate::utils::valkey::{
+    Cmd, Connection, PubSubEvent, PubSubMessage, Subscriber, SubscriptionKind,
+};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Flags of `notify-keyspace-events` for keyspace channels of all key events.
+pub const KEYSPACE_EVENTS: &str = "KA";
+/// Flags of `notify-keyspace-events` for keyevent channels of all key events.
+pub const KEYEVENT_EVENTS: &str = "EA";
+
+/// Event classes `A` stands for.
+const ALL_CLASSES: &str = "g$lshzxetd";
+
+/// Notification about a key, received on a keyspace or a keyevent channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyspaceEvent {
+    pub received: SystemTime,
+    pub db: u32,
+    pub key: Vec<u8>,
+    /// Name of the event, e.g. `set`, `del`, `expired` or `hset`.
+    pub event: String,
+}
+
+impl KeyspaceEvent {
+    /// Parses a message of a `__keyspace@<db>__:<key>` or a `__keyevent@<db>__:<event>`
+    /// channel.
+    pub fn parse(message: &PubSubMessage) -> Option<Self> {
+        let channel = message.channel.strip_prefix(b"__key")?;
+        let (keyspace, rest) = match channel.strip_prefix(b"space@") {
+            Some(rest) => (true, rest),
+            None => (false, channel.strip_prefix(b"event@")?),
+        };
+        let separator = rest.windows(3).position(|window| window == b"__:")?;
+        let db = std::str::from_utf8(&rest[..separator]).ok()?.parse().ok()?;
+        let name = rest[separator + 3..].to_vec();
+        let (key, event) = if keyspace {
+            (name, message.payload.clone())
+        } else {
+            (message.payload.clone(), name)
+        };
+        Some(Self {
+            received: message.received,
+            db,
+            key,
+            event: String::from_utf8_lossy(&event).into_owned(),
+        })
+    }
+
+    /// Whether the key is gone after the event.
+    pub fn removes_key(&self) -> bool {
+        matches!(
+            self.event.as_str(),
+            "del" | "expired" | "evicted" | "rename_from" | "move_from"
+        )
+    }
+}
+
+/// Whether the `notify-keyspace-events` flags include all `required` ones.
+pub fn has_notify_flags(flags: &str, required: &str) -> bool {
+    let expand = |flags: &str| flags.replace('A', ALL_CLASSES);
+    let flags = expand(flags);
+    expand(required).chars().all(|flag| flags.contains(flag))
+}
+
+/// The flags with the missing `required` ones added.
+fn merge_notify_flags(flags: &str, required: &str) -> String {
+    let mut merged = flags.to_string();
+    for flag in required.chars() {
+        if !has_notify_flags(&merged, &flag.to_string()) {
+            merged.push(flag);
+        }
+    }
+    merged
+}
+
+/// Subscribes to keyspace notifications on every primary, as each node only notifies
+/// about its own keys.
+pub struct KeyspaceListener {
+    subscribers: Vec<Subscriber>,
+    closed: Option<String>,
+}
+
+impl KeyspaceListener {
+    /// Subscribes to the channel pattern, e.g. `__keyspace@0__:*`.
+    pub fn new(
+        client: &Arc<ValkeyClient>,
+        pattern: &str,
+        notify: impl Fn() + Clone + Send + 'static,
+    ) -> Result<Self, Error> {
+        let subscribers = client
+            .primaries()?
+            .into_iter()
+            .map(|address| {
+                let client = Arc::clone(client);
+                let subscriber = Subscriber::new(
+                    move || client.dedicated_connection_to(&address),
+                    notify.clone(),
+                );
+                subscriber.subscribe(SubscriptionKind::Pattern, pattern.as_bytes());
+                subscriber
+            })
+            .collect();
+        Ok(Self {
+            subscribers,
+            closed: None,
+        })
+    }
+
+    /// Takes the events received since the last call.
+    pub fn events(&mut self) -> Vec<KeyspaceEvent> {
+        let mut events = Vec::new();
+        for event in self.subscribers.iter().flat_map(Subscriber::events) {
+            match event {
+                PubSubEvent::Message(message) => events.extend(KeyspaceEvent::parse(&message)),
+                PubSubEvent::Error(reason) | PubSubEvent::Closed(reason) => {
+                    self.closed = Some(reason)
+                }
+                PubSubEvent::Subscribed { .. }
+                | PubSubEvent::Unsubscribed { .. }
+                | PubSubEvent::Invalidated(_) => {}
+            }
+        }
+        events
+    }
+
+    /// Why the subscription of a node failed. Changes on that node are missed.
+    pub fn closed(&self) -> Option<&str> {
+        self.closed.as_deref()
+    }
+
+    /// Whether `notify-keyspace-events` of all primaries include `flags`.
+    pub fn is_enabled(client: &ValkeyClient, flags: &str) -> Result<bool, Error> {
+        for address in client.primaries()? {
+            let mut connection = client.dedicated_connection_to(&address)?;
+            if !has_notify_flags(&Self::notify_flags(&mut connection)?, flags) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Adds `flags` to `notify-keyspace-events` of all primaries, the enabled events stay.
+    pub fn enable(client: &ValkeyClient, flags: &str) -> Result<(), Error> {
+        for address in client.primaries()? {
+            let mut connection = client.dedicated_connection_to(&address)?;
+            let current = Self::notify_flags(&mut connection)?;
+            if !has_notify_flags(&current, flags) {
+                connection
+                    .exec(
+                        &Cmd::new("CONFIG")
+                            .arg("SET")
+                            .arg("notify-keyspace-events")
+                            .arg(merge_notify_flags(&current, flags)),
+                    )?
+                    .into_result()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `notify-keyspace-events` of the first primary.
+    pub fn flags(client: &ValkeyClient) -> Result<String, Error> {
+        let address = client.primaries()?.into_iter().next().unwrap_or_default();
+        Self::notify_flags(&mut client.dedicated_connection_to(&address)?)
+    }
+
+    /// Replaces `notify-keyspace-events` of all primaries.
+    pub fn set_flags(client: &ValkeyClient, flags: &str) -> Result<(), Error> {
+        for address in client.primaries()? {
+            client
+                .dedicated_connection_to(&address)?
+                .exec(
+                    &Cmd::new("CONFIG")
+                        .arg("SET")
+                        .arg("notify-keyspace-events")
+                        .arg(flags),
+                )?
+                .into_result()?;
+        }
+        Ok(())
+    }
+
+    fn notify_flags(connection: &mut Connection) -> Result<String, Error> {
+        let reply = connection
+            .exec(&Cmd::new("CONFIG").arg("GET").arg("notify-keyspace-events"))?
+            .into_result()?;
+        Ok(reply
+            .into_bytes_vec()
+            .get(1)
+            .map(|flags| String::from_utf8_lossy(flags).into_owned())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: &str, payload: &str) -> PubSubMessage {
+        PubSubMessage {
+            received: SystemTime::UNIX_EPOCH,
+            kind: SubscriptionKind::Pattern,
+            pattern: None,
+            channel: channel.as_bytes().to_vec(),
+            payload: payload.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_parse_keyspace_event() {
+        let event = KeyspaceEvent::parse(&message("__keyspace@3__:user:1", "hset")).unwrap();
+        assert_eq!(event.db, 3);
+        assert_eq!(event.key, b"user:1");
+        assert_eq!(event.event, "hset");
+        assert!(!event.removes_key());
+
+        let event = KeyspaceEvent::parse(&message("__keyevent@0__:expired", "session")).unwrap();
+        assert_eq!(event.key, b"session");
+        assert!(event.removes_key());
+
+        assert!(KeyspaceEvent::parse(&message("news", "hello")).is_none());
+    }
+
+    #[test]
+    fn test_notify_flags() {
+        assert!(has_notify_flags("KA", KEYSPACE_EVENTS));
+        assert!(has_notify_flags("AKE", KEYSPACE_EVENTS));
+        assert!(has_notify_flags("Kg$lshzxetd", KEYSPACE_EVENTS));
+        assert!(!has_notify_flags("", KEYSPACE_EVENTS));
+        assert!(!has_notify_flags("Ex", KEYSPACE_EVENTS));
+        assert_eq!(merge_notify_flags("", KEYSPACE_EVENTS), "KA");
+        assert_eq!(merge_notify_flags("Ex", KEYSPACE_EVENTS), "ExKA");
+        assert_eq!(merge_notify_flags("KA", KEYSPACE_EVENTS), "KA");
+    }
+}
@@ -1,16 +1,45 @@
+mod cluster;
+mod cmd;
+mod connection;
 mod key_type;
+mod keyspace;
+mod monitor;
+mod pool;
+mod proxy;
+mod pubsub;
+mod resp_decoder;
+mod sentinel;
+mod server_info;
+mod slots;
+mod ssh;
+mod stream;
+mod tls;
+mod tracking;
+mod transaction;
 pub mod valkey_client;
 mod valkey_url;
 mod valkey_value;
 
+pub use cluster::ClusterClient;
+pub use cmd::{Cmd, ToArg};
+pub use connection::{CallOptions, CancelHandle, Connection, DEFAULT_TIMEOUT};
 pub use key_type::{KeyType, KeyTypeExtended};
+pub use keyspace::{
+    KEYEVENT_EVENTS, KEYSPACE_EVENTS, KeyspaceEvent, KeyspaceListener, has_notify_flags,
+};
+pub use monitor::{Monitor, MonitorEntry};
+pub use pool::{ConnectionPool, PooledConnection};
+pub use proxy::{ProxyKind, ProxyOptions};
+pub use pubsub::{PubSubEvent, PubSubMessage, Subscriber, SubscriptionKind};
+pub use resp_decoder::RespDecoder;
+pub use server_info::{Protocol, ServerInfo};
+pub use ssh::{SshOptions, SshTunnel};
+pub use tls::TlsOptions;
+pub use tracking::{Invalidation, KeyTracker};
+pub use transaction::{Transaction, TransactionResult};
 pub use valkey_url::{ValkeyUrl, ValkeyUrlBuilder};
 pub use valkey_value::ValkeyValue;
 
-pub trait Len {
-    fn len(&self) -> usize;
-}
-
 pub trait ToResp {
     fn to_resp(&self) -> String;
 }
@@ -0,0 +1,250 @@
+use crate::errors::Error;
+use crate::utils::valkey::valkey_client::ValkeyClient;
+use crate::utils::valkey::{Cmd, Connection, ValkeyValue};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How long a monitor thread waits for a line before it checks whether to stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A command as printed by `MONITOR`, e.g.
+/// `1339518083.107412 [0 127.0.0.1:60866] "set" "key" "value"`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorEntry {
+    /// Server time the command was executed at.
+    pub timestamp: SystemTime,
+    pub db: u32,
+    /// Address of the client, `lua` for scripts or `unix:<path>` for socket clients.
+    pub client: String,
+    pub args: Vec<Vec<u8>>,
+}
+
+impl MonitorEntry {
+    pub fn parse(line: &str) -> Option<Self> {
+        let (timestamp, rest) = line.split_once(" [")?;
+        let (seconds, fraction) = timestamp.split_once('.').unwrap_or((timestamp, "0"));
+        let micros = format!("{fraction:0<6}").get(..6)?.parse().ok()?;
+        let (source, args) = rest.split_once(']')?;
+        let (db, client) = source.split_once(' ')?;
+        Some(Self {
+            timestamp: SystemTime::UNIX_EPOCH
+                + Duration::from_secs(seconds.parse().ok()?)
+                + Duration::from_micros(micros),
+            db: db.parse().ok()?,
+            client: client.to_string(),
+            args: parse_quoted_args(args.as_bytes())?,
+        })
+    }
+
+    /// The arguments quoted the way `MONITOR` prints them.
+    pub fn command_line(&self) -> String {
+        self.args
+            .iter()
+            .map(|arg| quote_arg(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Formats the entry as the line it was parsed from.
+impl fmt::Display for MonitorEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let since_epoch = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        write!(
+            f,
+            "{}.{:06} [{} {}] {}",
+            since_epoch.as_secs(),
+            since_epoch.subsec_micros(),
+            self.db,
+            self.client,
+            self.command_line()
+        )
+    }
+}
+
+/// Parses space separated, double quoted arguments with the escapes of `MONITOR`.
+fn parse_quoted_args(input: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut args = Vec::new();
+    let mut i = 0;
+    loop {
+        while input.get(i) == Some(&b' ') {
+            i += 1;
+        }
+        match input.get(i) {
+            None => return Some(args),
+            Some(b'"') => i += 1,
+            Some(_) => return None,
+        }
+        let mut arg = Vec::new();
+        loop {
+            match *input.get(i)? {
+                b'"' => break,
+                b'\\' => {
+                    i += 1;
+                    match *input.get(i)? {
+                        b'n' => arg.push(b'\n'),
+                        b'r' => arg.push(b'\r'),
+                        b't' => arg.push(b'\t'),
+                        b'a' => arg.push(0x07),
+                        b'b' => arg.push(0x08),
+                        b'x' => {
+                            let hex = std::str::from_utf8(input.get(i + 1..i + 3)?).ok()?;
+                            arg.push(u8::from_str_radix(hex, 16).ok()?);
+                            i += 2;
+                        }
+                        c => arg.push(c),
+                    }
+                }
+                c => arg.push(c),
+            }
+            i += 1;
+        }
+        args.push(arg);
+        i += 1;
+    }
+}
+
+fn quote_arg(arg: &[u8]) -> String {
+    let mut quoted = String::from("\"");
+    for &b in arg {
+        match b {
+            b'\\' | b'"' => {
+                quoted.push('\\');
+                quoted.push(b as char);
+            }
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            b'\t' => quoted.push_str("\\t"),
+            0x07 => quoted.push_str("\\a"),
+            0x08 => quoted.push_str("\\b"),
+            b if b.is_ascii_graphic() || b == b' ' => quoted.push(b as char),
+            b => quoted.push_str(&format!("\\x{b:02x}")),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Runs `MONITOR` on a dedicated connection to every primary. Each connection streams
+/// in a background thread until the monitor is dropped.
+pub struct Monitor {
+    entries: Receiver<MonitorEntry>,
+    shutdown: Arc<AtomicBool>,
+    closed: Arc<Mutex<Option<String>>>,
+}
+
+impl Monitor {
+    /// `notify` is called whenever new entries are ready.
+    pub fn new(
+        client: &Arc<ValkeyClient>,
+        notify: impl Fn() + Clone + Send + 'static,
+    ) -> Result<Self, Error> {
+        let (sender, entries) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let closed = Arc::new(Mutex::new(None));
+        for address in client.primaries()? {
+            let client = Arc::clone(client);
+            let sender = sender.clone();
+            let stop = Arc::clone(&shutdown);
+            let closed = Arc::clone(&closed);
+            let notify = notify.clone();
+            thread::spawn(move || {
+                let close = |reason: String| {
+                    if let Ok(mut closed) = closed.lock() {
+                        *closed = Some(reason);
+                    }
+                    notify();
+                };
+                let mut connection = match Self::open(&client, &address) {
+                    Ok(connection) => connection,
+                    Err(e) => return close(e.to_string()),
+                };
+                while !stop.load(Ordering::Relaxed) {
+                    match connection.read_push(POLL_INTERVAL) {
+                        Ok(Some(ValkeyValue::SimpleString(line))) => {
+                            if let Some(entry) = MonitorEntry::parse(&line) {
+                                if sender.send(entry).is_err() {
+                                    return;
+                                }
+                                notify();
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => return close(e.to_string()),
+                    }
+                }
+            });
+        }
+        Ok(Self {
+            entries,
+            shutdown,
+            closed,
+        })
+    }
+
+    fn open(client: &ValkeyClient, address: &str) -> Result<Connection, Error> {
+        let mut connection = client.dedicated_connection_to(address)?;
+        connection.exec(&Cmd::new("MONITOR"))?.into_result()?;
+        Ok(connection)
+    }
+
+    /// Takes the entries received since the last call.
+    pub fn entries(&self) -> Vec<MonitorEntry> {
+        self.entries.try_iter().collect()
+    }
+
+    /// Why the monitor of a node stopped. Commands on that node are missed.
+    pub fn closed(&self) -> Option<String> {
+        self.closed.lock().ok().and_then(|closed| closed.clone())
+    }
+}
+
+/// Stops the threads without waiting for them, they close their connections within the
+/// poll interval.
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_monitor_entry() {
+        let line = r#"1339518083.107412 [0 127.0.0.1:60866] "set" "say \"hi\"" "a\\b\r\n\x00""#;
+        let entry = MonitorEntry::parse(line).unwrap();
+        assert_eq!(entry.db, 0);
+        assert_eq!(entry.client, "127.0.0.1:60866");
+        assert_eq!(
+            entry.args,
+            vec![
+                b"set".to_vec(),
+                b"say \"hi\"".to_vec(),
+                b"a\\b\r\n\0".to_vec()
+            ]
+        );
+        assert_eq!(
+            entry
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_micros(),
+            1_339_518_083_107_412
+        );
+        assert_eq!(entry.to_string(), line);
+
+        let entry = MonitorEntry::parse(r#"1.5 [3 lua] "get" "k""#).unwrap();
+        assert_eq!((entry.db, entry.client.as_str()), (3, "lua"));
+        assert!(MonitorEntry::parse("OK").is_none());
+        assert!(MonitorEntry::parse(r#"1.5 [0 lua] "unterminated"#).is_none());
+    }
+}
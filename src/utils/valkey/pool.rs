@@ -0,0 +1,318 @@
+use crate::errors::Error;
+use crate::i18n::I18N;
+use crate::utils::ValkeyUrl;
+use crate::utils::valkey::connection::Connection;
+use crate::utils::valkey::{Cmd, ValkeyValue};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Upper bound of simultaneously open connections of one pool.
+pub const POOL_SIZE: usize = 4;
+/// Waits between the attempts to reach the server again after the connection was lost.
+const RECONNECT_BACKOFF_MS: [u64; 5] = [250, 500, 1000, 2000, 4000];
+
+struct PoolState {
+    idle: Vec<Connection>,
+    open: usize,
+    db: Option<u32>,
+    /// Client id receiving the invalidations of keys read on the connections.
+    tracking: Option<i64>,
+}
+
+/// A small set of connections to one server, so a long running request in one window
+/// does not stall the others. All connections follow the database selected last and the
+/// client tracking set with [`Self::set_tracking`].
+///
+/// Connections closed by the server are replaced transparently. New connections replay
+/// the session (AUTH, SELECT, HELLO 3, client name) and are retried with a backoff.
+pub struct ConnectionPool {
+    url: ValkeyUrl,
+    i18n: Arc<I18N>,
+    max_size: usize,
+    state: Mutex<PoolState>,
+    available: Condvar,
+    reconnect: Mutex<()>,
+    reconnecting: AtomicBool,
+    gave_up: AtomicBool,
+}
+
+impl ConnectionPool {
+    /// Creates a pool, optionally around an already established connection.
+    /// Without one, the first connection is opened on the first request.
+    pub fn new(url: ValkeyUrl, i18n: Arc<I18N>, connection: Option<Connection>) -> Self {
+        let db = connection.as_ref().map_or(url.db(), |c| c.db());
+        let idle: Vec<Connection> = connection.into_iter().collect();
+        Self {
+            url,
+            i18n,
+            max_size: POOL_SIZE,
+            state: Mutex::new(PoolState {
+                open: idle.len(),
+                idle,
+                db,
+                tracking: None,
+            }),
+            available: Condvar::new(),
+            reconnect: Mutex::new(()),
+            reconnecting: AtomicBool::new(false),
+            gave_up: AtomicBool::new(false),
+        }
+    }
+
+    /// Checks out an idle connection, opens a new one while below the limit, or waits
+    /// until another thread returns one.
+    pub fn get(self: &Arc<Self>) -> Result<PooledConnection, Error> {
+        let mut state = self.state.lock()?;
+        loop {
+            if let Some(mut connection) = state.idle.pop() {
+                let (db, tracking) = (state.db, state.tracking);
+                drop(state);
+                if connection.is_closed() {
+                    self.discard();
+                    state = self.state.lock()?;
+                    continue;
+                }
+                if connection.db() != db {
+                    let select = Cmd::new("SELECT").arg(db.unwrap_or(0));
+                    if let Err(e) = connection.exec(&select).and_then(ValkeyValue::into_result) {
+                        self.discard();
+                        return Err(e);
+                    }
+                }
+                if let Err(e) = self.follow_tracking(&mut connection, tracking) {
+                    self.discard();
+                    return Err(e);
+                }
+                return Ok(PooledConnection {
+                    pool: self.clone(),
+                    connection: Some(connection),
+                });
+            }
+            if state.open < self.max_size {
+                state.open += 1;
+                let (db, tracking) = (state.db, state.tracking);
+                drop(state);
+                return match self.open_with_backoff(db).and_then(|mut connection| {
+                    self.follow_tracking(&mut connection, tracking)?;
+                    Ok(connection)
+                }) {
+                    Ok(connection) => Ok(PooledConnection {
+                        pool: self.clone(),
+                        connection: Some(connection),
+                    }),
+                    Err(e) => {
+                        self.discard();
+                        Err(e)
+                    }
+                };
+            }
+            state = self.available.wait(state)?;
+        }
+    }
+
+    /// Opens a connection outside the pool for blocking commands or long scans.
+    /// It is closed when dropped and does not count against the pool limit.
+    pub fn dedicated(&self) -> Result<Connection, Error> {
+        let db = self.state.lock()?.db;
+        Connection::open(&self.url, db, &self.i18n).map(|(connection, _)| connection)
+    }
+
+    /// Turns client tracking on for the connections of the pool, the invalidations of the
+    /// keys they read are sent to the connection with the client id `redirect`. Turned off
+    /// for `None`. Connections follow when they are checked out next.
+    pub fn set_tracking(&self, redirect: Option<i64>) -> Result<(), Error> {
+        self.state.lock()?.tracking = redirect;
+        Ok(())
+    }
+
+    /// Applies the tracking of the pool to a checked out connection. The server refuses
+    /// the redirect once the receiving connection is gone, tracking is turned off for the
+    /// pool and the connection then, so no keys are tracked for a stale client id.
+    fn follow_tracking(
+        &self,
+        connection: &mut Connection,
+        tracking: Option<i64>,
+    ) -> Result<(), Error> {
+        if connection.tracking() == tracking {
+            return Ok(());
+        }
+        match connection.set_tracking(tracking) {
+            Err(Error::Server { .. }) => {
+                let mut state = self.state.lock()?;
+                if state.tracking == tracking {
+                    state.tracking = None;
+                }
+                drop(state);
+                connection.set_tracking(None)
+            }
+            result => result,
+        }
+    }
+
+    /// Whether the pool is currently waiting for the server to come back.
+    pub fn is_reconnecting(&self) -> bool {
+        self.reconnecting.load(Ordering::Relaxed)
+    }
+
+    /// Opens a connection, retrying with a backoff while the server is unreachable.
+    /// Only one thread retries at a time; once it gave up, later calls try just once
+    /// so every request does not wait for the full backoff again.
+    fn open_with_backoff(&self, db: Option<u32>) -> Result<Connection, Error> {
+        let _reconnect = self.reconnect.lock()?;
+        let mut result = Connection::open(&self.url, db, &self.i18n);
+        if result.is_err() && !self.gave_up.load(Ordering::Relaxed) {
+            self.reconnecting.store(true, Ordering::Relaxed);
+            for delay in RECONNECT_BACKOFF_MS {
+                thread::sleep(Duration::from_millis(delay));
+                result = Connection::open(&self.url, db, &self.i18n);
+                if result.is_ok() {
+                    break;
+                }
+            }
+            self.reconnecting.store(false, Ordering::Relaxed);
+        }
+        self.gave_up.store(result.is_err(), Ordering::Relaxed);
+        result.map(|(connection, _)| connection)
+    }
+
+    fn release(&self, connection: Connection) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if connection.is_cancelled() {
+            // Closed by the user, the server and the other connections are fine.
+            state.open -= 1;
+        } else if connection.is_broken() {
+            // The other idle connections most likely lost the server as well.
+            state.open -= 1 + state.idle.len();
+            state.idle.clear();
+        } else {
+            state.db = connection.db();
+            state.idle.push(connection);
+        }
+        self.available.notify_one();
+    }
+
+    fn discard(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.open -= 1;
+        }
+        self.available.notify_one();
+    }
+}
+
+/// A connection borrowed from a [`ConnectionPool`], returned to it on drop.
+pub struct PooledConnection {
+    pool: Arc<ConnectionPool>,
+    connection: Option<Connection>,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection
+            .as_ref()
+            .expect("connection already released")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection
+            .as_mut()
+            .expect("connection already released")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.release(connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// A server on localhost that only knows client 7, its replies to `GET` are preceded by
+    /// the notice that the redirect target of client tracking closed. Returns the
+    /// `CLIENT TRACKING` commands received.
+    fn fake_server(listener: TcpListener) -> Arc<Mutex<Vec<String>>> {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let received = log.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let log = log.clone();
+                thread::spawn(move || {
+                    let mut writer = stream.try_clone().unwrap();
+                    let mut reader = BufReader::new(stream);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                        let count: usize = line.trim()[1..].parse().unwrap();
+                        let mut args = Vec::new();
+                        for _ in 0..count * 2 {
+                            line.clear();
+                            reader.read_line(&mut line).unwrap();
+                            if !line.starts_with('$') {
+                                args.push(line.trim_end().to_string());
+                            }
+                        }
+                        let cmd = args.join(" ");
+                        let answer = match args[0].as_str() {
+                            "PING" => "+PONG\r\n",
+                            "GET" => ">2\r\n$21\r\ntracking-redir-broken\r\n:7\r\n$1\r\nv\r\n",
+                            _ if cmd.starts_with("CLIENT TRACKING ON REDIRECT")
+                                && !cmd.ends_with(" 7") =>
+                            {
+                                "-ERR The client ID you want redirect to does not exist\r\n"
+                            }
+                            _ => "+OK\r\n",
+                        };
+                        if cmd.starts_with("CLIENT TRACKING") {
+                            log.lock().unwrap().push(cmd);
+                        }
+                        writer.write_all(answer.as_bytes()).unwrap();
+                        line.clear();
+                    }
+                });
+            }
+        });
+        received
+    }
+
+    #[test]
+    fn test_connections_follow_tracking() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let log = fake_server(listener);
+        let url =
+            ValkeyUrl::parse_valkey_url(None, &format!("valkey://127.0.0.1:{port}?protocol=2"))
+                .unwrap();
+        let pool = Arc::new(ConnectionPool::new(url, Arc::new(I18N::default()), None));
+
+        pool.set_tracking(Some(7)).unwrap();
+        let value = pool.get().unwrap().exec(&Cmd::new("GET").arg("k")).unwrap();
+        assert_eq!(value.to_string(), "v");
+
+        // The redirect is refused, the pool and the connection give up tracking.
+        pool.set_tracking(Some(8)).unwrap();
+        assert_eq!(pool.get().unwrap().tracking(), None);
+        assert_eq!(pool.get().unwrap().tracking(), None);
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "CLIENT TRACKING ON REDIRECT 7",
+                "CLIENT TRACKING ON REDIRECT 8",
+                "CLIENT TRACKING OFF"
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::errors::Error;
+use crate::utils::valkey::{Cmd, Connection, ValkeyValue};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// How long the subscriber thread waits for a message before it looks for new requests.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Kind of a subscription, each has its own pair of subscribe commands.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SubscriptionKind {
+    #[default]
+    Channel,
+    Pattern,
+    /// Sharded channel of a cluster, only delivered by the node owning its slot.
+    Shard,
+}
+
+impl SubscriptionKind {
+    pub const ALL: [SubscriptionKind; 3] = [
+        SubscriptionKind::Channel,
+        SubscriptionKind::Pattern,
+        SubscriptionKind::Shard,
+    ];
+
+    fn subscribe_command(&self) -> &'static str {
+        match self {
+            SubscriptionKind::Channel => "SUBSCRIBE",
+            SubscriptionKind::Pattern => "PSUBSCRIBE",
+            SubscriptionKind::Shard => "SSUBSCRIBE",
+        }
+    }
+
+    fn unsubscribe_command(&self) -> &'static str {
+        match self {
+            SubscriptionKind::Channel => "UNSUBSCRIBE",
+            SubscriptionKind::Pattern => "PUNSUBSCRIBE",
+            SubscriptionKind::Shard => "SUNSUBSCRIBE",
+        }
+    }
+
+    /// Command a subscription is routed by on a cluster.
+    pub fn subscribe_cmd(&self, channel: &[u8]) -> Cmd {
+        Cmd::new(self.subscribe_command()).arg(channel)
+    }
+}
+
+/// A message published to a subscribed channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PubSubMessage {
+    pub received: SystemTime,
+    pub kind: SubscriptionKind,
+    /// The matching pattern of a pattern subscription.
+    pub pattern: Option<Vec<u8>>,
+    pub channel: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PubSubEvent {
+    Message(PubSubMessage),
+    /// Confirms a subscription, `count` is the number of subscriptions of the connection.
+    Subscribed {
+        kind: SubscriptionKind,
+        channel: Vec<u8>,
+        count: i64,
+    },
+    Unsubscribed {
+        kind: SubscriptionKind,
+        channel: Vec<u8>,
+        count: i64,
+    },
+    /// Keys changed on the server, sent to a connection with `CLIENT TRACKING` on. `None`
+    /// after a flush, when every key is stale.
+    Invalidated(Option<Vec<Vec<u8>>>),
+    /// The server rejected a request, the connection stays subscribed.
+    Error(String),
+    /// The connection is gone, no further events follow.
+    Closed(String),
+}
+
+impl PubSubEvent {
+    /// Parses a push message of RESP3 or the equivalent array of RESP2. Other replies,
+    /// e.g. to `PING`, are no events.
+    pub fn parse(value: ValkeyValue) -> Option<Self> {
+        let parts = match value {
+            ValkeyValue::Pushes(parts) | ValkeyValue::Array(parts) => parts,
+            value if value.is_error() => return Some(PubSubEvent::Error(value.to_string())),
+            _ => return None,
+        };
+        let bytes = |index: usize| parts.get(index)?.as_bytes().map(<[u8]>::to_vec);
+        let count = || parts.get(2).and_then(ValkeyValue::as_int).unwrap_or(0);
+        let subscribed = |kind| {
+            Some(PubSubEvent::Subscribed {
+                kind,
+                channel: bytes(1)?,
+                count: count(),
+            })
+        };
+        // Unsubscribing from all channels at once replies with a null channel if there
+        // was no subscription.
+        let unsubscribed = |kind| {
+            Some(PubSubEvent::Unsubscribed {
+                kind,
+                channel: bytes(1).unwrap_or_default(),
+                count: count(),
+            })
+        };
+        let message = |kind, pattern, channel, payload| {
+            Some(PubSubEvent::Message(PubSubMessage {
+                received: SystemTime::now(),
+                kind,
+                pattern,
+                channel: bytes(channel)?,
+                payload: bytes(payload)?,
+            }))
+        };
+
+        match bytes(0)?.to_ascii_lowercase().as_slice() {
+            b"message" => message(SubscriptionKind::Channel, None, 1, 2),
+            b"smessage" => message(SubscriptionKind::Shard, None, 1, 2),
+            b"pmessage" => message(SubscriptionKind::Pattern, Some(bytes(1)?), 2, 3),
+            b"subscribe" => subscribed(SubscriptionKind::Channel),
+            b"psubscribe" => subscribed(SubscriptionKind::Pattern),
+            b"ssubscribe" => subscribed(SubscriptionKind::Shard),
+            b"unsubscribe" => unsubscribed(SubscriptionKind::Channel),
+            b"punsubscribe" => unsubscribed(SubscriptionKind::Pattern),
+            b"sunsubscribe" => unsubscribed(SubscriptionKind::Shard),
+            b"invalidate" => Some(PubSubEvent::Invalidated(
+                parts
+                    .get(1)
+                    .filter(|keys| !keys.is_null())
+                    .map(|keys| keys.clone().into_bytes_vec()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Listens on a connection of its own in a background thread. Requests are queued and
+/// sent between two reads, received events are collected until [`Self::events`] is called.
+pub struct Subscriber {
+    requests: Sender<Cmd>,
+    events: Receiver<PubSubEvent>,
+    shutdown: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Subscriber {
+    /// Opens the connection with `open` in the background thread, so a slow server does not
+    /// block the caller. `notify` is called whenever new events are ready.
+    pub fn new(
+        open: impl FnOnce() -> Result<Connection, Error> + Send + 'static,
+        notify: impl Fn() + Send + 'static,
+    ) -> Self {
+        let (requests, request_receiver) = mpsc::channel::<Cmd>();
+        let (event_sender, events) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&shutdown);
+
+        let thread = thread::spawn(move || {
+            let closed = |reason: String| {
+                let _ = event_sender.send(PubSubEvent::Closed(reason));
+                notify();
+            };
+            let mut connection = match open() {
+                Ok(connection) => connection,
+                Err(e) => return closed(e.to_string()),
+            };
+            while !stop.load(Ordering::Relaxed) {
+                for cmd in request_receiver.try_iter() {
+                    if let Err(e) = connection.send_command(&cmd) {
+                        return closed(e.to_string());
+                    }
+                }
+                match connection.read_push(POLL_INTERVAL) {
+                    Ok(Some(value)) => {
+                        if let Some(event) = PubSubEvent::parse(value) {
+                            if event_sender.send(event).is_err() {
+                                return;
+                            }
+                            notify();
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => return closed(e.to_string()),
+                }
+            }
+        });
+
+        Self {
+            requests,
+            events,
+            shutdown,
+            thread,
+        }
+    }
+
+    pub fn subscribe(&self, kind: SubscriptionKind, channel: &[u8]) {
+        let _ = self.requests.send(kind.subscribe_cmd(channel));
+    }
+
+    pub fn unsubscribe(&self, kind: SubscriptionKind, channel: &[u8]) {
+        let _ = self
+            .requests
+            .send(Cmd::new(kind.unsubscribe_command()).arg(channel));
+    }
+
+    /// Takes the events received since the last call.
+    pub fn events(&self) -> Vec<PubSubEvent> {
+        self.events.try_iter().collect()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.thread.is_finished()
+    }
+}
+
+/// Stops the thread without waiting for it, it closes the connection within the poll
+/// interval.
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulk(value: &str) -> ValkeyValue {
+        ValkeyValue::BulkString(value.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let event = PubSubEvent::parse(ValkeyValue::Pushes(vec![
+            bulk("pmessage"),
+            bulk("news.*"),
+            bulk("news.tech"),
+            bulk("hello"),
+        ]));
+        let Some(PubSubEvent::Message(message)) = event else {
+            panic!("expected a message, got {event:?}");
+        };
+        assert_eq!(message.kind, SubscriptionKind::Pattern);
+        assert_eq!(message.pattern.as_deref(), Some(b"news.*".as_slice()));
+        assert_eq!(message.channel, b"news.tech");
+        assert_eq!(message.payload, b"hello");
+
+        assert_eq!(
+            PubSubEvent::parse(ValkeyValue::Array(vec![
+                bulk("ssubscribe"),
+                bulk("orders"),
+                ValkeyValue::Integer(1),
+            ])),
+            Some(PubSubEvent::Subscribed {
+                kind: SubscriptionKind::Shard,
+                channel: b"orders".to_vec(),
+                count: 1,
+            })
+        );
+        assert_eq!(
+            PubSubEvent::parse(ValkeyValue::Array(vec![
+                bulk("punsubscribe"),
+                ValkeyValue::Null,
+                ValkeyValue::Integer(0),
+            ])),
+            Some(PubSubEvent::Unsubscribed {
+                kind: SubscriptionKind::Pattern,
+                channel: vec![],
+                count: 0,
+            })
+        );
+        assert_eq!(
+            PubSubEvent::parse(ValkeyValue::Pushes(vec![
+                bulk("invalidate"),
+                ValkeyValue::Array(vec![bulk("user:1"), bulk("user:2")]),
+            ])),
+            Some(PubSubEvent::Invalidated(Some(vec![
+                b"user:1".to_vec(),
+                b"user:2".to_vec()
+            ])))
+        );
+        assert_eq!(
+            PubSubEvent::parse(ValkeyValue::Pushes(vec![
+                bulk("invalidate"),
+                ValkeyValue::Null
+            ])),
+            Some(PubSubEvent::Invalidated(None))
+        );
+        assert!(matches!(
+            PubSubEvent::parse(ValkeyValue::SimpleError("ERR unknown".to_string())),
+            Some(PubSubEvent::Error(_))
+        ));
+        assert_eq!(
+            PubSubEvent::parse(ValkeyValue::SimpleString("PONG".to_string())),
+            None
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::utils::valkey::{ValkeyValue, find_crlf};
+use std::collections::HashMap;
+
+/// Incremental RESP3 decoder. Bytes are fed as they arrive from the socket and every
+/// value is yielded as soon as its last byte is available. Partially received
+/// aggregates and bulk payloads are kept across reads, so no byte is parsed twice.
+#[derive(Debug, Default)]
+pub struct RespDecoder {
+    buf: Vec<u8>,
+    pos: usize,
+    scan: usize,
+    blob: Option<(u8, usize)>,
+    stack: Vec<Frame>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    kind: u8,
+    remaining: usize,
+    items: Vec<ValkeyValue>,
+}
+
+enum Token {
+    Value(ValkeyValue),
+    Started,
+}
+
+impl Frame {
+    fn finish(self) -> ValkeyValue {
+        match self.kind {
+            b'*' => ValkeyValue::Array(self.items),
+            b'~' => {
+                let mut items = self.items;
+                items.dedup();
+                ValkeyValue::Sets(items)
+            }
+            b'>' => ValkeyValue::Pushes(self.items),
+            _ => {
+                let mut map = HashMap::with_capacity(self.items.len() / 2);
+                let mut items = self.items.into_iter();
+                while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                    map.insert(key, value);
+                }
+                ValkeyValue::Maps(map)
+            }
+        }
+    }
+}
+
+impl RespDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, data: &[u8]) {
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.scan -= self.pos;
+            self.pos = 0;
+        }
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Returns the next complete value, or `None` if more data is needed.
+    pub fn next_value(&mut self) -> Result<Option<ValkeyValue>, &'static str> {
+        loop {
+            let mut value = match self.next_token()? {
+                None => return Ok(None),
+                Some(Token::Started) => continue,
+                Some(Token::Value(value)) => value,
+            };
+
+            loop {
+                let Some(frame) = self.stack.last_mut() else {
+                    return Ok(Some(value));
+                };
+                frame.items.push(value);
+                frame.remaining -= 1;
+                if frame.remaining > 0 {
+                    break;
+                }
+                value = self.stack.pop().ok_or("Invalid decoder state")?.finish();
+            }
+        }
+    }
+
+    /// True if no partially received value is pending.
+    pub fn is_idle(&self) -> bool {
+        self.pos == self.buf.len() && self.blob.is_none() && self.stack.is_empty()
+    }
+
+    /// Number of bytes consumed since the last call to `feed`.
+    pub(super) fn consumed(&self) -> usize {
+        self.pos
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, &'static str> {
+        if let Some((kind, length)) = self.blob {
+            let end = self
+                .pos
+                .checked_add(length)
+                .filter(|end| end.checked_add(2).is_some())
+                .ok_or("Blob length out of range")?;
+            if self.buf.len() < end + 2 {
+                return Ok(None);
+            }
+            if &self.buf[end..end + 2] != b"\r\n" {
+                return Err("Invalid blob terminator");
+            }
+
+            let blob = self.buf[self.pos..end].to_vec();
+            self.pos = end + 2;
+            self.scan = self.pos;
+            self.blob = None;
+
+            let value = match kind {
+                b'$' => ValkeyValue::BulkString(blob),
+                b'!' => ValkeyValue::BulkErrors(blob),
+                _ => {
+                    if blob.len() < 4 || blob[3] != b':' {
+                        return Err("Invalid verbatim string");
+                    }
+                    ValkeyValue::VerbatimString {
+                        format: String::from_utf8_lossy(&blob[..3]).to_string(),
+                        data: blob[4..].to_vec(),
+                    }
+                }
+            };
+            return Ok(Some(Token::Value(value)));
+        }
+
+        let Some(line_end) = find_crlf(&self.buf, self.scan.max(self.pos)) else {
+            self.scan = self.buf.len().saturating_sub(1).max(self.pos);
+            return Ok(None);
+        };
+        if line_end == self.pos {
+            return Err("Empty RESP line");
+        }
+
+        let kind = self.buf[self.pos];
+        let header = std::str::from_utf8(&self.buf[self.pos + 1..line_end])
+            .map_err(|_| "Invalid UTF-8 in header")?;
+
+        let token = match kind {
+            b'+' => Token::Value(ValkeyValue::SimpleString(header.to_string())),
+            b'-' => Token::Value(ValkeyValue::SimpleError(header.to_string())),
+            b':' => Token::Value(ValkeyValue::Integer(
+                header.parse().map_err(|_| "Invalid integer")?,
+            )),
+            b'_' => Token::Value(ValkeyValue::Null),
+            b'#' => match header {
+                "t" => Token::Value(ValkeyValue::Boolean(true)),
+                "f" => Token::Value(ValkeyValue::Boolean(false)),
+                _ => return Err("Invalid boolean"),
+            },
+            b',' => Token::Value(ValkeyValue::Double(
+                header.parse().map_err(|_| "Invalid double")?,
+            )),
+            b'(' => Token::Value(ValkeyValue::BigNumber(header.to_string())),
+            b'$' | b'!' | b'=' => match header.parse::<usize>() {
+                Ok(length) => {
+                    self.blob = Some((kind, length));
+                    Token::Started
+                }
+                Err(_) if header.parse::<i64>().is_ok_and(|length| length < 0) => {
+                    Token::Value(ValkeyValue::Null)
+                }
+                Err(_) => return Err("Invalid blob length"),
+            },
+            b'*' | b'~' | b'>' | b'%' => match header.parse::<usize>() {
+                Ok(count) => {
+                    let remaining = if kind == b'%' {
+                        count.checked_mul(2).ok_or("Invalid aggregate length")?
+                    } else {
+                        count
+                    };
+                    let frame = Frame {
+                        kind,
+                        remaining,
+                        items: Vec::with_capacity(remaining.min(1024)),
+                    };
+                    if remaining == 0 {
+                        Token::Value(frame.finish())
+                    } else {
+                        self.stack.push(frame);
+                        Token::Started
+                    }
+                }
+                Err(_) if header.parse::<i64>().is_ok_and(|count| count < 0) => {
+                    Token::Value(ValkeyValue::Null)
+                }
+                Err(_) => return Err("Invalid aggregate length"),
+            },
+            _ => return Err("Unknown RESP type"),
+        };
+
+        self.pos = line_end + 2;
+        self.scan = self.pos;
+        Ok(Some(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ValkeyValue::*;
+
+    fn decode_all(decoder: &mut RespDecoder) -> Vec<ValkeyValue> {
+        let mut values = Vec::new();
+        while let Some(value) = decoder.next_value().unwrap() {
+            values.push(value);
+        }
+        values
+    }
+
+    #[test]
+    fn test_split_reads() {
+        let input = b"*3\r\n$5\r\nhello\r\n%1\r\n+k\r\n:12\r\n~1\r\n#t\r\n";
+        let mut decoder = RespDecoder::new();
+        let mut values = Vec::new();
+        for byte in input {
+            decoder.feed(&[*byte]);
+            values.extend(decode_all(&mut decoder));
+        }
+        assert_eq!(
+            values,
+            vec![Array(vec![
+                BulkString(b"hello".to_vec()),
+                Maps(HashMap::from([(SimpleString("k".into()), Integer(12))])),
+                Sets(vec![Boolean(true)]),
+            ])]
+        );
+        assert!(decoder.is_idle());
+    }
+
+    #[test]
+    fn test_pipelined_multi_line_replies() {
+        let info = b"# Server\r\nvalkey_version:8.0.1\r\nmode:standalone\r\n";
+        let mut input = format!("${}\r\n", info.len()).into_bytes();
+        input.extend_from_slice(info);
+        input.extend_from_slice(b"\r\n+OK\r\n*2\r\n$2\r\n\r\n\r\n$-1\r\n:3\r\n");
+
+        let mut decoder = RespDecoder::new();
+        let (first, second) = input.split_at(20);
+        decoder.feed(first);
+        assert!(decode_all(&mut decoder).is_empty());
+        assert!(!decoder.is_idle());
+        decoder.feed(second);
+
+        assert_eq!(
+            decode_all(&mut decoder),
+            vec![
+                BulkString(info.to_vec()),
+                SimpleString("OK".into()),
+                Array(vec![BulkString(b"\r\n".to_vec()), Null]),
+                Integer(3),
+            ]
+        );
+        assert!(decoder.is_idle());
+    }
+
+    #[test]
+    fn test_empty_aggregates() {
+        let mut decoder = RespDecoder::new();
+        decoder.feed(b"*0\r\n%0\r\n*-1\r\n");
+        assert_eq!(
+            decode_all(&mut decoder),
+            vec![Array(vec![]), Maps(HashMap::new()), Null]
+        );
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let mut decoder = RespDecoder::new();
+        decoder.feed(b"?what\r\n");
+        assert!(decoder.next_value().is_err());
+
+        let mut decoder = RespDecoder::new();
+        decoder.feed(b"$2\r\nabcd\r\n");
+        assert!(decoder.next_value().is_err());
+
+        let mut decoder = RespDecoder::new();
+        decoder.feed(format!("+OK\r\n${}\r\n", usize::MAX).as_bytes());
+        assert!(decoder.next_value().unwrap().is_some());
+        assert!(decoder.next_value().is_err());
+
+        let mut decoder = RespDecoder::new();
+        decoder.feed(format!("%{}\r\n", usize::MAX).as_bytes());
+        assert!(decoder.next_value().is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use crate::utils::valkey::{Cmd, Connection, ValkeyValue};
+use crate::utils::{ValkeyUrl, ValkeyUrlBuilder};
+
+/// Asks the sentinels one after the other for the current primary and returns the url
+/// of the primary. Sentinels that are unreachable or do not know the master are skipped.
+pub fn resolve_primary(valkey_url: &ValkeyUrl, i18n: &I18N) -> Result<ValkeyUrl, Error> {
+    let master_name = valkey_url.master_name().unwrap_or_default();
+    let mut unreachable = None;
+    let mut answered = false;
+    for (host, port) in valkey_url.sentinels() {
+        match query_sentinel(valkey_url, host, *port, master_name, i18n) {
+            Ok(Some((host, port))) => return Ok(valkey_url.with_address(&host, port)),
+            Ok(None) => answered = true,
+            Err(e) => unreachable = Some(e),
+        }
+    }
+    match unreachable {
+        Some(e) if !answered => Err(e),
+        _ => Err(Error::Network(format!(
+            "{} ({master_name})",
+            i18n.get(LangKey::SentinelPrimaryUnknown)
+        ))),
+    }
+}
+
+fn query_sentinel(
+    valkey_url: &ValkeyUrl,
+    host: &str,
+    port: u16,
+    master_name: &str,
+    i18n: &I18N,
+) -> Result<Option<(String, u16)>, Error> {
+    // The credentials and the database belong to the primary, not to the sentinel.
+    let mut sentinel_url = ValkeyUrlBuilder::new()
+        .host(host)
+        .port(port)
+        .tls(valkey_url.tls().cloned())
+        .proxy(valkey_url.proxy().cloned())
+        .build()?;
+    if let Some(tunnel) = valkey_url.tunnel() {
+        sentinel_url = sentinel_url.with_tunnel(tunnel.clone());
+    }
+    let (mut connection, _) = Connection::open(&sentinel_url, None, i18n)?;
+    let reply = connection.exec(
+        &Cmd::new("SENTINEL")
+            .arg("GET-MASTER-ADDR-BY-NAME")
+            .arg(master_name),
+    )?;
+    let ValkeyValue::Array(address) = reply else {
+        return Ok(None);
+    };
+    Ok(match address.as_slice() {
+        [host, port] => port
+            .to_string()
+            .parse()
+            .ok()
+            .map(|port| (host.to_string(), port)),
+        _ => None,
+    })
+}
+
+/// Whether the server is a primary. Right after a failover the sentinels may still
+/// report the old primary, which then runs as replica.
+pub fn is_primary(connection: &mut Connection) -> Result<bool, Error> {
+    Ok(match connection.exec(&Cmd::new("ROLE"))? {
+        ValkeyValue::Array(role) => role
+            .first()
+            .is_some_and(|role| role.to_string() == "master"),
+        _ => false,
+    })
+}
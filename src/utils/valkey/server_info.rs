@@ -0,0 +1,160 @@
+use crate::utils::valkey::ValkeyValue;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Protocol negotiated on the connections of a client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Resp2,
+    Resp3,
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Protocol::Resp2 => write!(f, "RESP2"),
+            Protocol::Resp3 => write!(f, "RESP3"),
+        }
+    }
+}
+
+/// Identity of the connected server, used to turn off features it does not support.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerInfo {
+    pub name: String,
+    pub version: String,
+    pub mode: String,
+    pub protocol: Protocol,
+}
+
+impl ServerInfo {
+    /// Reads the map returned by `HELLO 3`. `None` when it lacks the server version.
+    pub fn from_hello(hello: &ValkeyValue) -> Option<Self> {
+        let ValkeyValue::Maps(map) = hello else {
+            return None;
+        };
+        let field = |name: &str| {
+            map.get(&ValkeyValue::BulkString(name.as_bytes().to_vec()))
+                .map(|value| value.to_string())
+        };
+        Some(Self {
+            name: field("server").unwrap_or_else(|| "unknown".to_string()),
+            version: field("version")?,
+            mode: field("mode").unwrap_or_else(|| "unknown".to_string()),
+            protocol: Protocol::Resp3,
+        })
+    }
+
+    /// Reads the output of `INFO server`, the fallback for servers without `HELLO 3`.
+    /// Redis compatible servers report the redis version whose API they implement.
+    pub fn from_info(info: &str, protocol: Protocol) -> Self {
+        let fields: HashMap<&str, &str> = info
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .collect();
+
+        let (name, version) = if let Some(version) = fields.get("valkey_version") {
+            ("valkey", *version)
+        } else if fields.contains_key("dragonfly_version") {
+            (
+                "dragonfly",
+                fields.get("redis_version").copied().unwrap_or("0.0.0"),
+            )
+        } else {
+            (
+                fields.get("server_name").copied().unwrap_or("redis"),
+                fields.get("redis_version").copied().unwrap_or("0.0.0"),
+            )
+        };
+        let mode = fields
+            .get("server_mode")
+            .or(fields.get("redis_mode"))
+            .copied()
+            .unwrap_or("unknown");
+
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            mode: mode.to_string(),
+            protocol,
+        }
+    }
+
+    /// Major, minor and patch version, missing or malformed parts count as 0.
+    pub fn version_number(&self) -> (u8, u8, u8) {
+        let mut parts = self
+            .version
+            .split('.')
+            .map(|part| part.parse::<u8>().unwrap_or(0));
+        (
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+        )
+    }
+
+    /// `SCAN ... TYPE` exists since 6.0.
+    pub fn supports_scan_type(&self) -> bool {
+        self.version_number() >= (6, 0, 0)
+    }
+
+    /// `CLIENT TRACKING` exists since 6.0, its invalidations are only pushed with RESP3.
+    pub fn supports_client_tracking(&self) -> bool {
+        self.protocol == Protocol::Resp3 && self.version_number() >= (6, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_hello() {
+        let hello = ValkeyValue::Maps(HashMap::from([
+            (
+                ValkeyValue::BulkString(b"server".to_vec()),
+                ValkeyValue::BulkString(b"valkey".to_vec()),
+            ),
+            (
+                ValkeyValue::BulkString(b"version".to_vec()),
+                ValkeyValue::BulkString(b"8.1.2".to_vec()),
+            ),
+            (
+                ValkeyValue::BulkString(b"mode".to_vec()),
+                ValkeyValue::BulkString(b"standalone".to_vec()),
+            ),
+        ]));
+        let info = ServerInfo::from_hello(&hello).unwrap();
+        assert_eq!(info.name, "valkey");
+        assert_eq!(info.version_number(), (8, 1, 2));
+        assert_eq!(info.mode, "standalone");
+        assert_eq!(info.protocol, Protocol::Resp3);
+        assert!(info.supports_client_tracking());
+        assert!(ServerInfo::from_hello(&ValkeyValue::Null).is_none());
+    }
+
+    #[test]
+    fn test_from_info() {
+        let valkey = "# Server\r\nredis_version:7.2.4\r\nserver_name:valkey\r\n\
+                      valkey_version:7.2.8\r\nredis_mode:standalone\r\n";
+        let info = ServerInfo::from_info(valkey, Protocol::Resp2);
+        assert_eq!(info.name, "valkey");
+        assert_eq!(info.version_number(), (7, 2, 8));
+        assert_eq!(info.mode, "standalone");
+        assert!(info.supports_scan_type());
+        assert!(!info.supports_client_tracking());
+
+        let old_redis = "# Server\r\nredis_version:5.0.14\r\nredis_mode:cluster\r\n";
+        let info = ServerInfo::from_info(old_redis, Protocol::Resp2);
+        assert_eq!(info.name, "redis");
+        assert_eq!(info.mode, "cluster");
+        assert!(!info.supports_scan_type());
+
+        let dragonfly = "redis_version:7.4.0\r\ndragonfly_version:df-v1.25.1\r\n";
+        let info = ServerInfo::from_info(dragonfly, Protocol::Resp2);
+        assert_eq!(info.name, "dragonfly");
+        assert_eq!(info.version_number(), (7, 4, 0));
+    }
+}
@@ -0,0 +1,327 @@
+use crate::utils::ValkeyUrl;
+use crate::utils::valkey::ValkeyValue;
+
+/// Number of hash slots of a Valkey cluster.
+pub const SLOT_COUNT: u16 = 16384;
+
+/// Hash slot of a key. Only the part inside the first non-empty `{...}` is hashed,
+/// so keys sharing a hash tag end up on the same node.
+pub fn key_slot(key: &[u8]) -> u16 {
+    let hashed = key
+        .iter()
+        .position(|&b| b == b'{')
+        .and_then(|open| {
+            let tag = &key[open + 1..];
+            let close = tag.iter().position(|&b| b == b'}')?;
+            (close > 0).then(|| &tag[..close])
+        })
+        .unwrap_or(key);
+    crc16(hashed) % SLOT_COUNT
+}
+
+/// CRC16/XMODEM as specified by the cluster protocol.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// A `MOVED` or `ASK` error reply pointing to the node that serves the slot.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Redirect {
+    Moved { slot: u16, address: String },
+    Ask { slot: u16, address: String },
+}
+
+impl Redirect {
+    pub fn parse(value: &ValkeyValue) -> Option<Self> {
+        if !value.is_error() {
+            return None;
+        }
+        let message = String::from_utf8_lossy(value.as_bytes()?).to_string();
+        let mut parts = message.split_whitespace();
+        let kind = parts.next()?;
+        let slot = parts.next()?.parse().ok()?;
+        let address = parts.next()?.to_string();
+        match kind {
+            "MOVED" => Some(Redirect::Moved { slot, address }),
+            "ASK" => Some(Redirect::Ask { slot, address }),
+            _ => None,
+        }
+    }
+}
+
+struct SlotRange {
+    start: u16,
+    end: u16,
+    primary: String,
+}
+
+/// Primary node (`host:port`) of every slot range of the cluster.
+#[derive(Default)]
+pub struct SlotMap {
+    ranges: Vec<SlotRange>,
+}
+
+impl SlotMap {
+    /// Reads the reply of `CLUSTER SHARDS` (Valkey 7.0+). Nodes without an endpoint are
+    /// reachable under the host we are connected to. With `tls` the TLS port is preferred.
+    pub fn from_shards(reply: &ValkeyValue, seed_host: &str, tls: bool) -> Option<Self> {
+        let ValkeyValue::Array(shards) = reply else {
+            return None;
+        };
+        let mut ranges = Vec::new();
+        for shard in shards {
+            let slots = field(shard, "slots")?.clone().into_bytes_vec();
+            let nodes = match field(shard, "nodes")? {
+                ValkeyValue::Array(nodes) => nodes,
+                _ => return None,
+            };
+            let Some(primary) = nodes.iter().find(|node| {
+                field(node, "role").is_some_and(|role| role.to_string() == "master")
+                    && field(node, "health").is_none_or(|health| health.to_string() == "online")
+            }) else {
+                continue;
+            };
+            let host = ["endpoint", "ip"]
+                .iter()
+                .filter_map(|name| field(primary, name))
+                .map(|value| value.to_string())
+                .find(|host| !host.is_empty() && host != "?")
+                .unwrap_or_else(|| seed_host.to_string());
+            let port = tls
+                .then(|| field(primary, "tls-port"))
+                .flatten()
+                .or(field(primary, "port"))
+                .and_then(|port| u16::try_from(port.as_int()?).ok())?;
+            let primary = ValkeyUrl::format_address(&host, port);
+
+            for pair in slots.chunks(2) {
+                let [start, end] = pair else {
+                    return None;
+                };
+                ranges.push(SlotRange {
+                    start: String::from_utf8_lossy(start).parse().ok()?,
+                    end: String::from_utf8_lossy(end).parse().ok()?,
+                    primary: primary.clone(),
+                });
+            }
+        }
+        Some(Self::sorted(ranges))
+    }
+
+    /// Reads the reply of `CLUSTER SLOTS`, used for servers before 7.0.
+    pub fn from_slots(reply: &ValkeyValue, seed_host: &str) -> Option<Self> {
+        let ValkeyValue::Array(entries) = reply else {
+            return None;
+        };
+        let mut ranges = Vec::new();
+        for entry in entries {
+            let ValkeyValue::Array(entry) = entry else {
+                return None;
+            };
+            let ValkeyValue::Array(primary) = entry.get(2)? else {
+                return None;
+            };
+            let host = primary
+                .first()
+                .map(|host| host.to_string())
+                .filter(|host| !host.is_empty() && host != "?")
+                .unwrap_or_else(|| seed_host.to_string());
+            let port = u16::try_from(primary.get(1)?.as_int()?).ok()?;
+            ranges.push(SlotRange {
+                start: entry.first()?.as_int()? as u16,
+                end: entry.get(1)?.as_int()? as u16,
+                primary: ValkeyUrl::format_address(&host, port),
+            });
+        }
+        Some(Self::sorted(ranges))
+    }
+
+    fn sorted(mut ranges: Vec<SlotRange>) -> Self {
+        ranges.sort_by_key(|range| range.start);
+        Self { ranges }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn primary(&self, slot: u16) -> Option<&str> {
+        let index = self.ranges.partition_point(|range| range.end < slot);
+        self.ranges
+            .get(index)
+            .filter(|range| range.start <= slot)
+            .map(|range| range.primary.as_str())
+    }
+
+    /// All primaries, sorted and without duplicates.
+    pub fn primaries(&self) -> Vec<String> {
+        let mut primaries: Vec<String> = self.ranges.iter().map(|r| r.primary.clone()).collect();
+        primaries.sort();
+        primaries.dedup();
+        primaries
+    }
+
+    /// Moves a single slot to another node, as told by a `MOVED` redirect.
+    pub fn set_primary(&mut self, slot: u16, primary: &str) {
+        let index = self.ranges.partition_point(|range| range.end < slot);
+        match self.ranges.get_mut(index) {
+            Some(range) if range.start <= slot => {
+                let SlotRange { start, end, .. } = *range;
+                let mut replacement = Vec::new();
+                if start < slot {
+                    replacement.push(SlotRange {
+                        start,
+                        end: slot - 1,
+                        primary: range.primary.clone(),
+                    });
+                }
+                replacement.push(SlotRange {
+                    start: slot,
+                    end: slot,
+                    primary: primary.to_string(),
+                });
+                if slot < end {
+                    replacement.push(SlotRange {
+                        start: slot + 1,
+                        end,
+                        primary: range.primary.clone(),
+                    });
+                }
+                self.ranges.splice(index..=index, replacement);
+            }
+            _ => self.ranges.insert(
+                index,
+                SlotRange {
+                    start: slot,
+                    end: slot,
+                    primary: primary.to_string(),
+                },
+            ),
+        }
+    }
+}
+
+/// Field of a map reply. RESP2 servers send maps as flat arrays of name/value pairs.
+fn field<'a>(value: &'a ValkeyValue, name: &str) -> Option<&'a ValkeyValue> {
+    match value {
+        ValkeyValue::Maps(map) => map
+            .iter()
+            .find(|(key, _)| key.as_bytes() == Some(name.as_bytes()))
+            .map(|(_, value)| value),
+        ValkeyValue::Array(items) => items
+            .chunks(2)
+            .find(|pair| pair[0].as_bytes() == Some(name.as_bytes()))
+            .and_then(|pair| pair.get(1)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ValkeyValue::*;
+
+    fn bulk(s: &str) -> ValkeyValue {
+        BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_key_slot() {
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+        assert_eq!(key_slot(b"foo"), 12182);
+        assert_eq!(key_slot(b"bar"), 5061);
+        assert_eq!(key_slot(b"{user1000}.following"), key_slot(b"user1000"));
+        assert_eq!(key_slot(b"foo{}{bar}"), crc16(b"foo{}{bar}") % SLOT_COUNT);
+        assert_eq!(key_slot(b"foo{{bar}}zap"), key_slot(b"{bar"));
+    }
+
+    #[test]
+    fn test_redirect() {
+        assert_eq!(
+            Redirect::parse(&SimpleError("MOVED 3999 127.0.0.1:6381".into())),
+            Some(Redirect::Moved {
+                slot: 3999,
+                address: "127.0.0.1:6381".into()
+            })
+        );
+        assert_eq!(
+            Redirect::parse(&SimpleError("ASK 12 10.0.0.2:7000".into())),
+            Some(Redirect::Ask {
+                slot: 12,
+                address: "10.0.0.2:7000".into()
+            })
+        );
+        assert_eq!(Redirect::parse(&SimpleError("ERR wrong".into())), None);
+        assert_eq!(Redirect::parse(&bulk("MOVED 1 a:1")), None);
+    }
+
+    #[test]
+    fn test_slot_map() {
+        let slots = Array(vec![
+            Array(vec![
+                Integer(0),
+                Integer(8191),
+                Array(vec![bulk("10.0.0.1"), Integer(7000), bulk("id1")]),
+            ]),
+            Array(vec![
+                Integer(8192),
+                Integer(16383),
+                Array(vec![bulk(""), Integer(7001), bulk("id2")]),
+            ]),
+        ]);
+        let mut map = SlotMap::from_slots(&slots, "seed").unwrap();
+        assert_eq!(map.primary(0), Some("10.0.0.1:7000"));
+        assert_eq!(map.primary(8192), Some("seed:7001"));
+        assert_eq!(map.primaries(), vec!["10.0.0.1:7000", "seed:7001"]);
+
+        map.set_primary(100, "10.0.0.3:7002");
+        assert_eq!(map.primary(99), Some("10.0.0.1:7000"));
+        assert_eq!(map.primary(100), Some("10.0.0.3:7002"));
+        assert_eq!(map.primary(101), Some("10.0.0.1:7000"));
+
+        let shards = Array(vec![Array(vec![
+            bulk("slots"),
+            Array(vec![Integer(0), Integer(5), Integer(10), Integer(16383)]),
+            bulk("nodes"),
+            Array(vec![
+                Array(vec![
+                    bulk("port"),
+                    Integer(7003),
+                    bulk("endpoint"),
+                    bulk("10.0.0.4"),
+                    bulk("role"),
+                    bulk("replica"),
+                ]),
+                Array(vec![
+                    bulk("port"),
+                    Integer(7002),
+                    bulk("tls-port"),
+                    Integer(7102),
+                    bulk("endpoint"),
+                    bulk("10.0.0.3"),
+                    bulk("role"),
+                    bulk("master"),
+                    bulk("health"),
+                    bulk("online"),
+                ]),
+            ]),
+        ])]);
+        let map = SlotMap::from_shards(&shards, "seed", false).unwrap();
+        assert_eq!(map.primary(3), Some("10.0.0.3:7002"));
+        assert_eq!(map.primary(7), None);
+        assert_eq!(map.primary(16383), Some("10.0.0.3:7002"));
+        let map = SlotMap::from_shards(&shards, "seed", true).unwrap();
+        assert_eq!(map.primary(3), Some("10.0.0.3:7102"));
+    }
+}
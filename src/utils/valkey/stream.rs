@@ -0,0 +1,66 @@
+use crate::utils::valkey::tls::TlsStream;
+use std::io;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+/// Transport below the RESP connection.
+pub enum ValkeyStream {
+    Tcp(TcpStream),
+    Tls(Box<TlsStream>),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Read for ValkeyStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            ValkeyStream::Tcp(stream) => stream.read(buf),
+            ValkeyStream::Tls(stream) => stream.read(buf),
+            #[cfg(unix)]
+            ValkeyStream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for ValkeyStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            ValkeyStream::Tcp(stream) => stream.write(buf),
+            ValkeyStream::Tls(stream) => stream.write(buf),
+            #[cfg(unix)]
+            ValkeyStream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            ValkeyStream::Tcp(stream) => stream.flush(),
+            ValkeyStream::Tls(stream) => stream.flush(),
+            #[cfg(unix)]
+            ValkeyStream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+impl ValkeyStream {
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            ValkeyStream::Tcp(stream) => stream.set_read_timeout(timeout),
+            ValkeyStream::Tls(stream) => stream.sock.set_read_timeout(timeout),
+            #[cfg(unix)]
+            ValkeyStream::Unix(stream) => stream.set_read_timeout(timeout),
+        }
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            ValkeyStream::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            ValkeyStream::Tls(stream) => stream.sock.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            ValkeyStream::Unix(stream) => stream.set_nonblocking(nonblocking),
+        }
+    }
+}
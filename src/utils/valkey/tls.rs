@@ -0,0 +1,144 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{
+    ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, SignatureScheme,
+    StreamOwned,
+};
+use serde::{Deserialize, Serialize};
+use std::net::TcpStream;
+use std::sync::Arc;
+
+pub type TlsStream = StreamOwned<ClientConnection, TcpStream>;
+
+/// TLS settings of a `valkeys://` connection. All paths point to PEM files.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsOptions {
+    pub ca_file: Option<String>,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub server_name: Option<String>,
+    pub insecure: bool,
+}
+
+/// Wraps an established TCP stream into a TLS session and completes the handshake,
+/// so certificate problems are reported while connecting and not on the first command.
+pub fn connect(
+    stream: TcpStream,
+    host: &str,
+    options: &TlsOptions,
+    i18n: &I18N,
+) -> Result<TlsStream, Error> {
+    let tls_error = |e: &dyn std::fmt::Display| {
+        Error::Network(format!("{}: {e}", i18n.get(LangKey::TlsHandshakeFailed)))
+    };
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| tls_error(&e))?;
+
+    let builder = if options.insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+    } else {
+        let mut roots = RootCertStore::empty();
+        if let Some(ca_file) = &options.ca_file {
+            for cert in CertificateDer::pem_file_iter(ca_file).map_err(|e| tls_error(&e))? {
+                roots
+                    .add(cert.map_err(|e| tls_error(&e))?)
+                    .map_err(|e| tls_error(&e))?;
+            }
+        } else {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        }
+        builder.with_root_certificates(roots)
+    };
+
+    let config = match (&options.client_cert, &options.client_key) {
+        (Some(cert_file), Some(key_file)) => {
+            let certs = CertificateDer::pem_file_iter(cert_file)
+                .map_err(|e| tls_error(&e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| tls_error(&e))?;
+            let key = PrivateKeyDer::from_pem_file(key_file).map_err(|e| tls_error(&e))?;
+            builder
+                .with_client_auth_cert(certs, key)
+                .map_err(|e| tls_error(&e))?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => {
+            return Err(Error::InvalidInput(
+                i18n.get(LangKey::ClientCertAndKeyRequired),
+            ));
+        }
+    };
+
+    let server_name = options.server_name.as_deref().unwrap_or(host);
+    let server_name = ServerName::try_from(server_name.to_string()).map_err(|e| tls_error(&e))?;
+    let connection =
+        ClientConnection::new(Arc::new(config), server_name).map_err(|e| tls_error(&e))?;
+
+    let mut tls_stream = StreamOwned::new(connection, stream);
+    while tls_stream.conn.is_handshaking() {
+        tls_stream
+            .conn
+            .complete_io(&mut tls_stream.sock)
+            .map_err(|e| tls_error(&e))?;
+    }
+    Ok(tls_stream)
+}
+
+/// Accepts any server certificate. Only used when the user explicitly disabled verification.
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
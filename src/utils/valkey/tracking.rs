@@ -0,0 +1,110 @@
+use crate::errors::Error;
+use crate::utils::valkey::valkey_client::ValkeyClient;
+use crate::utils::valkey::{Cmd, PubSubEvent, Subscriber};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Keys whose cached data is stale.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Invalidation {
+    Keys(Vec<Vec<u8>>),
+    /// The database was flushed or a tracking connection was lost, nothing cached is valid.
+    All,
+}
+
+/// Client side caching with `CLIENT TRACKING`. The server remembers the keys read on the
+/// pooled connections and pushes the names of those that changed to a connection of the
+/// tracker, so cached data is kept until it is reported stale instead of being fetched
+/// again. Keys nobody read cause no traffic. Needs RESP3, see
+/// [`crate::utils::ServerInfo::supports_client_tracking`].
+pub struct KeyTracker {
+    client: Arc<ValkeyClient>,
+    primaries: Vec<String>,
+    subscribers: Vec<Subscriber>,
+    /// Tracking connections that became ready since the last call of [`Self::invalidations`].
+    started: Arc<AtomicUsize>,
+    closed: Option<String>,
+}
+
+impl KeyTracker {
+    /// Opens a connection to every primary, as each node only reports its own keys, and
+    /// redirects the invalidations of the pooled connections to it.
+    pub fn new(
+        client: &Arc<ValkeyClient>,
+        notify: impl Fn() + Clone + Send + 'static,
+    ) -> Result<Self, Error> {
+        let primaries = client.primaries()?;
+        let started = Arc::new(AtomicUsize::new(0));
+        let subscribers = primaries
+            .iter()
+            .map(|address| {
+                let client = Arc::clone(client);
+                let address = address.clone();
+                let started = Arc::clone(&started);
+                let notify_started = notify.clone();
+                Subscriber::new(
+                    move || {
+                        let mut connection = client.dedicated_connection_to(&address)?;
+                        let id = connection
+                            .exec(&Cmd::new("CLIENT").arg("ID"))?
+                            .into_result()?
+                            .as_int()
+                            .ok_or_else(|| Error::Any("CLIENT ID returned no id".into()))?;
+                        client.set_tracking(&address, Some(id))?;
+                        started.fetch_add(1, Ordering::Relaxed);
+                        notify_started();
+                        Ok(connection)
+                    },
+                    notify.clone(),
+                )
+            })
+            .collect();
+        Ok(Self {
+            client: Arc::clone(client),
+            primaries,
+            subscribers,
+            started,
+            closed: None,
+        })
+    }
+
+    /// Takes the invalidations received since the last call. Once a connection is lost,
+    /// changes are missed and [`Invalidation::All`] is reported. It is also reported when a
+    /// connection became ready, as keys read before are not tracked.
+    pub fn invalidations(&mut self) -> Vec<Invalidation> {
+        let mut invalidations = Vec::new();
+        if self.started.swap(0, Ordering::Relaxed) > 0 {
+            invalidations.push(Invalidation::All);
+        }
+        for event in self.subscribers.iter().flat_map(Subscriber::events) {
+            match event {
+                PubSubEvent::Invalidated(Some(keys)) => {
+                    invalidations.push(Invalidation::Keys(keys))
+                }
+                PubSubEvent::Invalidated(None) => invalidations.push(Invalidation::All),
+                PubSubEvent::Error(reason) | PubSubEvent::Closed(reason) => {
+                    invalidations.push(Invalidation::All);
+                    self.closed = Some(reason);
+                }
+                PubSubEvent::Message(_)
+                | PubSubEvent::Subscribed { .. }
+                | PubSubEvent::Unsubscribed { .. } => {}
+            }
+        }
+        invalidations
+    }
+
+    /// Why tracking stopped. Cached data can not be trusted anymore then.
+    pub fn closed(&self) -> Option<&str> {
+        self.closed.as_deref()
+    }
+}
+
+/// Turns tracking off for the pooled connections, they follow when they are used next.
+impl Drop for KeyTracker {
+    fn drop(&mut self) {
+        for address in &self.primaries {
+            let _ = self.client.set_tracking(address, None);
+        }
+    }
+}
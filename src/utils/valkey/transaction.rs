@@ -0,0 +1,128 @@
+use crate::errors::Error;
+use crate::utils::valkey::{Cmd, Connection, ValkeyValue};
+
+/// Commands run as one `MULTI`/`EXEC` block. Watched keys and expectations guard the
+/// block against changes by other clients, e.g. since a key editor loaded its value.
+#[derive(Clone, Debug, Default)]
+pub struct Transaction {
+    watch: Vec<Vec<u8>>,
+    expectations: Vec<(Cmd, Vec<u8>)>,
+    commands: Vec<Cmd>,
+}
+
+/// Outcome of a transaction that reached the server.
+#[derive(Debug, PartialEq)]
+pub enum TransactionResult {
+    /// The replies of the queued commands. Errors of single commands do not roll back
+    /// the others, they are part of the replies.
+    Committed(Vec<ValkeyValue>),
+    /// A watched key was modified or an expectation did not hold, nothing was run.
+    Aborted,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aborts the transaction if the key is modified before `EXEC`.
+    pub fn watch(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.watch.push(key.into());
+        self
+    }
+
+    /// Aborts the transaction unless `cmd` replies with `reply` after the keys are
+    /// watched. Aggregate replies are compared by their flattened elements.
+    pub fn expect(mut self, cmd: Cmd, reply: impl Into<Vec<u8>>) -> Self {
+        self.expectations.push((cmd, reply.into()));
+        self
+    }
+
+    pub fn command(mut self, cmd: Cmd) -> Self {
+        self.commands.push(cmd);
+        self
+    }
+
+    pub fn commands(&self) -> &[Cmd] {
+        &self.commands
+    }
+
+    pub fn watched(&self) -> &[Vec<u8>] {
+        &self.watch
+    }
+
+    /// Command the transaction is routed by on a cluster, all keys have to share its slot.
+    pub fn route(&self) -> Option<Cmd> {
+        self.commands.first().cloned().or_else(|| {
+            self.watch
+                .first()
+                .map(|key| Cmd::new("WATCH").arg(key.as_slice()))
+        })
+    }
+
+    /// Runs the transaction on the connection. `MULTI`, the commands and `EXEC` are
+    /// sent in one round trip. The connection is left without watched keys.
+    pub fn run(&self, connection: &mut Connection) -> Result<TransactionResult, Error> {
+        if !self.watch.is_empty() {
+            connection
+                .exec(&Cmd::new("WATCH").args(&self.watch))?
+                .into_result()?;
+        }
+        for (cmd, expected) in &self.expectations {
+            let reply = connection.exec(cmd).and_then(ValkeyValue::into_result);
+            let holds = reply
+                .as_ref()
+                .is_ok_and(|reply| reply.clone().into_bytes_vec() == [expected.clone()]);
+            if !holds {
+                connection.exec(&Cmd::new("UNWATCH"))?;
+                return reply.map(|_| TransactionResult::Aborted);
+            }
+        }
+
+        let mut block = Vec::with_capacity(self.commands.len() + 2);
+        block.push(Cmd::new("MULTI"));
+        block.extend(self.commands.iter().cloned());
+        block.push(Cmd::new("EXEC"));
+        let mut replies = match connection.exec_pipelined(&block) {
+            // Refused before anything was sent, the watched keys would stay on the connection.
+            Err(e @ Error::ReadOnly(_)) => {
+                connection.exec(&Cmd::new("UNWATCH"))?;
+                return Err(e);
+            }
+            result => result?,
+        };
+
+        let exec = replies.pop().unwrap_or(ValkeyValue::Null);
+        // A command rejected while queueing discards the whole block, its error tells more
+        // than the `EXECABORT` of `EXEC`.
+        if let Some(queue_error) = replies.into_iter().find(|reply| reply.is_error()) {
+            queue_error.into_result()?;
+        }
+        match exec.into_result()? {
+            ValkeyValue::Array(replies) => Ok(TransactionResult::Committed(replies)),
+            _ => Ok(TransactionResult::Aborted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        let transaction = Transaction::new()
+            .watch("a")
+            .command(Cmd::new("SET").arg("b").arg(1));
+        assert_eq!(
+            transaction.route().map(|cmd| cmd.name()),
+            Some("SET".to_string())
+        );
+        let transaction = Transaction::new().watch("a");
+        assert_eq!(
+            transaction.route().map(|cmd| cmd.arguments().to_vec()),
+            Some(vec![b"WATCH".to_vec(), b"a".to_vec()])
+        );
+        assert!(Transaction::new().route().is_none());
+    }
+}
@@ -1,41 +1,44 @@
-use crate::utils::valkey::{Len, ToResp, ToVec, find_crlf};
+use crate::errors::Error;
+use crate::utils::display_bytes;
+use crate::utils::valkey::resp_decoder::RespDecoder;
+use crate::utils::valkey::{ToResp, ToVec};
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::{Hash, Hasher};
 
 #[derive(Debug, Clone)]
-pub enum ValkeyValue<'a> {
-    SimpleString(&'a str),
-    SimpleError(&'a str),
+pub enum ValkeyValue {
+    SimpleString(String),
+    SimpleError(String),
     Integer(i64),
     BulkString(Vec<u8>),
-    Array(Vec<ValkeyValue<'a>>),
+    Array(Vec<ValkeyValue>),
 
     Null,
     Boolean(bool),
     Double(f64),
-    BigNumber(&'a str),
+    BigNumber(String),
     BulkErrors(Vec<u8>),
-    VerbatimString { format: &'a str, data: &'a str },
-    Maps(HashMap<ValkeyValue<'a>, ValkeyValue<'a>>),
-    Sets(Vec<ValkeyValue<'a>>),
-    Pushes(Vec<ValkeyValue<'a>>),
+    VerbatimString { format: String, data: Vec<u8> },
+    Maps(HashMap<ValkeyValue, ValkeyValue>),
+    Sets(Vec<ValkeyValue>),
+    Pushes(Vec<ValkeyValue>),
 }
 
-impl PartialEq for ValkeyValue<'_> {
+impl PartialEq for ValkeyValue {
     fn eq(&self, other: &Self) -> bool {
         use ValkeyValue::*;
         match (self, other) {
-            (SimpleString(a), SimpleString(b)) => **a == **b,
-            (SimpleError(a), SimpleError(b)) => **a == **b,
-            (Integer(a), Integer(b)) => *a == *b,
-            (BulkString(a), BulkString(b)) => *a == *b,
-            (Array(a), Array(b)) => *a == *b,
+            (SimpleString(a), SimpleString(b)) => a == b,
+            (SimpleError(a), SimpleError(b)) => a == b,
+            (Integer(a), Integer(b)) => a == b,
+            (BulkString(a), BulkString(b)) => a == b,
+            (Array(a), Array(b)) => a == b,
             (Null, Null) => true,
-            (Boolean(a), Boolean(b)) => *a == *b,
-            (Double(a), Double(b)) => *a == *b,
-            (BigNumber(a), BigNumber(b)) => **a == **b,
-            (BulkErrors(a), BulkErrors(b)) => **a == **b,
+            (Boolean(a), Boolean(b)) => a == b,
+            (Double(a), Double(b)) => a == b,
+            (BigNumber(a), BigNumber(b)) => a == b,
+            (BulkErrors(a), BulkErrors(b)) => a == b,
             (
                 VerbatimString {
                     format: fmt_a,
@@ -45,18 +48,18 @@ impl PartialEq for ValkeyValue<'_> {
                     format: fmt_b,
                     data: data_b,
                 },
-            ) => **fmt_a == **fmt_b && **data_a == **data_b,
-            (Maps(_), Maps(_)) => true,
-            (Sets(a), Sets(b)) => *a == *b,
-            (Pushes(a), Pushes(b)) => *a == *b,
+            ) => fmt_a == fmt_b && data_a == data_b,
+            (Maps(a), Maps(b)) => a == b,
+            (Sets(a), Sets(b)) => a == b,
+            (Pushes(a), Pushes(b)) => a == b,
             _ => false,
         }
     }
 }
 
-impl Eq for ValkeyValue<'_> {}
+impl Eq for ValkeyValue {}
 
-impl Hash for ValkeyValue<'_> {
+impl Hash for ValkeyValue {
     fn hash<H: Hasher>(&self, state: &mut H) {
         use ValkeyValue::*;
         std::mem::discriminant(self).hash(state);
@@ -106,7 +109,7 @@ impl Hash for ValkeyValue<'_> {
     }
 }
 
-impl ToVec for ValkeyValue<'_> {
+impl ToVec for ValkeyValue {
     fn to_vec(&self) -> Vec<String> {
         if let ValkeyValue::Array(v) | ValkeyValue::Sets(v) | ValkeyValue::Pushes(v) = self {
             v.iter().flat_map(|v| v.to_vec()).collect()
@@ -120,13 +123,13 @@ impl ToVec for ValkeyValue<'_> {
     }
 }
 
-impl ToResp for ValkeyValue<'_> {
+impl ToResp for ValkeyValue {
     fn to_resp(&self) -> String {
         use ValkeyValue::*;
         match self {
-            SimpleString(s) => format!("+{}\r\n", *s),
-            SimpleError(s) => format!("-{}\r\n", *s),
-            Integer(i) => format!(":{}\r\n", *i),
+            SimpleString(s) => format!("+{s}\r\n"),
+            SimpleError(s) => format!("-{s}\r\n"),
+            Integer(i) => format!(":{i}\r\n"),
             BulkString(v) => format!("${}\r\n{}\r\n", v.len(), String::from_utf8_lossy(v)),
             Array(v) => {
                 let mut s = String::new();
@@ -140,20 +143,20 @@ impl ToResp for ValkeyValue<'_> {
             }
             Null => "$-1\r\n".to_string(),
             Boolean(b) => format!("#{}\r\n", if *b { "t" } else { "f" }),
-            Double(d) => format!(",{}\r\n", *d),
-            BigNumber(s) => format!("({}\r\n", *s),
+            Double(d) => format!(",{d}\r\n"),
+            BigNumber(s) => format!("({s}\r\n"),
             BulkErrors(e) => format!("!{}\r\n{}\r\n", e.len(), String::from_utf8_lossy(e)),
             VerbatimString { format, data } => format!(
                 "={}\r\n{}:{}\r\n",
                 format.len() + data.len() + 1,
-                *format,
-                BulkString(data.as_bytes().to_vec())
+                format,
+                String::from_utf8_lossy(data)
             ),
             Maps(m) => {
                 let mut s = String::new();
                 for (k, v) in m.iter() {
-                    s.push_str(&v.to_resp());
                     s.push_str(&k.to_resp());
+                    s.push_str(&v.to_resp());
                 }
                 format!("%{}\r\n{}", m.len(), s)
             }
@@ -177,309 +180,124 @@ impl ToResp for ValkeyValue<'_> {
     }
 }
 
-impl ValkeyValue<'_> {
-    pub fn parse_value<'b, I>(lines: &mut I) -> ValkeyValue<'b>
-    where
-        I: Iterator<Item = &'b str>,
-    {
-        use ValkeyValue::*;
-        let header = match lines.next() {
-            Some(l) => l,
-            None => return Null,
-        };
-
-        let mut chars = header.chars();
-        let first_char = chars.next().unwrap_or('_');
+impl ValkeyValue {
+    pub fn parse_from_bytes(data: &[u8]) -> Result<(ValkeyValue, usize), &'static str> {
+        let mut decoder = RespDecoder::new();
+        decoder.feed(data);
+        match decoder.next_value()? {
+            Some(value) => Ok((value, decoder.consumed())),
+            None => Err("Incomplete data"),
+        }
+    }
 
-        match first_char {
-            '+' => {
-                let content = header.trim_start_matches('+').trim_end();
-                SimpleString(content)
-            }
-            '-' => {
-                let content = header.trim_start_matches('-').trim_end();
-                SimpleError(content)
-            }
-            ':' => {
-                let int_str = header.trim_start_matches(':').trim();
-                match int_str.parse::<i64>() {
-                    Ok(num) => Integer(num),
-                    Err(_) => Null,
-                }
-            }
-            '$' => {
-                let length_str = header.trim_start_matches('$').trim();
-                if let Ok(length) = length_str.parse::<i64>() {
-                    if length < 0 {
-                        Null
-                    } else if let Some(data_line) = lines.next() {
-                        let data = data_line.as_bytes().to_vec();
-                        BulkString(data)
-                    } else {
-                        Null
-                    }
-                } else {
-                    Null
-                }
-            }
-            '*' => {
-                let count_str = header.trim_start_matches('*').trim();
-                let count: i64 = count_str.parse::<i64>().unwrap_or(-1);
-                if count < 0 {
-                    return Null;
-                }
-                let mut elements = Vec::with_capacity(count as usize);
-                for _ in 0..count {
-                    let elem = ValkeyValue::parse_value(lines);
-                    elements.push(elem);
-                }
-                Array(elements)
-            }
-            '#' => {
-                let bool_str = header.trim_start_matches('#').trim();
-                Boolean(bool_str.eq_ignore_ascii_case("t"))
-            }
-            ',' => {
-                let double_str = header.trim_start_matches(',').trim();
-                match double_str.parse::<f64>() {
-                    Ok(num) => Double(num),
-                    Err(_) => Null,
-                }
-            }
-            '(' => {
-                let big_int_str = header.trim_start_matches('(').trim();
-                BigNumber(big_int_str)
-            }
-            '!' => {
-                let length_str = header.trim_start_matches('!').trim();
-                if let Ok(length) = length_str.parse::<i64>() {
-                    if length < 0 {
-                        SimpleError("Error")
-                    } else if let Some(data_line) = lines.next() {
-                        let data = data_line.as_bytes().to_vec();
-                        BulkErrors(data)
-                    } else {
-                        SimpleError("Error")
-                    }
-                } else {
-                    SimpleError("Error")
-                }
-            }
-            '=' => {
-                let length_str = header.trim_start_matches('=').trim();
-                if let Ok(length) = length_str.parse::<i64>() {
-                    if length < 0 {
-                        Null
-                    } else if let Some(data_line) = lines.next() {
-                        let data: Vec<_> = data_line.split(':').collect();
-                        VerbatimString {
-                            format: data.first().unwrap_or(&""),
-                            data: data.last().unwrap_or(&""),
-                        }
-                    } else {
-                        Null
-                    }
-                } else {
-                    Null
-                }
-            }
-            '%' => {
-                let len_str = header.trim_start_matches('%').trim();
-                let len = len_str.parse::<usize>().unwrap_or(0);
-                let mut map = HashMap::with_capacity(len);
-                for _ in 0..len {
-                    let key = Self::parse_value(lines);
-                    let value = Self::parse_value(lines);
-                    map.insert(key, value);
-                }
-                Maps(map)
-            }
-            '~' => {
-                let len_str = header.trim_start_matches('~').trim();
-                let len = len_str.parse::<usize>().unwrap_or(0);
-                let mut items = Vec::with_capacity(len);
-                for _ in 0..len {
-                    let elem = Self::parse_value(lines);
-                    items.push(elem);
-                }
-                items.dedup();
-                Sets(items)
-            }
-            '>' => {
-                let len_str = header.trim_start_matches('>').trim();
-                let len = len_str.parse::<usize>().unwrap_or(0);
-                let mut items = Vec::with_capacity(len);
-                for _ in 0..len {
-                    let elem = Self::parse_value(lines);
-                    items.push(elem);
-                }
-                Pushes(items)
-            }
-            _ => Null,
+    pub fn parse_all_values(input: &[u8]) -> Vec<ValkeyValue> {
+        let mut decoder = RespDecoder::new();
+        decoder.feed(input);
+        let mut values = Vec::new();
+        while let Ok(Some(value)) = decoder.next_value() {
+            values.push(value);
         }
+        values
     }
 
-    pub fn parse_from_bytes(data: &[u8]) -> Result<(ValkeyValue<'_>, usize), &'static str> {
-        Self::parse_value_from_bytes(data, 0)
+    /// Raw payload of string-like replies, without any UTF-8 conversion.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        use ValkeyValue::*;
+        match self {
+            SimpleString(s) | SimpleError(s) | BigNumber(s) => Some(s.as_bytes()),
+            BulkString(v) | BulkErrors(v) => Some(v),
+            VerbatimString { data, .. } => Some(data),
+            _ => None,
+        }
     }
 
-    fn parse_value_from_bytes(
-        data: &[u8],
-        start: usize,
-    ) -> Result<(ValkeyValue<'_>, usize), &'static str> {
-        if start >= data.len() {
-            return Err("Incomplete data");
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            ValkeyValue::Integer(i) => Some(*i),
+            ValkeyValue::Double(d) => Some(*d as i64),
+            _ => std::str::from_utf8(self.as_bytes()?).ok()?.parse().ok(),
         }
+    }
 
-        let first_byte = data[start];
-
-        match first_byte {
-            b'+' => {
-                // Simple string
-                if let Some(end_pos) = find_crlf(data, start) {
-                    let content = std::str::from_utf8(&data[start + 1..end_pos])
-                        .map_err(|_| "Invalid UTF-8 in simple string")?;
-                    Ok((ValkeyValue::SimpleString(content), end_pos + 2))
-                } else {
-                    Err("Incomplete simple string")
-                }
-            }
-            b'-' => {
-                // Simple error
-                if let Some(end_pos) = find_crlf(data, start) {
-                    let content = std::str::from_utf8(&data[start + 1..end_pos])
-                        .map_err(|_| "Invalid UTF-8 in simple error")?;
-                    Ok((ValkeyValue::SimpleError(content), end_pos + 2))
-                } else {
-                    Err("Incomplete simple error")
-                }
-            }
-            b':' => {
-                // Integer
-                if let Some(end_pos) = find_crlf(data, start) {
-                    let int_str = std::str::from_utf8(&data[start + 1..end_pos])
-                        .map_err(|_| "Invalid UTF-8 in integer")?;
-                    let num = int_str.parse::<i64>().map_err(|_| "Invalid integer")?;
-                    Ok((ValkeyValue::Integer(num), end_pos + 2))
-                } else {
-                    Err("Incomplete integer")
-                }
-            }
-            b'$' => {
-                // Bulk string
-                if let Some(header_end) = find_crlf(data, start) {
-                    let length_str = std::str::from_utf8(&data[start + 1..header_end])
-                        .map_err(|_| "Invalid UTF-8 in bulk string header")?;
-                    let length: i64 = length_str
-                        .parse()
-                        .map_err(|_| "Invalid bulk string length")?;
-
-                    if length < 0 {
-                        Ok((ValkeyValue::Null, header_end + 2))
-                    } else {
-                        let data_start = header_end + 2;
-                        let data_end = data_start + length as usize;
-
-                        if data_end + 2 <= data.len()
-                            && data[data_end] == b'\r'
-                            && data[data_end + 1] == b'\n'
-                        {
-                            let bulk_data = data[data_start..data_end].to_vec();
-                            Ok((ValkeyValue::BulkString(bulk_data), data_end + 2))
-                        } else {
-                            Err("Incomplete bulk string data")
-                        }
-                    }
-                } else {
-                    Err("Incomplete bulk string header")
-                }
-            }
-            b'*' => {
-                // Array
-                if let Some(header_end) = find_crlf(data, start) {
-                    let count_str = std::str::from_utf8(&data[start + 1..header_end])
-                        .map_err(|_| "Invalid UTF-8 in array header")?;
-                    let count: i64 = count_str.parse().map_err(|_| "Invalid array count")?;
-
-                    if count < 0 {
-                        Ok((ValkeyValue::Null, header_end + 2))
-                    } else {
-                        let mut elements = Vec::with_capacity(count as usize);
-                        let mut pos = header_end + 2;
-
-                        for _ in 0..count {
-                            let (element, new_pos) = Self::parse_value_from_bytes(data, pos)?;
-                            elements.push(element);
-                            pos = new_pos;
-                        }
-
-                        Ok((ValkeyValue::Array(elements), pos))
-                    }
-                } else {
-                    Err("Incomplete array header")
-                }
+    pub fn is_null(&self) -> bool {
+        matches!(self, ValkeyValue::Null)
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            ValkeyValue::SimpleError(_) | ValkeyValue::BulkErrors(_)
+        )
+    }
+
+    /// Turns an error reply into [`Error::Server`], other replies are passed through.
+    pub fn into_result(self) -> Result<ValkeyValue, Error> {
+        match self {
+            ValkeyValue::SimpleError(message) => Err(Error::server(message)),
+            ValkeyValue::BulkErrors(message) => {
+                Err(Error::server(String::from_utf8_lossy(&message)))
             }
-            _ => Err("Unknown RESP type"),
+            value => Ok(value),
         }
     }
-}
 
-impl<'a> From<&'a str> for ValkeyValue<'a> {
-    fn from(value: &'a str) -> Self {
-        match Self::parse_from_bytes(value.as_bytes()) {
-            Ok((parsed_value, _)) => parsed_value,
-            Err(_) => {
-                // Fallback to line-based parsing for backward compatibility
-                let mut lines = value.lines();
-                Self::parse_value(&mut lines)
-            }
+    /// Flattens aggregates into their raw elements, keeping binary payloads intact.
+    pub fn into_bytes_vec(self) -> Vec<Vec<u8>> {
+        use ValkeyValue::*;
+        match self {
+            Array(v) | Sets(v) | Pushes(v) => {
+                v.into_iter().flat_map(|v| v.into_bytes_vec()).collect()
+            }
+            Maps(m) => m
+                .into_iter()
+                .flat_map(|(k, v)| {
+                    let mut pair = k.into_bytes_vec();
+                    pair.extend(v.into_bytes_vec());
+                    pair
+                })
+                .collect(),
+            BulkString(v) | BulkErrors(v) | VerbatimString { data: v, .. } => vec![v],
+            Null => vec![],
+            other => vec![other.to_string().into_bytes()],
         }
     }
 }
 
-/*
-impl<'a> From<&'a str> for ValkeyValue<'a> {
-    fn from(value: &'a str) -> Self {
-        let mut lines = value.lines();
-        Self::parse_value(&mut lines)
+impl From<&[u8]> for ValkeyValue {
+    fn from(value: &[u8]) -> Self {
+        match Self::parse_from_bytes(value) {
+            Ok((parsed_value, _)) => parsed_value,
+            Err(_) => ValkeyValue::Null,
+        }
     }
 }
- */
 
-impl<'a> From<&'a String> for ValkeyValue<'a> {
-    fn from(value: &'a String) -> Self {
-        let mut lines = value.lines();
-        Self::parse_value(&mut lines)
+impl From<&str> for ValkeyValue {
+    fn from(value: &str) -> Self {
+        Self::from(value.as_bytes())
     }
 }
 
-impl ValkeyValue<'_> {
-    pub fn parse_all_values(input: &str) -> Vec<ValkeyValue<'_>> {
-        let mut lines = input.lines().peekable();
-        let mut values = Vec::new();
-
-        while lines.peek().is_some() {
-            let value = Self::parse_value(&mut lines);
-            values.push(value);
-        }
-
-        values
+impl From<&String> for ValkeyValue {
+    fn from(value: &String) -> Self {
+        Self::from(value.as_bytes())
     }
+}
 
-    pub fn parse_complete(input: &str) -> ValkeyValue<'_> {
-        let mut lines = input.lines();
-        Self::parse_value(&mut lines)
+impl From<&Vec<u8>> for ValkeyValue {
+    fn from(value: &Vec<u8>) -> Self {
+        Self::from(value.as_slice())
     }
 }
 
-impl Display for ValkeyValue<'_> {
+impl Display for ValkeyValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use ValkeyValue::*;
         match self {
-            SimpleString(s) => write!(f, "{}", *s),
-            SimpleError(s) => write!(f, "{}", *s),
-            Integer(i) => write!(f, "{}", *i),
-            BulkString(v) => write!(f, "{}", String::from_utf8_lossy(v)),
+            SimpleString(s) => write!(f, "{s}"),
+            SimpleError(s) => write!(f, "{s}"),
+            Integer(i) => write!(f, "{i}"),
+            BulkString(v) => write!(f, "{}", display_bytes(v)),
             Array(v) => {
                 let mut s = String::new();
                 for item in v {
@@ -488,10 +306,10 @@ impl Display for ValkeyValue<'_> {
                 write!(f, "{s}")
             }
             Boolean(b) => write!(f, "{}", if *b { "true" } else { "false" }),
-            Double(d) => write!(f, "{}", *d),
-            BigNumber(s) => write!(f, "{}", *s),
-            BulkErrors(e) => write!(f, "{}", String::from_utf8_lossy(e)),
-            VerbatimString { format, data } => write!(f, "{}:{}", *format, *data),
+            Double(d) => write!(f, "{d}"),
+            BigNumber(s) => write!(f, "{s}"),
+            BulkErrors(e) => write!(f, "{}", display_bytes(e)),
+            VerbatimString { format, data } => write!(f, "{format}:{}", display_bytes(data)),
             Maps(m) => {
                 let mut s = String::new();
                 for (k, v) in m {
@@ -523,35 +341,16 @@ impl Display for ValkeyValue<'_> {
     }
 }
 
-impl Len for ValkeyValue<'_> {
-    fn len(&self) -> usize {
-        use ValkeyValue::*;
-        match self {
-            SimpleString(_)
-            | SimpleError(_)
-            | Integer(_)
-            | BulkString(_)
-            | Null
-            | Boolean(_)
-            | Double(_)
-            | BigNumber(_)
-            | BulkErrors(_)
-            | VerbatimString { .. } => 1,
-            Array(v) | Sets(v) | Pushes(v) => v.len(),
-            Maps(m) => m.len(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::errors::ServerErrorKind;
     use ValkeyValue::*;
 
     #[test]
     fn test_simple_string() {
         let input = "+OK\r\n";
-        let expected = SimpleString("OK");
+        let expected = SimpleString("OK".into());
         let result = ValkeyValue::from(input);
         let output = result.to_resp();
         assert_eq!(result, expected);
@@ -561,7 +360,7 @@ mod tests {
     #[test]
     fn test_simple_error() {
         let input = "-Error message\r\n";
-        let expected = SimpleError("Error message");
+        let expected = SimpleError("Error message".into());
         let result = ValkeyValue::from(input);
         let output = result.to_resp();
         assert_eq!(result, expected);
@@ -611,7 +410,10 @@ mod tests {
     #[test]
     fn test_simple_string_array() {
         let input = "*2\r\n+hello\r\n+world\r\n";
-        let expected = Array(vec![SimpleString("hello"), SimpleString("world")]);
+        let expected = Array(vec![
+            SimpleString("hello".into()),
+            SimpleString("world".into()),
+        ]);
         let result = ValkeyValue::from(input);
         let output = result.to_resp();
         assert_eq!(result, expected);
@@ -662,7 +464,10 @@ mod tests {
         let input = "*2\r\n*3\r\n:1\r\n:2\r\n:3\r\n*2\r\n+Hello\r\n-World\r\n";
         let expected = Array(vec![
             Array(vec![Integer(1), Integer(2), Integer(3)]),
-            Array(vec![SimpleString("Hello"), SimpleError("World")]),
+            Array(vec![
+                SimpleString("Hello".into()),
+                SimpleError("World".into()),
+            ]),
         ]);
         let result = ValkeyValue::from(input);
         let output = result.to_resp();
@@ -785,7 +590,7 @@ mod tests {
     #[test]
     fn test_big_numbers() {
         let input = "(3492890328409238509324850943850943825024385\r\n";
-        let expected = BigNumber("3492890328409238509324850943850943825024385");
+        let expected = BigNumber("3492890328409238509324850943850943825024385".into());
         let result = ValkeyValue::from(input);
         let output = result.to_resp();
         assert_eq!(result, expected);
@@ -806,8 +611,8 @@ mod tests {
     fn test_verbatim_strings() {
         let input = "=15\r\ntxt:Some string\r\n";
         let expected = VerbatimString {
-            format: "txt",
-            data: "Some string",
+            format: "txt".into(),
+            data: b"Some string".to_vec(),
         };
         let result = ValkeyValue::from(input);
         let output = result.to_resp();
@@ -819,8 +624,8 @@ mod tests {
     fn test_maps() {
         let input = "%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n";
         let expected = Maps(HashMap::from([
-            (SimpleString("first"), Integer(1)),
-            (SimpleString("second"), Integer(2)),
+            (SimpleString("first".into()), Integer(1)),
+            (SimpleString("second".into()), Integer(2)),
         ]));
         let result = ValkeyValue::from(input);
         let output = result.to_resp();
@@ -877,4 +682,62 @@ mod tests {
         assert_eq!(result, expected);
         assert_eq!(expected.to_resp(), result.to_resp());
     }
+
+    #[test]
+    fn test_binary_bulk_string() {
+        let input = b"$6\r\n\x00\xff\r\n\x80a\r\n";
+        let (result, consumed) = ValkeyValue::parse_from_bytes(input).unwrap();
+        assert_eq!(result, BulkString(b"\x00\xff\r\n\x80a".to_vec()));
+        assert_eq!(consumed, input.len());
+        assert_eq!(result.as_bytes(), Some(&b"\x00\xff\r\n\x80a"[..]));
+    }
+
+    #[test]
+    fn test_incomplete_input() {
+        assert!(ValkeyValue::parse_from_bytes(b"$5\r\nhel").is_err());
+        assert!(ValkeyValue::parse_from_bytes(b"*2\r\n:1\r\n").is_err());
+        assert!(ValkeyValue::parse_from_bytes(b"+OK").is_err());
+    }
+
+    #[test]
+    fn test_parse_all_values() {
+        let input = b"+OK\r\n$3\r\n\xfe\x00\xfd\r\n:7\r\n";
+        let values = ValkeyValue::parse_all_values(input);
+        assert_eq!(
+            values,
+            vec![
+                SimpleString("OK".into()),
+                BulkString(vec![0xfe, 0x00, 0xfd]),
+                Integer(7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_into_bytes_vec() {
+        let input = b"*3\r\n$2\r\n\xc3\x28\r\n$-1\r\n:5\r\n";
+        let result = ValkeyValue::from(&input[..]);
+        assert_eq!(
+            result.into_bytes_vec(),
+            vec![vec![0xc3, 0x28], b"5".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_into_result() {
+        assert_eq!(Integer(1).into_result().ok(), Some(Integer(1)));
+        let error = ValkeyValue::from(&b"-NOPERM User bob has no permissions\r\n"[..]);
+        assert!(matches!(
+            error.into_result(),
+            Err(Error::Server {
+                kind: ServerErrorKind::NoPerm,
+                ..
+            })
+        ));
+        let error = BulkErrors(b"OOM command not allowed".to_vec());
+        assert_eq!(
+            error.into_result().err().map(|e| e.to_string()),
+            Some("OOM command not allowed".to_string())
+        );
+    }
 }
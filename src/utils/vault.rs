@@ -0,0 +1,284 @@
+use crate::errors::Error;
+use crate::i18n::{I18N, LangKey};
+use crate::utils::{ConnectionProfile, write_atomic};
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const VAULT_FILE_NAME: &str = "secrets.json";
+const VAULT_VERSION: u32 = 1;
+/// PBKDF2-HMAC-SHA256 rounds for new vaults, stored in the file so it can be raised later.
+const KDF_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+
+/// Whether the passwords of the profiles are kept in the vault.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultState {
+    Disabled,
+    Locked,
+    Unlocked,
+}
+
+/// Passwords of a profile, kept out of the profile file while the vault is enabled.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Credentials {
+    pub password: Option<String>,
+    pub proxy_password: Option<String>,
+    pub ssh_password: Option<String>,
+}
+
+impl Credentials {
+    /// Moves the passwords out of the profile.
+    pub fn take(profile: &mut ConnectionProfile) -> Self {
+        Self {
+            password: profile.password.take(),
+            proxy_password: profile
+                .proxy
+                .as_mut()
+                .and_then(|proxy| proxy.password.take()),
+            ssh_password: profile.ssh.as_mut().and_then(|ssh| ssh.password.take()),
+        }
+    }
+
+    /// Puts the passwords back into the profile.
+    pub fn apply(&self, profile: &mut ConnectionProfile) {
+        if self.password.is_some() {
+            profile.password = self.password.clone();
+        }
+        if let Some(proxy) = profile.proxy.as_mut()
+            && self.proxy_password.is_some()
+        {
+            proxy.password = self.proxy_password.clone();
+        }
+        if let Some(ssh) = profile.ssh.as_mut()
+            && self.ssh_password.is_some()
+        {
+            ssh.password = self.ssh_password.clone();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.password.is_none() && self.proxy_password.is_none() && self.ssh_password.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    iterations: u32,
+    /// Hex encoded like the nonce and the ciphertext.
+    salt: String,
+    nonce: String,
+    /// The credentials by alias as JSON, sealed with ChaCha20-Poly1305.
+    ciphertext: String,
+}
+
+/// An unlocked credential vault. The credentials are encrypted at rest with a key derived
+/// from the master password, the key only lives in memory until the vault is dropped.
+pub struct Vault {
+    path: PathBuf,
+    salt: Vec<u8>,
+    iterations: NonZeroU32,
+    key: LessSafeKey,
+    credentials: HashMap<String, Credentials>,
+    i18n: Arc<I18N>,
+}
+
+impl Vault {
+    /// Creates an empty vault file at `path`, an existing one is replaced.
+    pub fn create(path: &Path, master_password: &str, i18n: Arc<I18N>) -> Result<Self, Error> {
+        Self::create_with(path, master_password, KDF_ITERATIONS, i18n)
+    }
+
+    fn create_with(
+        path: &Path,
+        master_password: &str,
+        iterations: u32,
+        i18n: Arc<I18N>,
+    ) -> Result<Self, Error> {
+        if master_password.is_empty() {
+            return Err(Error::InvalidInput(i18n.get(LangKey::MasterPasswordEmpty)));
+        }
+        let mut salt = vec![0; SALT_LEN];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| Error::Critical(i18n.get(LangKey::NoSecureRandom)))?;
+        let iterations = NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN);
+        let vault = Self {
+            path: path.to_path_buf(),
+            key: derive_key(master_password, &salt, iterations, &i18n)?,
+            salt,
+            iterations,
+            credentials: HashMap::new(),
+            i18n,
+        };
+        vault.save()?;
+        Ok(vault)
+    }
+
+    /// Opens the vault file at `path`. Fails for a wrong master password.
+    pub fn unlock(path: &Path, master_password: &str, i18n: Arc<I18N>) -> Result<Self, Error> {
+        let file: VaultFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if file.version > VAULT_VERSION {
+            return Err(Error::InvalidInput(format!(
+                "{} ({} > {VAULT_VERSION})",
+                i18n.get(LangKey::VaultFromNewerVersion),
+                file.version
+            )));
+        }
+        let invalid = || Error::InvalidInput(i18n.get(LangKey::VaultDamaged));
+        let salt = hex_decode(&file.salt).ok_or_else(invalid)?;
+        let nonce = hex_decode(&file.nonce).ok_or_else(invalid)?;
+        let mut sealed = hex_decode(&file.ciphertext).ok_or_else(invalid)?;
+        let nonce = Nonce::try_assume_unique_for_key(&nonce).map_err(|_| invalid())?;
+        let iterations = NonZeroU32::new(file.iterations).ok_or_else(invalid)?;
+
+        let key = derive_key(master_password, &salt, iterations, &i18n)?;
+        let plaintext = key
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| Error::InvalidInput(i18n.get(LangKey::WrongMasterPassword)))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            salt,
+            iterations,
+            key,
+            credentials: serde_json::from_slice(plaintext)?,
+            i18n,
+        })
+    }
+
+    pub fn get(&self, alias: &str) -> Option<&Credentials> {
+        self.credentials.get(alias)
+    }
+
+    /// Stores the credentials of a profile, empty ones remove its entry.
+    pub fn set(&mut self, alias: &str, credentials: Credentials) -> Result<(), Error> {
+        if credentials.is_empty() {
+            self.credentials.remove(alias);
+        } else {
+            self.credentials.insert(alias.to_string(), credentials);
+        }
+        self.save()
+    }
+
+    pub fn remove(&mut self, alias: &str) -> Result<(), Error> {
+        if self.credentials.remove(alias).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the vault file, the credentials in memory stay until the vault is dropped.
+    pub fn delete(self) -> Result<HashMap<String, Credentials>, Error> {
+        fs::remove_file(&self.path)?;
+        Ok(self.credentials)
+    }
+
+    /// Encrypts the credentials with a fresh nonce and writes the file.
+    fn save(&self) -> Result<(), Error> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| Error::Critical(self.i18n.get(LangKey::NoSecureRandom)))?;
+        let mut sealed = serde_json::to_vec(&self.credentials)?;
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .map_err(|_| Error::Critical(self.i18n.get(LangKey::CannotEncryptCredentials)))?;
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            iterations: self.iterations.get(),
+            salt: hex_encode(&self.salt),
+            nonce: hex_encode(&nonce),
+            ciphertext: hex_encode(&sealed),
+        };
+        write_atomic(&self.path, serde_json::to_string_pretty(&file)?.as_bytes())
+    }
+}
+
+fn derive_key(
+    master_password: &str,
+    salt: &[u8],
+    iterations: NonZeroU32,
+    i18n: &I18N,
+) -> Result<LessSafeKey, Error> {
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        master_password.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&CHACHA20_POLY1305, &key)
+        .map_err(|_| Error::Critical(i18n.get(LangKey::CannotCreateVaultKey)))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ValkeyUrl;
+
+    #[test]
+    fn test_vault_round_trip() {
+        let dir = std::env::temp_dir().join(format!("vk_commander_vault_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(VAULT_FILE_NAME);
+        let mut vault = Vault::create_with(&path, "correct horse", 1_000, Arc::default()).unwrap();
+        let url = "valkey://:s3cret@h:6379?proxy=socks5%3A//u%3Apw%40proxy%3A1080";
+        let mut profile = ConnectionProfile::new("prod", &ValkeyUrl::from(url));
+        let credentials = Credentials::take(&mut profile);
+        assert_eq!(credentials.password.as_deref(), Some("s3cret"));
+        assert_eq!(credentials.proxy_password.as_deref(), Some("pw"));
+        assert!(profile.password.is_none());
+        assert!(profile.proxy.as_ref().unwrap().password.is_none());
+        vault.set("prod", credentials.clone()).unwrap();
+
+        let file = fs::read_to_string(&path).unwrap();
+        assert!(!file.contains("s3cret"));
+        assert!(Vault::unlock(&path, "wrong", Arc::default()).is_err());
+
+        let vault = Vault::unlock(&path, "correct horse", Arc::default()).unwrap();
+        vault.get("prod").unwrap().apply(&mut profile);
+        assert_eq!(
+            profile.connection_string(),
+            ValkeyUrl::from(url).connection_string()
+        );
+        assert_eq!(vault.delete().unwrap().get("prod"), Some(&credentials));
+        assert!(!fs::exists(&path).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex_encode(&[0, 15, 255]), "000fff");
+        assert_eq!(hex_decode("000fff"), Some(vec![0, 15, 255]));
+        assert_eq!(hex_decode("0f0"), None);
+        assert_eq!(hex_decode("zz"), None);
+    }
+}